@@ -1,10 +1,45 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, IntoVal, Symbol, Vec};
+use dex_types::{PoolConfig, PoolDetails, PoolMeta, PoolPage, PoolState, PoolSummary};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, BytesN,
+    Env, Executable, IntoVal, Symbol, TryFromVal, Val, Vec,
+};
 
 #[contract]
 pub struct DexFactory;
 
+/// Factory error codes
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FactoryError {
+    /// initialize called twice
+    AlreadyInitialized = 1,
+    /// Factory used before initialize
+    NotInitialized = 2,
+    /// Both pool tokens are the same address
+    IdenticalTokens = 3,
+    /// A pool already exists for this pair and fee
+    PoolExists = 4,
+    /// Fee tier has not been enabled
+    FeeNotEnabled = 5,
+    /// Tick spacing outside (0, 16384]
+    InvalidTickSpacing = 6,
+    /// Fee at or above 100%
+    FeeTooHigh = 7,
+    /// Fee tier is already enabled
+    FeeAlreadyEnabled = 8,
+    /// Address does not implement the token interface
+    InvalidToken = 9,
+    /// Batch is empty or larger than MAX_POOLS_PER_BATCH
+    InvalidBatchSize = 10,
+    /// Fee tier in use: a pool was already created at this fee
+    FeeTierInUse = 11,
+    /// Fee recipient has not been set
+    FeeRecipientNotSet = 12,
+}
+
 /// Storage keys for Factory contract
 #[contracttype]
 #[derive(Clone)]
@@ -25,6 +60,44 @@ pub enum DataKey {
     FeeRecipient,
     /// Protocol fee percentage (basis points)
     ProtocolFee,
+    /// Fee tier -> number of pools created at that tier
+    PoolCountByFee(u32),
+    /// (fee, index) -> pool address (per-tier indexed storage)
+    PoolByFeeAt(u32, u32),
+    /// Pool address -> cached token metadata
+    PoolTokens(Address),
+    /// Pool address -> (token0, token1, fee), marks factory-deployed pools
+    PoolAddress(Address),
+    /// Canonical router address
+    Router,
+    /// Canonical quoter address
+    Quoter,
+    /// Canonical position manager address
+    PositionManager,
+    /// Pool index -> creation metadata
+    PoolMeta(u32),
+    /// Kill switch: when true, every pool rejects swaps
+    GlobalPause,
+}
+
+/// Token metadata cached when a pool is created
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolTokenInfo {
+    pub token0: Address,
+    pub token1: Address,
+    pub decimals0: u32,
+    pub decimals1: u32,
+}
+
+/// One entry of a create_pools batch
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CreatePoolRequest {
+    pub token_a: Address,
+    pub token_b: Address,
+    pub fee: u32,
+    pub initial_sqrt_price_x96: u128,
 }
 
 // TTL constants
@@ -33,6 +106,17 @@ const INSTANCE_TTL_EXTEND: u32 = 518400;
 const PERSISTENT_TTL_THRESHOLD: u32 = 17280;
 const PERSISTENT_TTL_EXTEND: u32 = 518400;
 
+/// Maximum pools returned by one paginated query
+const MAX_POOLS_PER_PAGE: u32 = 50;
+
+/// Maximum summaries per get_pools_with_state_paginated call
+/// Each item costs a registry read plus two cross-contract pool reads
+const MAX_SUMMARIES_PER_PAGE: u32 = 10;
+
+/// Maximum pools deployed by one create_pools call (each pool writes ~7
+/// entries: instance, pair key, PoolAt, PoolByFeeAt, PoolTokens, ...)
+const MAX_POOLS_PER_BATCH: u32 = 5;
+
 // ============================================================================
 // SOROBAN RESOURCE LIMITS - Important constraints to be aware of:
 // ============================================================================
@@ -49,6 +133,8 @@ const PERSISTENT_TTL_EXTEND: u32 = 518400;
 //   to avoid a single unbounded ledger entry
 // - Each pool address is stored separately (~56 bytes each)
 // - Pagination is provided for querying pools
+// - create_pools is capped at MAX_POOLS_PER_BATCH to stay under the
+//   write-entry limit
 // ============================================================================
 
 #[contractimpl]
@@ -56,7 +142,7 @@ impl DexFactory {
     /// Initialize factory with admin and pool WASM hash
     pub fn initialize(env: Env, admin: Address, pool_wasm_hash: BytesN<32>) {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            panic_with_error!(&env, FactoryError::AlreadyInitialized);
         }
 
         admin.require_auth();
@@ -87,97 +173,42 @@ impl DexFactory {
     /// Returns the pool contract address
     pub fn create_pool(
         env: Env,
+        creator: Address,
         token_a: Address,
         token_b: Address,
         fee: u32,
         initial_sqrt_price_x96: u128,
     ) -> Address {
-        // Sort tokens
-        let (token0, token1) = if token_a < token_b {
-            (token_a, token_b)
-        } else {
-            (token_b, token_a)
-        };
-
-        // Validate tokens are different
-        if token0 == token1 {
-            panic!("Identical tokens");
-        }
+        creator.require_auth();
+        deploy_pool(&env, creator, token_a, token_b, fee, initial_sqrt_price_x96)
+    }
 
-        // Check pool doesn't already exist
-        let pool_key = DataKey::Pool(token0.clone(), token1.clone(), fee);
-        if env.storage().persistent().has(&pool_key) {
-            panic!("Pool already exists");
+    /// Create several pools in one transaction
+    /// All-or-nothing: any invalid entry fails the whole batch. The creator
+    /// authorizes the batch once
+    pub fn create_pools(
+        env: Env,
+        creator: Address,
+        requests: Vec<CreatePoolRequest>,
+    ) -> Vec<Address> {
+        if requests.is_empty() || requests.len() > MAX_POOLS_PER_BATCH {
+            panic_with_error!(&env, FactoryError::InvalidBatchSize);
         }
 
-        // Get tick spacing for fee
-        let tick_spacing = Self::get_fee_tick_spacing(env.clone(), fee);
-        if tick_spacing == 0 {
-            panic!("Fee not enabled");
+        creator.require_auth();
+
+        let mut pools = Vec::new(&env);
+        for request in requests.iter() {
+            pools.push_back(deploy_pool(
+                &env,
+                creator.clone(),
+                request.token_a,
+                request.token_b,
+                request.fee,
+                request.initial_sqrt_price_x96,
+            ));
         }
-
-        // Get pool WASM hash
-        let pool_wasm_hash: BytesN<32> = env
-            .storage()
-            .instance()
-            .get(&DataKey::PoolWasmHash)
-            .expect("Not initialized");
-
-        // Get current pool count for salt and indexing
-        let pool_count: u32 = env
-            .storage()
-            .instance()
-            .get(&DataKey::PoolCount)
-            .unwrap_or(0);
-
-        // Create deterministic salt from pool count + fee
-        let mut salt_bytes = [0u8; 32];
-        salt_bytes[0..4].copy_from_slice(&pool_count.to_be_bytes());
-        salt_bytes[4..8].copy_from_slice(&fee.to_be_bytes());
-        let salt = BytesN::from_array(&env, &salt_bytes);
-
-        // Deploy pool contract
-        let pool_address = env
-            .deployer()
-            .with_current_contract(salt)
-            .deploy_v2(pool_wasm_hash, ());
-
-        // Initialize the pool
-        init_pool(
-            &env,
-            &pool_address,
-            &env.current_contract_address(),
-            &token0,
-            &token1,
-            &fee,
-            &tick_spacing,
-            &initial_sqrt_price_x96,
-        );
-
-        // Store pool address by token pair
-        env.storage().persistent().set(&pool_key, &pool_address);
-        extend_persistent_ttl(&env, &pool_key);
-
-        // Store pool at index (indexed storage - O(1) append)
-        let pool_at_key = DataKey::PoolAt(pool_count);
-        env.storage()
-            .persistent()
-            .set(&pool_at_key, &pool_address);
-        extend_persistent_ttl(&env, &pool_at_key);
-
-        // Increment pool counter
-        env.storage()
-            .instance()
-            .set(&DataKey::PoolCount, &(pool_count + 1));
-
-        // Emit event
-        env.events().publish(
-            (Symbol::new(&env, "pool_created"),),
-            (token0, token1, fee, pool_address.clone()),
-        );
-
-        extend_instance_ttl(&env);
-        pool_address
+        pools
     }
 
     /// Get pool address for token pair and fee
@@ -188,8 +219,47 @@ impl DexFactory {
             (token_b, token_a)
         };
 
-        let pool_key = DataKey::Pool(token0, token1, fee);
-        env.storage().persistent().get(&pool_key)
+        extend_instance_ttl(&env);
+        get_persistent_and_extend(&env, &DataKey::Pool(token0, token1, fee))
+    }
+
+    /// Check whether an address is a pool deployed by this factory
+    pub fn is_pool(env: Env, address: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::PoolAddress(address))
+    }
+
+    /// Reverse lookup: pool address -> (token0, token1, fee)
+    pub fn get_pool_key(env: Env, pool: Address) -> Option<(Address, Address, u32)> {
+        env.storage().persistent().get(&DataKey::PoolAddress(pool))
+    }
+
+    /// Get pool address, state and config in one call
+    /// Returns None if no pool exists for the pair and fee
+    pub fn get_pool_details(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        fee: u32,
+    ) -> Option<PoolDetails> {
+        let pool = Self::get_pool(env.clone(), token_a, token_b, fee)?;
+
+        let state: PoolState =
+            env.invoke_contract(&pool, &Symbol::new(&env, "get_state"), ().into_val(&env));
+        let config: PoolConfig =
+            env.invoke_contract(&pool, &Symbol::new(&env, "get_config"), ().into_val(&env));
+
+        Some(PoolDetails {
+            pool,
+            state,
+            config,
+        })
+    }
+
+    /// Get token metadata cached for a pool at creation
+    pub fn get_pool_tokens(env: Env, pool: Address) -> Option<PoolTokenInfo> {
+        env.storage().persistent().get(&DataKey::PoolTokens(pool))
     }
 
     /// Enable a new fee tier
@@ -198,27 +268,55 @@ impl DexFactory {
             .storage()
             .instance()
             .get(&DataKey::Admin)
-            .expect("Not initialized");
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
         admin.require_auth();
 
-        if tick_spacing <= 0 || tick_spacing > 16384 {
-            panic!("Invalid tick spacing");
-        }
+        validate_tick_spacing(&env, tick_spacing);
 
         if fee >= 1_000_000 {
-            panic!("Fee too high");
+            panic_with_error!(&env, FactoryError::FeeTooHigh);
         }
 
         // Check not already set
         let key = DataKey::FeeTickSpacing(fee);
         if env.storage().instance().has(&key) {
-            panic!("Fee already enabled");
+            panic_with_error!(&env, FactoryError::FeeAlreadyEnabled);
         }
 
         env.storage().instance().set(&key, &tick_spacing);
         extend_instance_ttl(&env);
     }
 
+    /// Correct the tick spacing of an enabled fee tier (admin only)
+    /// Only allowed while no pool has ever been created at that tier
+    pub fn update_fee_tick_spacing(env: Env, fee: u32, new_spacing: i32) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+
+        validate_tick_spacing(&env, new_spacing);
+
+        let key = DataKey::FeeTickSpacing(fee);
+        if !env.storage().instance().has(&key) {
+            panic_with_error!(&env, FactoryError::FeeNotEnabled);
+        }
+
+        let pools_at_fee: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PoolCountByFee(fee))
+            .unwrap_or(0);
+        if pools_at_fee > 0 {
+            panic_with_error!(&env, FactoryError::FeeTierInUse);
+        }
+
+        env.storage().instance().set(&key, &new_spacing);
+        extend_instance_ttl(&env);
+    }
+
     /// Get tick spacing for fee tier
     pub fn get_fee_tick_spacing(env: Env, fee: u32) -> i32 {
         extend_instance_ttl(&env);
@@ -234,7 +332,7 @@ impl DexFactory {
             .storage()
             .instance()
             .get(&DataKey::Admin)
-            .expect("Not initialized");
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
         admin.require_auth();
 
         env.storage()
@@ -249,6 +347,201 @@ impl DexFactory {
         env.storage().instance().get(&DataKey::FeeRecipient)
     }
 
+    /// Pause or resume swaps in every pool (admin only)
+    /// Mint, burn and collect stay available so LPs can always exit
+    pub fn set_global_pause(env: Env, paused: bool) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::GlobalPause, &paused);
+        extend_instance_ttl(&env);
+
+        env.events()
+            .publish((Symbol::new(&env, "global_pause"),), (paused,));
+    }
+
+    /// Whether swaps are paused in every pool
+    pub fn get_global_pause(env: Env) -> bool {
+        extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .get(&DataKey::GlobalPause)
+            .unwrap_or(false)
+    }
+
+    /// Record the canonical periphery contracts (admin only)
+    pub fn set_periphery(
+        env: Env,
+        router: Address,
+        quoter: Address,
+        position_manager: Address,
+    ) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Router, &router);
+        env.storage().instance().set(&DataKey::Quoter, &quoter);
+        env.storage()
+            .instance()
+            .set(&DataKey::PositionManager, &position_manager);
+        extend_instance_ttl(&env);
+
+        env.events().publish(
+            (Symbol::new(&env, "periphery_set"),),
+            (router, quoter, position_manager),
+        );
+    }
+
+    /// Get the canonical router address
+    pub fn get_router(env: Env) -> Option<Address> {
+        extend_instance_ttl(&env);
+        env.storage().instance().get(&DataKey::Router)
+    }
+
+    /// Get the canonical quoter address
+    pub fn get_quoter(env: Env) -> Option<Address> {
+        extend_instance_ttl(&env);
+        env.storage().instance().get(&DataKey::Quoter)
+    }
+
+    /// Get the canonical position manager address
+    pub fn get_position_manager(env: Env) -> Option<Address> {
+        extend_instance_ttl(&env);
+        env.storage().instance().get(&DataKey::PositionManager)
+    }
+
+    /// Sweep tokens donated directly to a pool to the fee recipient
+    /// Returns the (amount0, amount1) skimmed
+    pub fn skim_pool(env: Env, pool: Address) -> (u128, u128) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+        extend_instance_ttl(&env);
+
+        let recipient: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeRecipient)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::FeeRecipientNotSet));
+
+        env.invoke_contract(
+            &pool,
+            &Symbol::new(&env, "skim"),
+            (recipient,).into_val(&env),
+        )
+    }
+
+    /// Set a pool's protocol fee denominators (admin only)
+    /// Relayed to the pool, which accepts it because the factory is the caller
+    pub fn set_pool_fee_protocol(
+        env: Env,
+        pool: Address,
+        fee_protocol_0: u32,
+        fee_protocol_1: u32,
+    ) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+        extend_instance_ttl(&env);
+
+        env.invoke_contract::<()>(
+            &pool,
+            &Symbol::new(&env, "set_fee_protocol"),
+            (fee_protocol_0, fee_protocol_1).into_val(&env),
+        );
+    }
+
+    /// Pause or resume swaps in a single pool (admin only)
+    /// Relayed to the pool, which accepts it because the factory is the caller
+    pub fn set_pool_paused(env: Env, pool: Address, paused: bool) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+        extend_instance_ttl(&env);
+
+        env.invoke_contract::<()>(
+            &pool,
+            &Symbol::new(&env, "set_paused"),
+            (paused,).into_val(&env),
+        );
+    }
+
+    /// Upgrade a pool to an uploaded WASM and migrate its storage (admin only)
+    /// The migration runs as a second call so it executes the new code
+    ///
+    /// # Returns
+    /// The pool's storage version after migration
+    pub fn upgrade_pool(env: Env, pool: Address, new_wasm_hash: BytesN<32>) -> u32 {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+        extend_instance_ttl(&env);
+
+        env.invoke_contract::<()>(
+            &pool,
+            &Symbol::new(&env, "upgrade"),
+            (new_wasm_hash,).into_val(&env),
+        );
+        env.invoke_contract::<u32>(&pool, &Symbol::new(&env, "migrate"), Vec::new(&env))
+    }
+
+    /// Get pools with their pair, fee and current price (max 10 per call)
+    pub fn get_pools_with_state_paginated(env: Env, start: u32, limit: u32) -> Vec<PoolSummary> {
+        let safe_limit = limit.min(MAX_SUMMARIES_PER_PAGE);
+
+        extend_instance_ttl(&env);
+        let total: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PoolCount)
+            .unwrap_or(0);
+        let end_index = start.saturating_add(safe_limit).min(total);
+
+        let mut summaries: Vec<PoolSummary> = Vec::new(&env);
+        for i in start..end_index {
+            let pool: Address = match get_persistent_and_extend(&env, &DataKey::PoolAt(i)) {
+                Some(pool) => pool,
+                None => continue,
+            };
+
+            let state: PoolState =
+                env.invoke_contract(&pool, &Symbol::new(&env, "get_state"), ().into_val(&env));
+            let config: PoolConfig =
+                env.invoke_contract(&pool, &Symbol::new(&env, "get_config"), ().into_val(&env));
+
+            summaries.push_back(PoolSummary {
+                pool,
+                token0: config.token0,
+                token1: config.token1,
+                fee: config.fee,
+                sqrt_price_x96: state.sqrt_price_x96,
+                tick: state.tick,
+                liquidity: state.liquidity,
+            });
+        }
+        summaries
+    }
+
     /// Get total number of pools created
     pub fn get_pool_count(env: Env) -> u32 {
         extend_instance_ttl(&env);
@@ -258,20 +551,37 @@ impl DexFactory {
             .unwrap_or(0)
     }
 
+    /// Get creation metadata for the pool at `index`
+    pub fn get_pool_meta(env: Env, index: u32) -> Option<PoolMeta> {
+        env.storage().persistent().get(&DataKey::PoolMeta(index))
+    }
+
     /// Get pool address at specific index
     pub fn get_pool_at(env: Env, index: u32) -> Option<Address> {
-        let pool_at_key = DataKey::PoolAt(index);
-        env.storage().persistent().get(&pool_at_key)
+        extend_instance_ttl(&env);
+        get_persistent_and_extend(&env, &DataKey::PoolAt(index))
     }
 
     /// Get pools with pagination
-    /// Returns up to `limit` pools starting from `start_index`
+    /// Returns up to `limit` pools starting at registry index `start_index`
+    /// Ascending pages walk from oldest to newest. Descending pages walk from
+    /// `start_index` towards index 0; pass `u32::MAX` to start at the newest pool.
     /// Maximum limit is 50 to stay within Soroban's read entry limits
-    pub fn get_pools_paginated(env: Env, start_index: u32, limit: u32) -> Vec<Address> {
+    pub fn get_pools_paginated(
+        env: Env,
+        start_index: u32,
+        limit: u32,
+        descending: bool,
+    ) -> PoolPage {
         // Cap limit to prevent exceeding read entry limits (100 max, using 50 for safety)
-        let safe_limit = if limit > 50 { 50 } else { limit };
+        let safe_limit = if limit > MAX_POOLS_PER_PAGE {
+            MAX_POOLS_PER_PAGE
+        } else {
+            limit
+        };
 
-        let pool_count: u32 = env
+        extend_instance_ttl(&env);
+        let total: u32 = env
             .storage()
             .instance()
             .get(&DataKey::PoolCount)
@@ -279,14 +589,85 @@ impl DexFactory {
 
         let mut pools: Vec<Address> = Vec::new(&env);
 
-        let end_index = if start_index + safe_limit > pool_count {
-            pool_count
+        let next_start_index = if descending {
+            if total == 0 {
+                None
+            } else {
+                let first = start_index.min(total - 1);
+                let count = safe_limit.min(first + 1);
+
+                for offset in 0..count {
+                    let pool_at_key = DataKey::PoolAt(first - offset);
+                    if let Some(pool) = get_persistent_and_extend(&env, &pool_at_key) {
+                        pools.push_back(pool);
+                    }
+                }
+
+                if count <= first {
+                    Some(first - count)
+                } else {
+                    None
+                }
+            }
+        } else {
+            let end_index = start_index.saturating_add(safe_limit).min(total);
+
+            for i in start_index..end_index {
+                if let Some(pool) = get_persistent_and_extend(&env, &DataKey::PoolAt(i)) {
+                    pools.push_back(pool);
+                }
+            }
+
+            if start_index < total && end_index < total {
+                Some(end_index)
+            } else {
+                None
+            }
+        };
+
+        PoolPage {
+            pools,
+            next_start_index,
+            total,
+        }
+    }
+
+    /// Get number of pools created at a fee tier
+    pub fn get_pool_count_by_fee(env: Env, fee: u32) -> u32 {
+        extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .get(&DataKey::PoolCountByFee(fee))
+            .unwrap_or(0)
+    }
+
+    /// Get pools at a fee tier with pagination
+    /// Returns up to `limit` pools starting from `start_index`
+    /// Maximum limit is 50 to stay within Soroban's read entry limits
+    pub fn get_pools_by_fee_paginated(
+        env: Env,
+        fee: u32,
+        start_index: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        let safe_limit = if limit > MAX_POOLS_PER_PAGE {
+            MAX_POOLS_PER_PAGE
         } else {
-            start_index + safe_limit
+            limit
         };
 
+        let pool_count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PoolCountByFee(fee))
+            .unwrap_or(0);
+
+        let mut pools: Vec<Address> = Vec::new(&env);
+
+        let end_index = start_index.saturating_add(safe_limit).min(pool_count);
+
         for i in start_index..end_index {
-            if let Some(pool) = env.storage().persistent().get(&DataKey::PoolAt(i)) {
+            if let Some(pool) = env.storage().persistent().get(&DataKey::PoolByFeeAt(fee, i)) {
                 pools.push_back(pool);
             }
         }
@@ -323,7 +704,7 @@ impl DexFactory {
         env.storage()
             .instance()
             .get(&DataKey::Admin)
-            .expect("Not initialized")
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized))
     }
 
     /// Get pool WASM hash
@@ -332,7 +713,33 @@ impl DexFactory {
         env.storage()
             .instance()
             .get(&DataKey::PoolWasmHash)
-            .expect("Not initialized")
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized))
+    }
+
+    /// Upgrade the factory contract code
+    /// Storage (registry, fee tiers, admin) is preserved across the upgrade
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(&env, FactoryError::NotInitialized));
+        admin.require_auth();
+
+        // The running code's hash, read before the swap
+        let old_wasm_hash = match env.current_contract_address().executable() {
+            Some(Executable::Wasm(hash)) => Some(hash),
+            _ => None,
+        };
+        extend_instance_ttl(&env);
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish(
+            (Symbol::new(&env, "factory_upgraded"),),
+            (old_wasm_hash, new_wasm_hash),
+        );
     }
 }
 
@@ -348,37 +755,265 @@ fn extend_persistent_ttl(env: &Env, key: &DataKey) {
         .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
 }
 
-// Pool initialization via invoke
-fn init_pool(
-    env: &Env,
-    pool_address: &Address,
-    factory: &Address,
-    token0: &Address,
-    token1: &Address,
-    fee: &u32,
-    tick_spacing: &i32,
-    sqrt_price_x96: &u128,
-) {
-    env.invoke_contract::<()>(
-        pool_address,
-        &Symbol::new(env, "initialize"),
-        (factory, token0, token1, fee, tick_spacing, sqrt_price_x96).into_val(env),
-    );
+/// Read a persistent entry and extend its TTL if it exists
+/// Keeps registry entries of frequently looked-up pools alive
+fn get_persistent_and_extend<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent_ttl(env, key);
+    }
+    value
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::testutils::Address as _;
-    use soroban_sdk::{Address, BytesN, Env};
+/// Tick spacing must be in (0, 16384]
+fn validate_tick_spacing(env: &Env, tick_spacing: i32) {
+    if tick_spacing <= 0 || tick_spacing > 16384 {
+        panic_with_error!(env, FactoryError::InvalidTickSpacing);
+    }
+}
 
-    fn setup_factory(env: &Env) -> (Address, Address) {
-        let admin = Address::generate(env);
-        let contract_id = env.register(DexFactory, ());
-        (admin, contract_id)
+/// Read a token's decimals, rejecting addresses that don't implement the token interface
+fn probe_token_decimals(env: &Env, token: &Address) -> u32 {
+    match token::Client::new(env, token).try_decimals() {
+        Ok(Ok(decimals)) => decimals,
+        _ => panic_with_error!(env, FactoryError::InvalidToken),
     }
+}
 
-    // === Initialization Tests ===
+/// Deploy, initialize and index a pool for the pair and fee
+/// Callers are responsible for the creator's auth
+fn deploy_pool(
+    env: &Env,
+    creator: Address,
+    token_a: Address,
+    token_b: Address,
+    fee: u32,
+    initial_sqrt_price_x96: u128,
+) -> Address {
+    // Sort tokens
+    let (token0, token1) = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+
+    // Validate tokens are different
+    if token0 == token1 {
+        panic_with_error!(env, FactoryError::IdenticalTokens);
+    }
+
+    // Check pool doesn't already exist
+    let pool_key = DataKey::Pool(token0.clone(), token1.clone(), fee);
+    if env.storage().persistent().has(&pool_key) {
+        panic_with_error!(env, FactoryError::PoolExists);
+    }
+
+    // Get tick spacing for fee
+    let tick_spacing = DexFactory::get_fee_tick_spacing(env.clone(), fee);
+    if tick_spacing == 0 {
+        panic_with_error!(env, FactoryError::FeeNotEnabled);
+    }
+
+    // Reject addresses that don't implement the token interface
+    let decimals0 = probe_token_decimals(env, &token0);
+    let decimals1 = probe_token_decimals(env, &token1);
+
+    // Get pool WASM hash
+    let pool_wasm_hash: BytesN<32> = env
+        .storage()
+        .instance()
+        .get(&DataKey::PoolWasmHash)
+        .unwrap_or_else(|| panic_with_error!(env, FactoryError::NotInitialized));
+
+    // Get current pool count for salt and indexing
+    let pool_count: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::PoolCount)
+        .unwrap_or(0);
+
+    // Create deterministic salt from pool count + fee
+    let mut salt_bytes = [0u8; 32];
+    salt_bytes[0..4].copy_from_slice(&pool_count.to_be_bytes());
+    salt_bytes[4..8].copy_from_slice(&fee.to_be_bytes());
+    let salt = BytesN::from_array(env, &salt_bytes);
+
+    // Deploy pool contract
+    let pool_address = env
+        .deployer()
+        .with_current_contract(salt)
+        .deploy_v2(pool_wasm_hash, ());
+
+    // Initialize the pool
+    init_pool(
+        env,
+        &pool_address,
+        &env.current_contract_address(),
+        &token0,
+        &token1,
+        &fee,
+        &tick_spacing,
+        &initial_sqrt_price_x96,
+    );
+
+    // Store pool address by token pair
+    env.storage().persistent().set(&pool_key, &pool_address);
+    extend_persistent_ttl(env, &pool_key);
+
+    // Reverse lookup marker for is_pool / get_pool_key
+    let pool_address_key = DataKey::PoolAddress(pool_address.clone());
+    env.storage()
+        .persistent()
+        .set(&pool_address_key, &(token0.clone(), token1.clone(), fee));
+    extend_persistent_ttl(env, &pool_address_key);
+
+    // Store pool at index (indexed storage - O(1) append)
+    let pool_at_key = DataKey::PoolAt(pool_count);
+    env.storage()
+        .persistent()
+        .set(&pool_at_key, &pool_address);
+    extend_persistent_ttl(env, &pool_at_key);
+
+    // Record who created the pool and when
+    let pool_meta_key = DataKey::PoolMeta(pool_count);
+    env.storage().persistent().set(
+        &pool_meta_key,
+        &PoolMeta {
+            creator,
+            created_ledger: env.ledger().sequence(),
+            created_timestamp: env.ledger().timestamp(),
+        },
+    );
+    extend_persistent_ttl(env, &pool_meta_key);
+
+    // Increment pool counter
+    env.storage()
+        .instance()
+        .set(&DataKey::PoolCount, &(pool_count + 1));
+
+    // Store pool at per-fee-tier index
+    let fee_pool_count: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::PoolCountByFee(fee))
+        .unwrap_or(0);
+    let pool_by_fee_key = DataKey::PoolByFeeAt(fee, fee_pool_count);
+    env.storage()
+        .persistent()
+        .set(&pool_by_fee_key, &pool_address);
+    extend_persistent_ttl(env, &pool_by_fee_key);
+    env.storage()
+        .instance()
+        .set(&DataKey::PoolCountByFee(fee), &(fee_pool_count + 1));
+
+    // Cache token metadata for front-ends
+    let pool_tokens_key = DataKey::PoolTokens(pool_address.clone());
+    env.storage().persistent().set(
+        &pool_tokens_key,
+        &PoolTokenInfo {
+            token0: token0.clone(),
+            token1: token1.clone(),
+            decimals0,
+            decimals1,
+        },
+    );
+    extend_persistent_ttl(env, &pool_tokens_key);
+
+    // Emit event (pool index as topic so consumers can page)
+    env.events().publish(
+        (Symbol::new(env, "pool_created"), pool_count),
+        (
+            token0,
+            token1,
+            fee,
+            tick_spacing,
+            initial_sqrt_price_x96,
+            pool_address.clone(),
+        ),
+    );
+
+    extend_instance_ttl(env);
+    pool_address
+}
+
+// Pool initialization via invoke
+fn init_pool(
+    env: &Env,
+    pool_address: &Address,
+    factory: &Address,
+    token0: &Address,
+    token1: &Address,
+    fee: &u32,
+    tick_spacing: &i32,
+    sqrt_price_x96: &u128,
+) {
+    env.invoke_contract::<()>(
+        pool_address,
+        &Symbol::new(env, "initialize"),
+        (factory, token0, token1, fee, tick_spacing, sqrt_price_x96).into_val(env),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::storage::Persistent as _;
+    use soroban_sdk::testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke};
+    use soroban_sdk::token::{StellarAssetClient, TokenClient};
+    use soroban_sdk::{vec, Address, BytesN, Env, IntoVal, Val, Vec};
+
+    // Pool WASM is needed for tests that deploy pools through `create_pool`.
+    // Build it first with `cargo build --release --target wasm32-unknown-unknown`.
+    mod dex_pool_contract {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32-unknown-unknown/release/dex_pool.wasm"
+        );
+    }
+
+    // Factory WASM, used as the upgrade target in upgrade tests
+    mod dex_factory_contract {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32-unknown-unknown/release/dex_factory.wasm"
+        );
+    }
+
+    const Q96: u128 = 1 << 96;
+
+    /// A contract that does not implement the token interface
+    #[contract]
+    struct NotAToken;
+
+    #[contractimpl]
+    impl NotAToken {
+        pub fn ping(_env: Env) -> u32 {
+            1
+        }
+    }
+
+    fn setup_factory(env: &Env) -> (Address, Address) {
+        let admin = Address::generate(env);
+        let contract_id = env.register(DexFactory, ());
+        (admin, contract_id)
+    }
+
+    /// Register and initialize a factory able to deploy real pool contracts
+    fn setup_factory_with_pool_wasm<'a>(env: &Env) -> (Address, DexFactoryClient<'a>) {
+        let (admin, contract_id) = setup_factory(env);
+        let client = DexFactoryClient::new(env, &contract_id);
+
+        let pool_wasm_hash = env.deployer().upload_contract_wasm(dex_pool_contract::WASM);
+        client.initialize(&admin, &pool_wasm_hash);
+
+        (admin, client)
+    }
+
+    /// Register a Stellar asset contract to use as a pool token
+    fn create_token(env: &Env) -> Address {
+        let issuer = Address::generate(env);
+        env.register_stellar_asset_contract_v2(issuer).address()
+    }
+
+    // === Initialization Tests ===
 
     #[test]
     fn test_initialize_factory() {
@@ -405,7 +1040,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Already initialized")]
+    #[should_panic(expected = "Error(Contract, #1)")]
     fn test_initialize_twice_fails() {
         let env = Env::default();
         env.mock_all_auths();
@@ -462,7 +1097,46 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Fee already enabled")]
+    fn test_update_fee_tick_spacing_before_any_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        client.enable_fee_amount(&100, &7);
+
+        client.update_fee_tick_spacing(&100, &1);
+        assert_eq!(client.get_fee_tick_spacing(&100), 1);
+
+        // Same validation rules as enable_fee_amount
+        assert_eq!(
+            client.try_update_fee_tick_spacing(&100, &0),
+            Err(Ok(contract_error(FactoryError::InvalidTickSpacing)))
+        );
+        assert_eq!(
+            client.try_update_fee_tick_spacing(&250, &5),
+            Err(Ok(contract_error(FactoryError::FeeNotEnabled)))
+        );
+    }
+
+    #[test]
+    fn test_update_fee_tick_spacing_after_pool_fails() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        client.enable_fee_amount(&100, &7);
+        client.create_pool(&creator, &create_token(&env), &create_token(&env), &100, &Q96);
+
+        assert_eq!(
+            client.try_update_fee_tick_spacing(&100, &1),
+            Err(Ok(contract_error(FactoryError::FeeTierInUse)))
+        );
+        assert_eq!(client.get_fee_tick_spacing(&100), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #8)")]
     fn test_enable_existing_fee_fails() {
         let env = Env::default();
         env.mock_all_auths();
@@ -479,7 +1153,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Invalid tick spacing")]
+    #[should_panic(expected = "Error(Contract, #6)")]
     fn test_enable_invalid_tick_spacing_zero() {
         let env = Env::default();
         env.mock_all_auths();
@@ -495,7 +1169,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Invalid tick spacing")]
+    #[should_panic(expected = "Error(Contract, #6)")]
     fn test_enable_invalid_tick_spacing_too_large() {
         let env = Env::default();
         env.mock_all_auths();
@@ -511,7 +1185,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Fee too high")]
+    #[should_panic(expected = "Error(Contract, #7)")]
     fn test_enable_fee_too_high() {
         let env = Env::default();
         env.mock_all_auths();
@@ -586,8 +1260,98 @@ mod tests {
         assert!(client.get_pool_at(&0).is_none());
 
         // get_pools_paginated returns empty for no pools
-        let paginated = client.get_pools_paginated(&0, &10);
-        assert_eq!(paginated.len(), 0);
+        let paginated = client.get_pools_paginated(&0, &10, &false);
+        assert_eq!(paginated.pools.len(), 0);
+    }
+
+    #[test]
+    fn test_pools_paginated_empty_registry() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+
+        for descending in [false, true] {
+            let page = client.get_pools_paginated(&0, &10, &descending);
+            assert_eq!(page.pools.len(), 0);
+            assert_eq!(page.next_start_index, None);
+            assert_eq!(page.total, 0);
+        }
+
+        let page = client.get_pools_paginated(&u32::MAX, &10, &true);
+        assert_eq!(page.pools.len(), 0);
+        assert_eq!(page.next_start_index, None);
+    }
+
+    /// Create four pools; returns them in registry order
+    fn create_four_pools(env: &Env, client: &DexFactoryClient) -> Vec<Address> {
+        let creator = Address::generate(env);
+        let token_a = create_token(env);
+        let token_b = create_token(env);
+        let token_c = create_token(env);
+
+        let mut pools = Vec::new(env);
+        pools.push_back(client.create_pool(&creator, &token_a, &token_b, &500, &Q96));
+        pools.push_back(client.create_pool(&creator, &token_a, &token_b, &3000, &Q96));
+        pools.push_back(client.create_pool(&creator, &token_a, &token_b, &10000, &Q96));
+        pools.push_back(client.create_pool(&creator, &token_a, &token_c, &500, &Q96));
+        pools
+    }
+
+    #[test]
+    fn test_pools_paginated_exact_page_boundary() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let pools = create_four_pools(&env, &client);
+
+        let first = client.get_pools_paginated(&0, &2, &false);
+        assert_eq!(first.pools, pools.slice(0..2));
+        assert_eq!(first.next_start_index, Some(2));
+        assert_eq!(first.total, 4);
+
+        // The second page ends exactly at the last pool
+        let second = client.get_pools_paginated(&2, &2, &false);
+        assert_eq!(second.pools, pools.slice(2..4));
+        assert_eq!(second.next_start_index, None);
+
+        // Past the end and overflowing arithmetic are both empty
+        assert_eq!(client.get_pools_paginated(&4, &2, &false).pools.len(), 0);
+        let overflow = client.get_pools_paginated(&u32::MAX, &u32::MAX, &false);
+        assert_eq!(overflow.pools.len(), 0);
+        assert_eq!(overflow.next_start_index, None);
+    }
+
+    #[test]
+    fn test_pools_paginated_descending() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let pools = create_four_pools(&env, &client);
+
+        // Newest first
+        let first = client.get_pools_paginated(&u32::MAX, &3, &true);
+        assert_eq!(first.pools.len(), 3);
+        assert_eq!(first.pools.get(0).unwrap(), pools.get(3).unwrap());
+        assert_eq!(first.pools.get(1).unwrap(), pools.get(2).unwrap());
+        assert_eq!(first.pools.get(2).unwrap(), pools.get(1).unwrap());
+        assert_eq!(first.next_start_index, Some(0));
+        assert_eq!(first.total, 4);
+
+        let second = client.get_pools_paginated(&0, &3, &true);
+        assert_eq!(second.pools.len(), 1);
+        assert_eq!(second.pools.get(0).unwrap(), pools.get(0).unwrap());
+        assert_eq!(second.next_start_index, None);
+
+        // Exact boundary: two pages of two
+        let page = client.get_pools_paginated(&3, &2, &true);
+        assert_eq!(page.next_start_index, Some(1));
+        let page = client.get_pools_paginated(&1, &2, &true);
+        assert_eq!(page.pools.get(0).unwrap(), pools.get(1).unwrap());
+        assert_eq!(page.pools.get(1).unwrap(), pools.get(0).unwrap());
+        assert_eq!(page.next_start_index, None);
     }
 
     // === Fee Recipient Tests ===
@@ -616,6 +1380,88 @@ mod tests {
         assert_eq!(recipient, Some(new_recipient));
     }
 
+    #[test]
+    fn test_global_pause_blocks_swaps_only() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &token_a).mint(&user, &1_000_000_000);
+        StellarAssetClient::new(&env, &token_b).mint(&user, &1_000_000_000);
+        pool_client.mint(&user, &user, &-600, &600, &1_000_000);
+
+        assert!(!client.get_global_pause());
+        client.set_global_pause(&true);
+        assert!(client.get_global_pause());
+
+        // Swaps are blocked in every pool
+        assert!(pool_client.try_swap(&user, &false, &1_000, &0, &false).is_err());
+
+        // LP paths stay open
+        pool_client.mint(&user, &user, &-600, &600, &1_000);
+        pool_client.burn(&user, &-600, &600, &0);
+        assert_eq!(pool_client.collect(&user, &user, &-600, &600, &0, &0), (0, 0));
+
+        // Resuming re-enables swaps
+        client.set_global_pause(&false);
+        pool_client.swap(&user, &false, &1_000, &0, &false);
+    }
+
+    #[test]
+    fn test_set_periphery() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (admin, contract_id) = setup_factory(&env);
+        let client = DexFactoryClient::new(&env, &contract_id);
+        client.initialize(&admin, &BytesN::from_array(&env, &[1u8; 32]));
+
+        // Nothing recorded before set
+        assert_eq!(client.get_router(), None);
+        assert_eq!(client.get_quoter(), None);
+        assert_eq!(client.get_position_manager(), None);
+
+        let router = Address::generate(&env);
+        let quoter = Address::generate(&env);
+        let position_manager = Address::generate(&env);
+        client.set_periphery(&router, &quoter, &position_manager);
+
+        assert_eq!(client.get_router(), Some(router.clone()));
+        assert_eq!(client.get_quoter(), Some(quoter));
+        assert_eq!(client.get_position_manager(), Some(position_manager));
+
+        // Overwrite with a new deployment
+        let new_quoter = Address::generate(&env);
+        let new_position_manager = Address::generate(&env);
+        client.set_periphery(&router, &new_quoter, &new_position_manager);
+
+        assert_eq!(client.get_router(), Some(router));
+        assert_eq!(client.get_quoter(), Some(new_quoter));
+        assert_eq!(client.get_position_manager(), Some(new_position_manager));
+    }
+
+    #[test]
+    fn test_set_periphery_requires_admin() {
+        let env = Env::default();
+
+        let (admin, contract_id) = setup_factory(&env);
+        let client = DexFactoryClient::new(&env, &contract_id);
+        env.mock_all_auths();
+        client.initialize(&admin, &BytesN::from_array(&env, &[1u8; 32]));
+
+        env.set_auths(&[]);
+        let router = Address::generate(&env);
+        assert!(client.try_set_periphery(&router, &router, &router).is_err());
+        assert_eq!(client.get_router(), None);
+    }
+
     // === Admin Tests ===
 
     #[test]
@@ -719,4 +1565,787 @@ mod tests {
         client.enable_fee_amount(&2, &16384);
         assert_eq!(client.get_fee_tick_spacing(&2), 16384);
     }
+
+    // === Per-Fee-Tier Statistics Tests ===
+
+    #[test]
+    fn test_pool_count_by_fee() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let token_c = create_token(&env);
+
+        let pool_ab_500 = client.create_pool(&creator, &token_a, &token_b, &500, &Q96);
+        let pool_ac_500 = client.create_pool(&creator, &token_a, &token_c, &500, &Q96);
+        let pool_ab_3000 = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+
+        assert_eq!(client.get_pool_count(), 3);
+        assert_eq!(client.get_pool_count_by_fee(&500), 2);
+        assert_eq!(client.get_pool_count_by_fee(&3000), 1);
+        assert_eq!(client.get_pool_count_by_fee(&10000), 0);
+
+        let pools_500 = client.get_pools_by_fee_paginated(&500, &0, &10);
+        assert_eq!(pools_500.len(), 2);
+        assert_eq!(pools_500.get(0).unwrap(), pool_ab_500);
+        assert_eq!(pools_500.get(1).unwrap(), pool_ac_500);
+
+        let pools_3000 = client.get_pools_by_fee_paginated(&3000, &0, &10);
+        assert_eq!(pools_3000.len(), 1);
+        assert_eq!(pools_3000.get(0).unwrap(), pool_ab_3000);
+
+        // Offset past the first entry
+        let pools_500_tail = client.get_pools_by_fee_paginated(&500, &1, &10);
+        assert_eq!(pools_500_tail.len(), 1);
+        assert_eq!(pools_500_tail.get(0).unwrap(), pool_ac_500);
+
+        // Unused tier is empty
+        assert_eq!(client.get_pools_by_fee_paginated(&10000, &0, &10).len(), 0);
+    }
+
+    // === Error Code Tests ===
+
+    fn contract_error(error: FactoryError) -> soroban_sdk::Error {
+        error.into()
+    }
+
+    #[test]
+    fn test_create_pool_error_codes() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+
+        assert_eq!(
+            client.try_create_pool(&creator, &token_a, &token_a, &3000, &Q96),
+            Err(Ok(contract_error(FactoryError::IdenticalTokens)))
+        );
+        assert_eq!(
+            client.try_create_pool(&creator, &token_a, &token_b, &2500, &Q96),
+            Err(Ok(contract_error(FactoryError::FeeNotEnabled)))
+        );
+
+        client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+        assert_eq!(
+            client.try_create_pool(&creator, &token_b, &token_a, &3000, &Q96),
+            Err(Ok(contract_error(FactoryError::PoolExists)))
+        );
+    }
+
+    #[test]
+    fn test_uninitialized_factory_error_code() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (_, contract_id) = setup_factory(&env);
+        let client = DexFactoryClient::new(&env, &contract_id);
+
+        assert_eq!(
+            client.try_get_admin(),
+            Err(Ok(contract_error(FactoryError::NotInitialized)))
+        );
+        assert_eq!(
+            client.try_enable_fee_amount(&100, &1),
+            Err(Ok(contract_error(FactoryError::NotInitialized)))
+        );
+    }
+
+    // === Batch Creation Tests ===
+
+    fn pool_request(token_a: &Address, token_b: &Address, fee: u32) -> CreatePoolRequest {
+        CreatePoolRequest {
+            token_a: token_a.clone(),
+            token_b: token_b.clone(),
+            fee,
+            initial_sqrt_price_x96: Q96,
+        }
+    }
+
+    #[test]
+    fn test_create_pools_batch() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+
+        let requests = Vec::from_array(
+            &env,
+            [
+                pool_request(&token_a, &token_b, 500),
+                pool_request(&token_a, &token_b, 3000),
+                pool_request(&token_b, &token_a, 10000),
+            ],
+        );
+        let pools = client.create_pools(&creator, &requests);
+
+        assert_eq!(pools.len(), 3);
+        assert_eq!(client.get_pool_count(), 3);
+        assert_eq!(client.get_pool(&token_a, &token_b, &500), Some(pools.get(0).unwrap()));
+        assert_eq!(client.get_pool(&token_a, &token_b, &3000), Some(pools.get(1).unwrap()));
+        assert_eq!(client.get_pool(&token_a, &token_b, &10000), Some(pools.get(2).unwrap()));
+    }
+
+    #[test]
+    fn test_create_pools_needs_only_one_creator_auth() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+
+        let requests = Vec::from_array(
+            &env,
+            [
+                pool_request(&token_a, &token_b, 500),
+                pool_request(&token_a, &token_b, 3000),
+            ],
+        );
+        let pools = client
+            .mock_auths(&[MockAuth {
+                address: &creator,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "create_pools",
+                    args: (creator.clone(), requests.clone()).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .create_pools(&creator, &requests);
+
+        assert_eq!(pools.len(), 2);
+        assert_eq!(client.get_pool_count(), 2);
+    }
+
+    #[test]
+    fn test_create_pools_failure_leaves_no_partial_state() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+
+        // Second entry uses a fee tier that is not enabled
+        let requests = Vec::from_array(
+            &env,
+            [
+                pool_request(&token_a, &token_b, 500),
+                pool_request(&token_a, &token_b, 1234),
+            ],
+        );
+        assert_eq!(
+            client.try_create_pools(&creator, &requests),
+            Err(Ok(contract_error(FactoryError::FeeNotEnabled)))
+        );
+
+        assert_eq!(client.get_pool_count(), 0);
+        assert_eq!(client.get_pool_count_by_fee(&500), 0);
+        assert_eq!(client.get_pool(&token_a, &token_b, &500), None);
+
+        // Duplicate pair and fee inside one batch also fails as a whole
+        let requests = Vec::from_array(
+            &env,
+            [
+                pool_request(&token_a, &token_b, 3000),
+                pool_request(&token_b, &token_a, 3000),
+            ],
+        );
+        assert_eq!(
+            client.try_create_pools(&creator, &requests),
+            Err(Ok(contract_error(FactoryError::PoolExists)))
+        );
+        assert_eq!(client.get_pool_count(), 0);
+    }
+
+    #[test]
+    fn test_create_pools_batch_size_bounds() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+
+        assert_eq!(
+            client.try_create_pools(&creator, &Vec::new(&env)),
+            Err(Ok(contract_error(FactoryError::InvalidBatchSize)))
+        );
+
+        let token = create_token(&env);
+        let mut requests = Vec::new(&env);
+        for _ in 0..=MAX_POOLS_PER_BATCH {
+            requests.push_back(pool_request(&token, &create_token(&env), 3000));
+        }
+        assert_eq!(
+            client.try_create_pools(&creator, &requests),
+            Err(Ok(contract_error(FactoryError::InvalidBatchSize)))
+        );
+        assert_eq!(client.get_pool_count(), 0);
+    }
+
+    // === Event Tests ===
+
+    /// Events the last invocation published from `contract`
+    fn contract_events(env: &Env, contract: &Address) -> Vec<(Address, Vec<Val>, Val)> {
+        let mut events = Vec::new(env);
+        for event in env.events().all().iter() {
+            if event.0 == *contract {
+                events.push_back(event);
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_pool_created_event_payload() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        client.create_pool(&creator, &create_token(&env), &create_token(&env), &500, &Q96);
+
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let initial_price = Q96 * 2;
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &initial_price);
+
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        // Second pool: index 1 in the topics, tick spacing 60 for the 0.3% tier
+        assert_eq!(
+            contract_events(&env, &client.address),
+            vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(&env, "pool_created"), 1u32).into_val(&env),
+                    (token0, token1, 3000u32, 60i32, initial_price, pool).into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    // === Token Validation Tests ===
+
+    #[test]
+    fn test_create_pool_caches_token_decimals() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        // Stellar asset contracts use 7 decimals
+        let info = client.get_pool_tokens(&pool).unwrap();
+        assert_eq!(info.token0, token0);
+        assert_eq!(info.token1, token1);
+        assert_eq!(info.decimals0, 7);
+        assert_eq!(info.decimals1, 7);
+
+        assert_eq!(client.get_pool_tokens(&Address::generate(&env)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #9)")]
+    fn test_create_pool_rejects_non_token_contract() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token = create_token(&env);
+        let not_a_token = env.register(NotAToken, ());
+
+        client.create_pool(&creator, &token, &not_a_token, &3000, &Q96);
+    }
+
+    #[test]
+    fn test_create_pool_rejected_leaves_registry_empty() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token = create_token(&env);
+        let not_a_token = env.register(NotAToken, ());
+
+        assert!(client.try_create_pool(&creator, &token, &not_a_token, &3000, &Q96).is_err());
+        assert_eq!(client.get_pool_count(), 0);
+        assert_eq!(client.get_pool(&token, &not_a_token, &3000), None);
+    }
+
+    // === Upgrade Tests ===
+
+    #[test]
+    fn test_upgrade_preserves_registry() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (admin, client) = setup_factory_with_pool_wasm(&env);
+
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+
+        let new_wasm_hash = env
+            .deployer()
+            .upload_contract_wasm(dex_factory_contract::WASM);
+        client.upgrade(&new_wasm_hash);
+
+        // Registry and config survive the code swap
+        assert_eq!(client.get_pool_count(), 1);
+        assert_eq!(client.get_pool(&token_a, &token_b, &3000), Some(pool.clone()));
+        assert_eq!(client.get_pool_at(&0), Some(pool));
+        assert_eq!(client.get_admin(), admin);
+        assert_eq!(client.get_fee_tick_spacing(&3000), 60);
+    }
+
+    #[test]
+    fn test_upgrade_event_reports_running_wasm_hash() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let factory_id = env.register(dex_factory_contract::WASM, ());
+        let client = dex_factory_contract::Client::new(&env, &factory_id);
+        let pool_wasm_hash = env.deployer().upload_contract_wasm(dex_pool_contract::WASM);
+        client.initialize(&Address::generate(&env), &pool_wasm_hash);
+
+        // Re-uploading the same code yields the deployed hash
+        let running_hash = env
+            .deployer()
+            .upload_contract_wasm(dex_factory_contract::WASM);
+        client.upgrade(&running_hash);
+
+        let events = contract_events(&env, &factory_id);
+        assert_eq!(
+            events,
+            vec![
+                &env,
+                (
+                    factory_id.clone(),
+                    (Symbol::new(&env, "factory_upgraded"),).into_val(&env),
+                    (Some(running_hash.clone()), running_hash).into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_upgrade_requires_admin() {
+        let env = Env::default();
+
+        let (admin, contract_id) = setup_factory(&env);
+        let client = DexFactoryClient::new(&env, &contract_id);
+
+        let pool_wasm_hash = BytesN::from_array(&env, &[1u8; 32]);
+        env.mock_all_auths();
+        client.initialize(&admin, &pool_wasm_hash);
+
+        // Clear mocked auths so the admin check fails
+        env.set_auths(&[]);
+        client.upgrade(&BytesN::from_array(&env, &[2u8; 32]));
+    }
+
+    // === Skim Tests ===
+
+    #[test]
+    fn test_pool_meta_records_creator_and_ledger() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        assert_eq!(client.get_pool_meta(&0), None);
+
+        env.ledger().with_mut(|li| {
+            li.sequence_number = 1234;
+            li.timestamp = 1_700_000_000;
+        });
+        let creator = Address::generate(&env);
+        client.create_pool(&creator, &create_token(&env), &create_token(&env), &3000, &Q96);
+
+        env.ledger().with_mut(|li| {
+            li.sequence_number = 1300;
+            li.timestamp = 1_700_000_330;
+        });
+        let second_creator = Address::generate(&env);
+        client.create_pool(&second_creator, &create_token(&env), &create_token(&env), &500, &Q96);
+
+        assert_eq!(
+            client.get_pool_meta(&0),
+            Some(PoolMeta {
+                creator,
+                created_ledger: 1234,
+                created_timestamp: 1_700_000_000,
+            })
+        );
+        assert_eq!(
+            client.get_pool_meta(&1),
+            Some(PoolMeta {
+                creator: second_creator,
+                created_ledger: env.ledger().sequence(),
+                created_timestamp: env.ledger().timestamp(),
+            })
+        );
+        assert_eq!(client.get_pool_meta(&2), None);
+    }
+
+    #[test]
+    fn test_create_pool_requires_creator_auth() {
+        let env = Env::default();
+
+        env.mock_all_auths();
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+
+        env.set_auths(&[]);
+        let creator = Address::generate(&env);
+        assert!(client
+            .try_create_pool(&creator, &token_a, &token_b, &3000, &Q96)
+            .is_err());
+        assert_eq!(client.get_pool_count(), 0);
+    }
+
+    #[test]
+    fn test_registry_reads_keep_entries_live() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+
+        let pool_key = DataKey::Pool(
+            token_a.clone().min(token_b.clone()),
+            token_a.clone().max(token_b.clone()),
+            3000,
+        );
+        let ttl_of = |key: &DataKey| {
+            env.as_contract(&client.address, || env.storage().persistent().get_ttl(key))
+        };
+
+        // Several rounds of read traffic, each advancing close to expiry
+        for _ in 0..3 {
+            env.ledger().with_mut(|li| {
+                li.sequence_number += PERSISTENT_TTL_EXTEND - PERSISTENT_TTL_THRESHOLD / 2;
+            });
+            assert!(ttl_of(&pool_key) < PERSISTENT_TTL_THRESHOLD);
+            assert!(ttl_of(&DataKey::PoolAt(0)) < PERSISTENT_TTL_THRESHOLD);
+
+            assert_eq!(client.get_pool(&token_a, &token_b, &3000), Some(pool.clone()));
+            assert_eq!(client.get_pool_at(&0), Some(pool.clone()));
+            assert_eq!(client.get_pools_paginated(&0, &10, &false).pools.len(), 1);
+
+            assert_eq!(ttl_of(&pool_key), PERSISTENT_TTL_EXTEND);
+            assert_eq!(ttl_of(&DataKey::PoolAt(0)), PERSISTENT_TTL_EXTEND);
+        }
+
+        // Missing entries are not an error
+        assert_eq!(client.get_pool(&token_a, &token_b, &500), None);
+        assert_eq!(client.get_pool_at(&1), None);
+    }
+
+    #[test]
+    fn test_is_pool_and_reverse_lookup() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &500, &Q96);
+
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        assert!(client.is_pool(&pool));
+        assert_eq!(client.get_pool_key(&pool), Some((token0.clone(), token1.clone(), 500)));
+
+        // Tokens and random addresses are not pools
+        let random = Address::generate(&env);
+        assert!(!client.is_pool(&random));
+        assert!(!client.is_pool(&token0));
+        assert_eq!(client.get_pool_key(&random), None);
+    }
+
+    #[test]
+    fn test_get_pool_details_matches_pool_queries() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+
+        assert!(client.get_pool_details(&token_a, &token_b, &3000).is_none());
+
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+
+        let details = client.get_pool_details(&token_b, &token_a, &3000).unwrap();
+        assert_eq!(details.pool, pool);
+
+        let state = pool_client.get_state();
+        assert_eq!(details.state.sqrt_price_x96, state.sqrt_price_x96);
+        assert_eq!(details.state.tick, state.tick);
+        assert_eq!(details.state.liquidity, state.liquidity);
+        assert_eq!(details.state.fee_growth_global_0_x128, state.fee_growth_global_0_x128);
+        assert_eq!(details.state.fee_growth_global_1_x128, state.fee_growth_global_1_x128);
+
+        let config = pool_client.get_config();
+        assert_eq!(details.config.factory, client.address);
+        assert_eq!(details.config.token0, config.token0);
+        assert_eq!(details.config.token1, config.token1);
+        assert_eq!(details.config.fee, 3000);
+        assert_eq!(details.config.tick_spacing, config.tick_spacing);
+        assert_eq!(details.config.max_liquidity_per_tick, config.max_liquidity_per_tick);
+
+        assert!(client.get_pool_details(&token_a, &token_b, &500).is_none());
+    }
+
+    #[test]
+    fn test_pools_with_state_match_pool_queries() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let token_c = create_token(&env);
+
+        let pools = [
+            client.create_pool(&creator, &token_a, &token_b, &500, &Q96),
+            client.create_pool(&creator, &token_a, &token_c, &3000, &(Q96 * 2)),
+            client.create_pool(&creator, &token_b, &token_c, &10000, &(Q96 / 2)),
+        ];
+
+        // Give one pool in-range liquidity so the summaries differ
+        let lp = Address::generate(&env);
+        for token in [&token_a, &token_b] {
+            StellarAssetClient::new(&env, token).mint(&lp, &1_000_000_000);
+        }
+        dex_pool_contract::Client::new(&env, &pools[0])
+            .mint(&lp, &lp, &-600, &600, &1_000_000);
+
+        let summaries = client.get_pools_with_state_paginated(&0, &10);
+        assert_eq!(summaries.len(), 3);
+
+        for (i, pool) in pools.iter().enumerate() {
+            let summary = summaries.get(i as u32).unwrap();
+            let pool_client = dex_pool_contract::Client::new(&env, pool);
+            let state = pool_client.get_state();
+            let config = pool_client.get_config();
+
+            assert_eq!(&summary.pool, pool);
+            assert_eq!(summary.token0, config.token0);
+            assert_eq!(summary.token1, config.token1);
+            assert_eq!(summary.fee, config.fee);
+            assert_eq!(summary.sqrt_price_x96, state.sqrt_price_x96);
+            assert_eq!(summary.tick, state.tick);
+            assert_eq!(summary.liquidity, state.liquidity);
+        }
+        assert_eq!(summaries.get(0).unwrap().liquidity, 1_000_000);
+
+        // Paging and the per-call cap
+        let page = client.get_pools_with_state_paginated(&1, &1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap().pool, pools[1]);
+        assert_eq!(client.get_pools_with_state_paginated(&3, &10).len(), 0);
+        assert_eq!(client.get_pools_with_state_paginated(&0, &100).len(), 3);
+    }
+
+    #[test]
+    fn test_set_pool_fee_protocol_relays_to_pool() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let pool = client.create_pool(&creator, &create_token(&env), &create_token(&env), &3000, &Q96);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        assert_eq!(pool_client.fee_protocol(), (0, 0));
+
+        client.set_pool_fee_protocol(&pool, &6, &0);
+        assert_eq!(pool_client.fee_protocol(), (6, 0));
+
+        // The pool itself refuses callers other than its factory
+        env.set_auths(&[]);
+        assert!(pool_client.try_set_fee_protocol(&4, &4).is_err());
+        assert_eq!(pool_client.fee_protocol(), (6, 0));
+    }
+
+    #[test]
+    fn test_set_pool_paused_relays_to_pool() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+        let other = client.create_pool(&creator, &token_a, &token_b, &500, &Q96);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+
+        client.set_pool_paused(&pool, &true);
+        assert!(pool_client.is_paused());
+        assert!(!dex_pool_contract::Client::new(&env, &other).is_paused());
+
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &token_a).mint(&user, &1_000_000_000);
+        StellarAssetClient::new(&env, &token_b).mint(&user, &1_000_000_000);
+        pool_client.mint(&user, &user, &-600, &600, &1_000_000);
+        assert!(pool_client.try_swap(&user, &false, &1_000, &0, &false).is_err());
+
+        client.set_pool_paused(&pool, &false);
+        pool_client.swap(&user, &false, &1_000, &0, &false);
+
+        // The pool itself refuses callers other than its factory
+        env.set_auths(&[]);
+        assert!(pool_client.try_set_paused(&true).is_err());
+        assert!(!pool_client.is_paused());
+    }
+
+    #[test]
+    fn test_upgrade_pool_keeps_live_positions() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &token_a).mint(&user, &1_000_000_000);
+        StellarAssetClient::new(&env, &token_b).mint(&user, &1_000_000_000);
+        pool_client.mint(&user, &user, &-600, &600, &1_000_000_000);
+        pool_client.swap(&user, &false, &10_000, &0, &false);
+        let state_before = pool_client.get_state();
+        let position_before = pool_client.get_position(&user, &-600, &600);
+
+        let new_wasm_hash = env.deployer().upload_contract_wasm(dex_pool_contract::WASM);
+        let version = client.upgrade_pool(&pool, &new_wasm_hash);
+        assert_eq!(version, pool_client.storage_version());
+
+        let state = pool_client.get_state();
+        assert_eq!(state.sqrt_price_x96, state_before.sqrt_price_x96);
+        assert_eq!(state.liquidity, state_before.liquidity);
+        assert_eq!(state.fee_growth_global_1_x128, state_before.fee_growth_global_1_x128);
+        let position = pool_client.get_position(&user, &-600, &600);
+        assert_eq!(position.liquidity, position_before.liquidity);
+        assert_eq!(
+            position.fee_growth_inside_1_last_x128,
+            position_before.fee_growth_inside_1_last_x128
+        );
+
+        // The upgraded pool keeps trading against the same liquidity
+        let result = pool_client.swap(&user, &true, &10_000, &0, &false);
+        assert_eq!(result.amount0, 10_000);
+        assert!(result.amount1 < 0);
+
+        // Only the factory can upgrade the pool directly
+        env.set_auths(&[]);
+        assert!(pool_client.try_upgrade(&new_wasm_hash).is_err());
+        assert!(client.try_upgrade_pool(&pool, &new_wasm_hash).is_err());
+    }
+
+    #[test]
+    fn test_skim_pool_moves_exact_excess() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let fee_recipient = Address::generate(&env);
+        client.set_fee_recipient(&fee_recipient);
+
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        let token0 = pool_client.token0();
+        let token1 = pool_client.token1();
+
+        // Provide liquidity so the pool holds accounted balances
+        let lp = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&lp, &10_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&lp, &10_000_000);
+        pool_client.mint(&lp, &lp, &-600, &600, &1_000_000);
+
+        let (reserve0, reserve1) = pool_client.get_reserves().unwrap();
+        assert!(reserve0 > 0 && reserve1 > 0);
+
+        // Airdrop tokens straight to the pool
+        let donor = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&donor, &1_234);
+        StellarAssetClient::new(&env, &token1).mint(&donor, &5_678);
+        TokenClient::new(&env, &token0).transfer(&donor, &pool, &1_234);
+        TokenClient::new(&env, &token1).transfer(&donor, &pool, &5_678);
+
+        assert_eq!(client.skim_pool(&pool), (1_234, 5_678));
+        assert_eq!(TokenClient::new(&env, &token0).balance(&fee_recipient), 1_234);
+        assert_eq!(TokenClient::new(&env, &token1).balance(&fee_recipient), 5_678);
+
+        // Accounting is untouched and the pool still covers it exactly
+        assert_eq!(pool_client.get_reserves(), Some((reserve0, reserve1)));
+        assert_eq!(TokenClient::new(&env, &token0).balance(&pool), reserve0 as i128);
+        assert_eq!(TokenClient::new(&env, &token1).balance(&pool), reserve1 as i128);
+
+        // Nothing left to skim
+        assert_eq!(client.skim_pool(&pool), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #12)")]
+    fn test_skim_pool_requires_fee_recipient() {
+        let env = Env::default();
+        let creator = Address::generate(&env);
+        env.mock_all_auths();
+
+        let (_, client) = setup_factory_with_pool_wasm(&env);
+        let token_a = create_token(&env);
+        let token_b = create_token(&env);
+        let pool = client.create_pool(&creator, &token_a, &token_b, &3000, &Q96);
+
+        client.skim_pool(&pool);
+    }
 }
@@ -0,0 +1,255 @@
+// ============================================================================
+// BENCHMARKS - CPU and memory budgets for core pool operations
+// ============================================================================
+//
+// Each benchmark measures one operation with the host budget and asserts it
+// stays under an explicit ceiling. A regression beyond the ceiling fails the
+// suite; when an operation gets cheaper, ratchet its ceiling down to ~20%
+// above the newly reported numbers.
+//
+// Run with output to see the report:
+//   cargo test -p dex-pool benchmarks -- --nocapture
+//
+// Contracts are registered natively, so the numbers cover host work (storage,
+// U256 math, token calls) rather than WASM execution.
+//
+// ============================================================================
+
+extern crate std;
+
+use crate::{DexPool, DexPoolClient};
+use dex_types::Q96;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::token::StellarAssetClient;
+use soroban_sdk::{contract, contractimpl, Address, Env};
+
+// Budgets: (cpu instructions, memory bytes), ~1.2x the measured cost
+// Measured: 6_963_144 / 518_300
+const SWAP_WITHIN_TICK_BUDGET: (u64, u64) = (8_400_000, 625_000);
+// Measured: 11_168_460 / 848_097
+const SWAP_CROSS_5_TICKS_BUDGET: (u64, u64) = (13_500_000, 1_020_000);
+// Measured: 17_709_410 / 1_190_349
+const SWAP_ACROSS_5_EMPTY_WORDS_BUDGET: (u64, u64) = (21_300_000, 1_430_000);
+// Extra cost of one tick crossing over an otherwise identical swap
+// Measured: 749_308 / 60_550
+const TICK_CROSSING_MARGINAL_BUDGET: (u64, u64) = (900_000, 73_000);
+// Measured: 1_956_940 / 267_830
+const MINT_BUDGET: (u64, u64) = (2_350_000, 322_000);
+// Measured: 1_327_071 / 150_258
+const BURN_BUDGET: (u64, u64) = (1_600_000, 181_000);
+// Measured: 622_789 / 106_837
+const COLLECT_BUDGET: (u64, u64) = (750_000, 129_000);
+
+/// Minimal factory answering the pool's pause check
+#[contract]
+struct StubFactory;
+
+#[contractimpl]
+impl StubFactory {
+    pub fn get_global_pause(_env: Env) -> bool {
+        false
+    }
+}
+
+struct Bench<'a> {
+    env: Env,
+    pool: DexPoolClient<'a>,
+    token0: Address,
+    token1: Address,
+}
+
+/// Pool at price 1 (0.3% tier) with SAC tokens and a wide base position
+fn setup<'a>() -> Bench<'a> {
+    let bench = setup_without_liquidity();
+
+    let lp = bench.funded_user();
+    bench.pool.mint(&lp, &lp, &-6000, &6000, &1_000_000_000);
+
+    bench
+}
+
+/// Pool at price 1 (0.3% tier) with SAC tokens and no positions
+fn setup_without_liquidity<'a>() -> Bench<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_a = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let token_b = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let (token0, token1) = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+
+    let contract_id = env.register(DexPool, ());
+    let pool = DexPoolClient::new(&env, &contract_id);
+    let factory = env.register(StubFactory, ());
+    pool.initialize(&factory, &token0, &token1, &3000, &60, &Q96);
+
+    Bench {
+        env,
+        pool,
+        token0,
+        token1,
+    }
+}
+
+impl Bench<'_> {
+    fn funded_user(&self) -> Address {
+        let user = Address::generate(&self.env);
+        StellarAssetClient::new(&self.env, &self.token0).mint(&user, &1_000_000_000_000);
+        StellarAssetClient::new(&self.env, &self.token1).mint(&user, &1_000_000_000_000);
+        user
+    }
+
+    /// Measure `f`, print a report line and assert it fits `budget`
+    fn measure<R>(&self, name: &str, budget: (u64, u64), f: impl FnOnce() -> R) -> R {
+        let mut cost = self.env.cost_estimate().budget();
+        cost.reset_default();
+
+        let result = f();
+
+        let cpu = cost.cpu_instruction_cost();
+        let mem = cost.memory_bytes_cost();
+        std::println!(
+            "[bench] {:<22} cpu {:>11} / {:>11}   mem {:>9} / {:>9}",
+            name,
+            cpu,
+            budget.0,
+            mem,
+            budget.1
+        );
+
+        assert!(cpu <= budget.0, "{} cpu {} over budget {}", name, cpu, budget.0);
+        assert!(mem <= budget.1, "{} mem {} over budget {}", name, mem, budget.1);
+        result
+    }
+}
+
+#[test]
+fn bench_swap_within_one_tick() {
+    let bench = setup();
+    let trader = bench.funded_user();
+
+    bench.measure("swap within tick", SWAP_WITHIN_TICK_BUDGET, || {
+        bench.pool.swap(&trader, &false, &1_000, &0, &false)
+    });
+    assert!(bench.pool.tick() < 60);
+}
+
+#[test]
+fn bench_swap_crossing_five_ticks() {
+    let bench = setup();
+    let lp = bench.funded_user();
+
+    // Initialized ticks at 60, 120, 180, 240, 300 (and 360, left uncrossed)
+    bench.pool.mint(&lp, &lp, &60, &120, &1_000_000);
+    bench.pool.mint(&lp, &lp, &180, &240, &1_000_000);
+    bench.pool.mint(&lp, &lp, &300, &360, &1_000_000);
+
+    let trader = bench.funded_user();
+    let limit = dex_math::get_sqrt_ratio_at_tick(&bench.env, 330);
+
+    bench.measure("swap crossing 5 ticks", SWAP_CROSS_5_TICKS_BUDGET, || {
+        bench.pool.swap(&trader, &false, &1_000_000_000, &limit, &false)
+    });
+
+    let tick = bench.pool.tick();
+    assert!((300..360).contains(&tick));
+}
+
+#[test]
+fn bench_tick_crossing_marginal_cost() {
+    // Two identical pools; only the second has ticks to cross at 60..=300
+    let plain = setup();
+    let ticked = setup();
+    let lp = ticked.funded_user();
+    ticked.pool.mint(&lp, &lp, &60, &120, &1_000_000);
+    ticked.pool.mint(&lp, &lp, &180, &240, &1_000_000);
+    ticked.pool.mint(&lp, &lp, &300, &360, &1_000_000);
+
+    let swap_to_330 = |bench: &Bench| {
+        let trader = bench.funded_user();
+        let limit = dex_math::get_sqrt_ratio_at_tick(&bench.env, 330);
+        let mut cost = bench.env.cost_estimate().budget();
+        cost.reset_default();
+        let result = bench.pool.swap(&trader, &false, &1_000_000_000, &limit, &false);
+        (result, cost.cpu_instruction_cost(), cost.memory_bytes_cost())
+    };
+    let (plain_result, plain_cpu, plain_mem) = swap_to_330(&plain);
+    let (ticked_result, ticked_cpu, ticked_mem) = swap_to_330(&ticked);
+    assert_eq!(plain_result.ticks_crossed, 0);
+    assert_eq!(ticked_result.ticks_crossed, 5);
+
+    // All five ticks share bitmap word 0, which the swap reads once
+    let cpu = ticked_cpu.saturating_sub(plain_cpu) / 5;
+    let mem = ticked_mem.saturating_sub(plain_mem) / 5;
+    let budget = TICK_CROSSING_MARGINAL_BUDGET;
+    std::println!(
+        "[bench] {:<22} cpu {:>11} / {:>11}   mem {:>9} / {:>9}",
+        "per tick crossed",
+        cpu,
+        budget.0,
+        mem,
+        budget.1
+    );
+    assert!(cpu <= budget.0, "tick crossing cpu {} over budget {}", cpu, budget.0);
+    assert!(mem <= budget.1, "tick crossing mem {} over budget {}", mem, budget.1);
+}
+
+#[test]
+fn bench_swap_across_five_empty_words() {
+    let bench = setup_without_liquidity();
+    let lp = bench.funded_user();
+
+    // The only liquidity sits 5 bitmap words (5 * 128 * 60 ticks) above the price
+    bench.pool.mint(&lp, &lp, &38400, &38460, &1_000_000);
+
+    let trader = bench.funded_user();
+    let result = bench.measure(
+        "swap across 5 empty words",
+        SWAP_ACROSS_5_EMPTY_WORDS_BUDGET,
+        || bench.pool.swap(&trader, &false, &1_000, &0, &false),
+    );
+
+    assert!(result.amount0 < 0);
+    assert!((38400..38460).contains(&bench.pool.tick()));
+}
+
+#[test]
+fn bench_mint() {
+    let bench = setup();
+    let lp = bench.funded_user();
+
+    bench.measure("mint", MINT_BUDGET, || {
+        bench.pool.mint(&lp, &lp, &-1200, &1200, &10_000_000)
+    });
+}
+
+#[test]
+fn bench_burn() {
+    let bench = setup();
+    let lp = bench.funded_user();
+    bench.pool.mint(&lp, &lp, &-1200, &1200, &10_000_000);
+
+    bench.measure("burn", BURN_BUDGET, || {
+        bench.pool.burn(&lp, &-1200, &1200, &10_000_000)
+    });
+}
+
+#[test]
+fn bench_collect() {
+    let bench = setup();
+    let lp = bench.funded_user();
+    bench.pool.mint(&lp, &lp, &-1200, &1200, &10_000_000);
+    bench.pool.burn(&lp, &-1200, &1200, &10_000_000);
+
+    let (amount0, amount1) = bench.measure("collect", COLLECT_BUDGET, || {
+        bench.pool.collect(&lp, &lp, &-1200, &1200, &u128::MAX, &u128::MAX)
+    });
+    assert!(amount0 > 0 && amount1 > 0);
+}
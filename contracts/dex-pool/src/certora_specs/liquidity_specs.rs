@@ -80,6 +80,7 @@ pub fn mint_increases_position_liquidity(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -133,6 +134,7 @@ pub fn mint_increases_position_liquidity_sanity(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -190,6 +192,7 @@ pub fn burn_decreases_position_liquidity(
     let _mint_amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         mint_amount,
@@ -203,6 +206,7 @@ pub fn burn_decreases_position_liquidity(
     // Now burn
     let _burn_amounts = DexPool::burn(
         env.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         burn_amount,
@@ -256,6 +260,7 @@ pub fn burn_bounded_by_position(
     let _mint_amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         mint_amount,
@@ -332,6 +337,7 @@ pub fn position_ticks_aligned(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -389,6 +395,7 @@ pub fn liquidity_net_balance(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -448,6 +455,7 @@ pub fn pool_liquidity_updates_for_active_position(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -505,6 +513,7 @@ pub fn pool_liquidity_updates_sanity(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -206,6 +206,9 @@ mod tests {
             fee_growth_global_1_x128: 0,
             protocol_fees_0: 0,
             protocol_fees_1: 0,
+            observation_index: 0,
+            observation_cardinality: 1,
+            observation_cardinality_next: 1,
         }
     }
 
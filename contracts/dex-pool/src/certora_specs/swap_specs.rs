@@ -79,6 +79,7 @@ pub fn zero_for_one_decreases_price(
         true, // zero_for_one
         amount_specified,
         sqrt_price_limit,
+        true,
     );
 
     // Capture state after
@@ -126,6 +127,7 @@ pub fn zero_for_one_decreases_price_sanity(
         true,
         amount_specified,
         sqrt_price_limit,
+        true,
     );
 
     // Verify this rule is not vacuously true
@@ -174,6 +176,7 @@ pub fn one_for_zero_increases_price(
         false, // one_for_zero
         amount_specified,
         sqrt_price_limit,
+        true,
     );
 
     let after = PoolSnapshot::capture(&env);
@@ -221,13 +224,15 @@ pub fn swap_amounts_opposite_signs(
         MAX_SQRT_RATIO - 1
     };
 
-    let (amount0, amount1) = DexPool::swap(
+    let result = DexPool::swap(
         env.clone(),
         recipient,
         zero_for_one,
         amount_specified,
         sqrt_price_limit,
+        true,
     );
+    let (amount0, amount1) = (result.amount0, result.amount1);
 
     // Valid swap: opposite signs, or one/both zero (no liquidity case)
     let valid = (amount0 > 0 && amount1 <= 0)
@@ -285,6 +290,7 @@ pub fn swap_respects_price_limit(
         zero_for_one,
         amount_specified,
         sqrt_price_limit,
+        true,
     );
 
     let after = PoolSnapshot::capture(&env);
@@ -341,6 +347,7 @@ pub fn tick_consistent_after_swap(
         zero_for_one,
         amount_specified,
         sqrt_price_limit,
+        true,
     );
 
     let after = PoolSnapshot::capture(&env);
@@ -401,6 +408,7 @@ pub fn fee_growth_monotonic_after_swap(
         zero_for_one,
         amount_specified,
         sqrt_price_limit,
+        true,
     );
 
     let after = PoolSnapshot::capture(&env);
@@ -410,6 +418,239 @@ pub fn fee_growth_monotonic_after_swap(
     cvlr_assert!(after.fee_growth_global_1 >= before.fee_growth_global_1);
 }
 
+// ============================================================================
+// PROTOCOL FEE RULES
+// ============================================================================
+
+/// RULE: The protocol fee split conserves the step fee
+/// protocol + lp == fee_amount, and the protocol never takes more than a quarter
+#[cfg(feature = "certora")]
+#[rule]
+pub fn protocol_fee_split_conserves_fee(fee_amount: u128, fee_protocol: u32) {
+    cvlr_assume!(fee_protocol == 0 || (fee_protocol >= 4 && fee_protocol <= 10));
+
+    let (protocol, lp) = crate::swap::split_protocol_fee(fee_amount, fee_protocol);
+
+    cvlr_assert!(protocol <= fee_amount);
+    cvlr_assert!(protocol + lp == fee_amount);
+    cvlr_assert!(protocol <= fee_amount / 4);
+}
+
+// ============================================================================
+// END-TO-END SWAP INVARIANT RULES
+// ============================================================================
+
+/// Initialize a pool at `sqrt_price_x96` and mint a nondeterministic amount of
+/// liquidity over a nondeterministic aligned range
+#[cfg(feature = "certora")]
+fn init_pool_with_liquidity(
+    env: &Env,
+    factory: &Address,
+    token0: &Address,
+    token1: &Address,
+    sqrt_price_x96: u128,
+    lp: &Address,
+) {
+    use cvlr::nondet::nondet;
+    use dex_types::{MAX_SQRT_RATIO, MAX_TICK, MIN_LIQUIDITY, MIN_SQRT_RATIO, MIN_TICK};
+
+    cvlr_assume!(token0 < token1);
+    cvlr_assume!(sqrt_price_x96 > MIN_SQRT_RATIO && sqrt_price_x96 < MAX_SQRT_RATIO);
+
+    let fee: u32 = 3000;
+    let tick_spacing: i32 = 60;
+    DexPool::initialize(
+        env.clone(),
+        factory.clone(),
+        token0.clone(),
+        token1.clone(),
+        fee,
+        tick_spacing,
+        sqrt_price_x96,
+    );
+
+    let tick_lower: i32 = nondet();
+    let tick_upper: i32 = nondet();
+    let liquidity: u128 = nondet();
+    cvlr_assume!(tick_lower >= MIN_TICK && tick_upper <= MAX_TICK);
+    cvlr_assume!(tick_lower < tick_upper);
+    cvlr_assume!(tick_lower % tick_spacing == 0);
+    cvlr_assume!(tick_upper % tick_spacing == 0);
+    cvlr_assume!(liquidity >= MIN_LIQUIDITY);
+
+    DexPool::mint(
+        env.clone(),
+        lp.clone(),
+        lp.clone(),
+        tick_lower,
+        tick_upper,
+        liquidity,
+    );
+}
+
+/// Assume `sqrt_price_limit` is in bounds and on the side the swap moves toward
+#[cfg(feature = "certora")]
+fn assume_valid_limit(zero_for_one: bool, sqrt_price_x96: u128, sqrt_price_limit: u128) {
+    use dex_types::{MAX_SQRT_RATIO, MIN_SQRT_RATIO};
+
+    cvlr_assume!(sqrt_price_limit > MIN_SQRT_RATIO && sqrt_price_limit < MAX_SQRT_RATIO);
+    if zero_for_one {
+        cvlr_assume!(sqrt_price_limit < sqrt_price_x96);
+    } else {
+        cvlr_assume!(sqrt_price_limit > sqrt_price_x96);
+    }
+}
+
+/// RULE: A swap against live liquidity moves the price the way it trades and
+/// stops at or before the caller's limit
+#[cfg(feature = "certora")]
+#[rule]
+pub fn swap_direction_and_limit_hold(
+    env: Env,
+    factory: Address,
+    token0: Address,
+    token1: Address,
+    sqrt_price_x96: u128,
+    lp: Address,
+    recipient: Address,
+    amount_specified: i128,
+    sqrt_price_limit: u128,
+    zero_for_one: bool,
+) {
+    use crate::invariants::{swap_direction_consistent, swap_respects_limit};
+
+    model::reset();
+    init_pool_with_liquidity(&env, &factory, &token0, &token1, sqrt_price_x96, &lp);
+    assume_valid_limit(zero_for_one, sqrt_price_x96, sqrt_price_limit);
+    cvlr_assume!(amount_specified != 0);
+
+    let before = PoolSnapshot::capture(&env);
+    model::set_last_swap_direction(zero_for_one);
+    DexPool::swap(
+        env.clone(),
+        recipient,
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit,
+        true,
+    );
+    let after = PoolSnapshot::capture(&env);
+
+    cvlr_assert!(swap_direction_consistent(
+        zero_for_one,
+        before.sqrt_price_x96,
+        after.sqrt_price_x96
+    ));
+    cvlr_assert!(swap_respects_limit(
+        zero_for_one,
+        after.sqrt_price_x96,
+        sqrt_price_limit
+    ));
+}
+
+/// SANITY: swap_direction_and_limit_hold is reachable with a price that moves
+#[cfg(feature = "certora")]
+#[rule]
+pub fn swap_direction_and_limit_hold_sanity(
+    env: Env,
+    factory: Address,
+    token0: Address,
+    token1: Address,
+    sqrt_price_x96: u128,
+    lp: Address,
+    recipient: Address,
+    amount_specified: i128,
+    sqrt_price_limit: u128,
+    zero_for_one: bool,
+) {
+    model::reset();
+    init_pool_with_liquidity(&env, &factory, &token0, &token1, sqrt_price_x96, &lp);
+    assume_valid_limit(zero_for_one, sqrt_price_x96, sqrt_price_limit);
+    cvlr_assume!(amount_specified != 0);
+
+    let before = PoolSnapshot::capture(&env);
+    DexPool::swap(
+        env.clone(),
+        recipient,
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit,
+        true,
+    );
+    let after = PoolSnapshot::capture(&env);
+
+    cvlr_satisfy!(after.sqrt_price_x96 != before.sqrt_price_x96);
+}
+
+/// RULE: Price and tick stay within the supported range after a swap
+#[cfg(feature = "certora")]
+#[rule]
+pub fn swap_post_state_in_bounds(
+    env: Env,
+    factory: Address,
+    token0: Address,
+    token1: Address,
+    sqrt_price_x96: u128,
+    lp: Address,
+    recipient: Address,
+    amount_specified: i128,
+    sqrt_price_limit: u128,
+    zero_for_one: bool,
+) {
+    use crate::invariants::{price_in_bounds, tick_in_bounds};
+
+    model::reset();
+    init_pool_with_liquidity(&env, &factory, &token0, &token1, sqrt_price_x96, &lp);
+    assume_valid_limit(zero_for_one, sqrt_price_x96, sqrt_price_limit);
+    cvlr_assume!(amount_specified != 0);
+
+    DexPool::swap(
+        env.clone(),
+        recipient,
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit,
+        true,
+    );
+    let state = DexPool::get_state(env.clone());
+
+    cvlr_assert!(tick_in_bounds(&state));
+    cvlr_assert!(price_in_bounds(&state));
+}
+
+/// SANITY: swap_post_state_in_bounds is reachable with a price that moves
+#[cfg(feature = "certora")]
+#[rule]
+pub fn swap_post_state_in_bounds_sanity(
+    env: Env,
+    factory: Address,
+    token0: Address,
+    token1: Address,
+    sqrt_price_x96: u128,
+    lp: Address,
+    recipient: Address,
+    amount_specified: i128,
+    sqrt_price_limit: u128,
+    zero_for_one: bool,
+) {
+    model::reset();
+    init_pool_with_liquidity(&env, &factory, &token0, &token1, sqrt_price_x96, &lp);
+    assume_valid_limit(zero_for_one, sqrt_price_x96, sqrt_price_limit);
+    cvlr_assume!(amount_specified != 0);
+
+    DexPool::swap(
+        env.clone(),
+        recipient,
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit,
+        true,
+    );
+    let state = DexPool::get_state(env.clone());
+
+    cvlr_satisfy!(state.sqrt_price_x96 != sqrt_price_x96);
+}
+
 // ============================================================================
 // UNIT TESTS
 // ============================================================================
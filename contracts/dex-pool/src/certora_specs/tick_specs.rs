@@ -127,6 +127,7 @@ pub fn liquidity_net_bounded_by_gross(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -193,6 +194,7 @@ pub fn initialized_tick_has_liquidity(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -321,6 +323,7 @@ pub fn lower_tick_has_positive_net(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -373,6 +376,7 @@ pub fn upper_tick_has_negative_net(
     let _amounts = DexPool::mint(
         env.clone(),
         recipient.clone(),
+        recipient.clone(),
         tick_lower,
         tick_upper,
         liquidity_amount,
@@ -417,6 +421,9 @@ mod tests {
             liquidity_net: 500,
             fee_growth_outside_0_x128: 0,
             fee_growth_outside_1_x128: 0,
+            tick_cumulative_outside: 0,
+            seconds_per_liq_outside_x128: 0,
+            seconds_outside: 0,
             initialized: true,
         };
 
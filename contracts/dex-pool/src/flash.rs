@@ -0,0 +1,126 @@
+// ============================================================================
+// FLASH MODULE - Borrow pool inventory within one invocation
+// ============================================================================
+//
+// flash() lends token0/token1 to a recipient, calls `dex_flash_callback` on
+// the borrower contract, and requires the pool's balances to have grown by
+// at least the fee by the time the callback returns. Everything paid above
+// the loan is credited to LPs through fee growth (minus the protocol share).
+//
+// Callback interface implemented by borrowers:
+//   fn dex_flash_callback(env, fee0: i128, fee1: i128, data: Bytes)
+//
+// ============================================================================
+
+use crate::storage::{adjust_reserves, get_config, get_fee_protocol, get_state, set_state};
+use crate::swap::{compute_fee_growth_delta, split_protocol_fee};
+use dex_types::PoolError;
+use soroban_sdk::{panic_with_error, token, Address, Bytes, Env, IntoVal, Symbol};
+
+/// Fee owed on a flash loan, rounded up in the pool's favour (pure)
+pub fn compute_flash_fee(env: &Env, amount: i128, fee_pips: u32) -> i128 {
+    dex_math::mul_div_rounding_up(env, amount as u128, fee_pips as u128, 1_000_000) as i128
+}
+
+/// Execute a flash loan
+///
+/// # Returns
+/// (paid0, paid1) - Amounts repaid above the loan, i.e. the fees collected
+pub fn execute_flash(
+    env: &Env,
+    recipient: Address,
+    amount0: i128,
+    amount1: i128,
+    callback_contract: Address,
+    callback_data: Bytes,
+) -> (u128, u128) {
+    let config = get_config(env);
+    let state = get_state(env);
+
+    if !dex_types::is_valid_recipient(
+        env,
+        &recipient,
+        &[&config.token0, &config.token1, &config.factory],
+    ) {
+        panic_with_error!(env, PoolError::InvalidRecipient);
+    }
+
+    if amount0 < 0 || amount1 < 0 {
+        panic_with_error!(env, PoolError::InvalidFlashAmount);
+    }
+    if state.liquidity == 0 {
+        panic_with_error!(env, PoolError::NoLiquidity);
+    }
+
+    let fee0 = compute_flash_fee(env, amount0, config.fee);
+    let fee1 = compute_flash_fee(env, amount1, config.fee);
+
+    let contract_address = env.current_contract_address();
+    let token0_client = token::Client::new(env, &config.token0);
+    let token1_client = token::Client::new(env, &config.token1);
+    let balance0_before = token0_client.balance(&contract_address);
+    let balance1_before = token1_client.balance(&contract_address);
+
+    if amount0 > 0 {
+        token0_client.transfer(&contract_address, &recipient, &amount0);
+    }
+    if amount1 > 0 {
+        token1_client.transfer(&contract_address, &recipient, &amount1);
+    }
+
+    env.invoke_contract::<()>(
+        &callback_contract,
+        &Symbol::new(env, "dex_flash_callback"),
+        (fee0, fee1, callback_data).into_val(env),
+    );
+
+    let balance0_after = token0_client.balance(&contract_address);
+    let balance1_after = token1_client.balance(&contract_address);
+    if balance0_after < balance0_before + fee0 {
+        panic_with_error!(env, PoolError::FlashRepaymentShort);
+    }
+    if balance1_after < balance1_before + fee1 {
+        panic_with_error!(env, PoolError::FlashRepaymentShort);
+    }
+
+    let paid0 = (balance0_after - balance0_before) as u128;
+    let paid1 = (balance1_after - balance1_before) as u128;
+
+    // Credit fees to LPs (liquidity can't change during the callback)
+    let (fee_protocol_0, fee_protocol_1) = get_fee_protocol(env);
+    let (protocol0, lp0) = split_protocol_fee(paid0, fee_protocol_0);
+    let (protocol1, lp1) = split_protocol_fee(paid1, fee_protocol_1);
+
+    let mut state = get_state(env);
+    state.fee_growth_global_0_x128 = state
+        .fee_growth_global_0_x128
+        .wrapping_add(compute_fee_growth_delta(env, lp0, state.liquidity));
+    state.fee_growth_global_1_x128 = state
+        .fee_growth_global_1_x128
+        .wrapping_add(compute_fee_growth_delta(env, lp1, state.liquidity));
+    state.protocol_fees_0 += protocol0 as i128;
+    state.protocol_fees_1 += protocol1 as i128;
+    set_state(env, &state);
+
+    adjust_reserves(env, paid0 as i128, paid1 as i128);
+
+    env.events().publish(
+        (Symbol::new(env, "flash"),),
+        (recipient, amount0, amount1, paid0, paid1),
+    );
+
+    (paid0, paid1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_flash_fee_rounds_up() {
+        let env = Env::default();
+        assert_eq!(compute_flash_fee(&env, 1_000_000, 3000), 3000);
+        assert_eq!(compute_flash_fee(&env, 1, 3000), 1);
+        assert_eq!(compute_flash_fee(&env, 0, 3000), 0);
+    }
+}
@@ -178,6 +178,17 @@ pub fn swap_amounts_opposite_signs(amount0: i128, amount1: i128) -> bool {
     (amount0 > 0 && amount1 < 0) || (amount0 < 0 && amount1 > 0) || (amount0 == 0 || amount1 == 0)
 }
 
+/// Invariant: pool holds at least what its accounting says it owes
+///
+/// Property:
+///   token.balance(pool) >= reserve
+///
+/// The reserve counters track tokens added by mint and swaps minus tokens
+/// paid out, so any shortfall means value left the pool unaccounted.
+pub fn pool_solvent(balance: i128, reserve: u128) -> bool {
+    balance >= 0 && balance as u128 >= reserve
+}
+
 // ============================================================================
 // TICK INVARIANTS
 // ============================================================================
@@ -305,6 +316,9 @@ mod tests {
             fee_growth_global_1_x128: 0,
             protocol_fees_0: 0,
             protocol_fees_1: 0,
+            observation_index: 0,
+            observation_cardinality: 1,
+            observation_cardinality_next: 1,
         };
         assert!(price_in_bounds(&state));
     }
@@ -319,6 +333,9 @@ mod tests {
             fee_growth_global_1_x128: 0,
             protocol_fees_0: 0,
             protocol_fees_1: 0,
+            observation_index: 0,
+            observation_cardinality: 1,
+            observation_cardinality_next: 1,
         };
         assert!(!price_in_bounds(&state)); // MIN_SQRT_RATIO is not valid (needs to be >)
     }
@@ -333,6 +350,9 @@ mod tests {
             fee_growth_global_1_x128: 0,
             protocol_fees_0: 0,
             protocol_fees_1: 0,
+            observation_index: 0,
+            observation_cardinality: 1,
+            observation_cardinality_next: 1,
         };
         assert!(tick_in_bounds(&state));
     }
@@ -424,4 +444,12 @@ mod tests {
         assert!(!swap_amounts_opposite_signs(100, 100)); // Both positive
         assert!(!swap_amounts_opposite_signs(-100, -100)); // Both negative
     }
+
+    #[test]
+    fn test_pool_solvent() {
+        assert!(pool_solvent(100, 100)); // Exactly backed
+        assert!(pool_solvent(150, 100)); // Donated excess
+        assert!(!pool_solvent(99, 100)); // Shortfall
+        assert!(!pool_solvent(-1, 0)); // Impossible balance
+    }
 }
@@ -1,7 +1,9 @@
 #![no_std]
 
+mod flash;
 mod invariants;
 mod liquidity;
+mod oracle;
 mod storage;
 mod swap;
 mod tick;
@@ -9,11 +11,22 @@ mod tick;
 #[cfg(any(test, feature = "certora"))]
 mod certora_specs;
 
-use dex_types::{PoolConfig, PoolState, PositionKey, PositionInfo, TickInfo};
-use soroban_sdk::{contract, contractimpl, token, Address, Env};
+#[cfg(test)]
+mod benchmarks;
+
+use dex_types::{
+    Observation, PoolConfig, PoolError, PoolState, PopulatedTickPage, PopulatedTickRange,
+    PositionKey, PositionInfo, PriceCheckpoint, Slot0, SwapResult, SwapSimulation, TickInfo,
+};
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, token, Address, Bytes, BytesN, Env, IntoVal, Symbol,
+    Vec,
+};
 use storage::{
-    get_config, get_position, get_state, get_tick, set_config, set_position, set_state, set_tick,
-    DataKey,
+    get_config, get_fee_protocol, get_paused, get_position, get_reserves, get_state,
+    get_storage_version, get_tick, get_tick_bitmap_word, is_position_operator, lock, set_config,
+    set_fee_protocol, set_paused, set_position, set_position_operator, set_reserves, set_state,
+    set_storage_version, set_tick, unlock, DataKey, CURRENT_STORAGE_VERSION,
 };
 
 #[contract]
@@ -33,12 +46,25 @@ impl DexPool {
     ) {
         // Ensure not already initialized
         if env.storage().instance().has(&DataKey::Config) {
-            panic!("Already initialized");
+            panic_with_error!(&env, PoolError::AlreadyInitialized);
         }
 
         // Validate token ordering
         if token0 >= token1 {
-            panic!("token0 must be less than token1");
+            panic_with_error!(&env, PoolError::InvalidTokenOrder);
+        }
+
+        // Validate parameters before any storage writes
+        if sqrt_price_x96 <= dex_types::MIN_SQRT_RATIO
+            || sqrt_price_x96 >= dex_types::MAX_SQRT_RATIO
+        {
+            panic_with_error!(&env, PoolError::InvalidSqrtPrice);
+        }
+        if fee >= 1_000_000 {
+            panic_with_error!(&env, PoolError::InvalidFee);
+        }
+        if tick_spacing <= 0 {
+            panic_with_error!(&env, PoolError::InvalidTickSpacing);
         }
 
         // Calculate initial tick from sqrt price
@@ -58,281 +84,928 @@ impl DexPool {
         // Store initial state
         let state = PoolState::new(sqrt_price_x96, tick);
         set_state(&env, &state);
+
+        // Start solvency counters at zero
+        set_reserves(&env, &(0, 0));
+
+        // New pools start on the current storage layout
+        set_storage_version(&env, CURRENT_STORAGE_VERSION);
+
+        // First oracle observation
+        oracle::initialize(&env, env.ledger().timestamp());
+
+        env.events().publish(
+            (Symbol::new(&env, "initialized"),),
+            (
+                config.token0,
+                config.token1,
+                fee,
+                tick_spacing,
+                sqrt_price_x96,
+                tick,
+            ),
+        );
     }
 
     /// Execute a swap
     ///
     /// # Arguments
-    /// * `recipient` - Address to receive output tokens
+    /// * `recipient` - Address that pays the input and receives the output (must authorize)
     /// * `zero_for_one` - True if swapping token0 for token1
     /// * `amount_specified` - Positive for exact input, negative for exact output
     /// * `sqrt_price_limit_x96` - Price limit for the swap
+    /// * `partial_fill_allowed` - Return a partial fill instead of panicking
+    ///   when the swap hits the tick crossing cap
     ///
     /// # Returns
-    /// (amount0, amount1) - Negative values are amounts paid out
+    /// SwapResult - Amounts (negative values are paid out), final price, fees and ticks crossed
     pub fn swap(
         env: Env,
         recipient: Address,
         zero_for_one: bool,
         amount_specified: i128,
         sqrt_price_limit_x96: u128,
-    ) -> (i128, i128) {
-        swap::execute_swap(&env, recipient, zero_for_one, amount_specified, sqrt_price_limit_x96)
+        partial_fill_allowed: bool,
+    ) -> SwapResult {
+        recipient.require_auth();
+        lock(&env);
+        let result = swap::execute_swap(
+            &env,
+            recipient,
+            zero_for_one,
+            amount_specified,
+            sqrt_price_limit_x96,
+            partial_fill_allowed,
+        );
+        unlock(&env);
+        result
+    }
+
+    /// Execute a swap, returning swap failures as a `PoolError` instead of panicking
+    ///
+    /// Same arguments and results as `swap`. Invalid amounts and price limits,
+    /// pauses, missing liquidity, the tick crossing cap and unfilled exact
+    /// outputs come back as `Err`, and the invocation's changes are rolled back.
+    /// (Named `checked_swap` since clients already generate `try_swap` for `swap`.)
+    pub fn checked_swap(
+        env: Env,
+        recipient: Address,
+        zero_for_one: bool,
+        amount_specified: i128,
+        sqrt_price_limit_x96: u128,
+        partial_fill_allowed: bool,
+    ) -> Result<SwapResult, PoolError> {
+        recipient.require_auth();
+        lock(&env);
+        let result = swap::try_execute_swap(
+            &env,
+            recipient,
+            zero_for_one,
+            amount_specified,
+            sqrt_price_limit_x96,
+            partial_fill_allowed,
+        );
+        unlock(&env);
+        result
     }
 
-    /// Add liquidity to a position
+    /// Add liquidity to a position owned by `recipient`, paid for by `payer`
     ///
     /// # Returns
     /// (amount0, amount1) - Token amounts deposited
     pub fn mint(
         env: Env,
+        payer: Address,
         recipient: Address,
         tick_lower: i32,
         tick_upper: i32,
         amount: u128,
     ) -> (u128, u128) {
-        recipient.require_auth();
-        liquidity::mint(&env, recipient, tick_lower, tick_upper, amount)
+        payer.require_auth();
+        lock(&env);
+        let result = liquidity::mint(&env, payer, recipient, tick_lower, tick_upper, amount);
+        unlock(&env);
+        result
     }
 
-    /// Remove liquidity from a position
+    /// Remove liquidity from `owner`'s position
+    ///
+    /// Burning 0 pokes the position, crediting the fees it has earned to
+    /// `tokens_owed` so a following `collect` can withdraw them. Positions
+    /// without liquidity cannot be poked.
     ///
     /// # Returns
     /// (amount0, amount1) - Token amounts withdrawn
-    pub fn burn(env: Env, tick_lower: i32, tick_upper: i32, amount: u128) -> (u128, u128) {
-        liquidity::burn(&env, tick_lower, tick_upper, amount)
+    pub fn burn(
+        env: Env,
+        owner: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount: u128,
+    ) -> (u128, u128) {
+        owner.require_auth();
+        lock(&env);
+        let result = liquidity::burn(&env, owner, tick_lower, tick_upper, amount);
+        unlock(&env);
+        result
     }
 
-    /// Collect accumulated fees from a position
+    /// Collect accumulated fees from `owner`'s position, sending them to `recipient`
     ///
     /// # Returns
     /// (amount0, amount1) - Fee amounts collected
     pub fn collect(
         env: Env,
+        owner: Address,
         recipient: Address,
         tick_lower: i32,
         tick_upper: i32,
         amount0_requested: u128,
         amount1_requested: u128,
     ) -> (u128, u128) {
-        liquidity::collect(&env, recipient, tick_lower, tick_upper, amount0_requested, amount1_requested)
-    }
-
-    // === View Functions ===
-
-    /// Get current pool state
-    pub fn get_state(env: Env) -> PoolState {
-        get_state(&env)
+        owner.require_auth();
+        lock(&env);
+        let result = liquidity::collect(
+            &env,
+            owner,
+            recipient,
+            tick_lower,
+            tick_upper,
+            amount0_requested,
+            amount1_requested,
+        );
+        unlock(&env);
+        result
     }
 
-    /// Get pool configuration
-    pub fn get_config(env: Env) -> PoolConfig {
-        get_config(&env)
-    }
+    /// Approve or revoke `operator` to burn and collect `owner`'s position
+    ///
+    /// An approved operator calls `operator_burn` and `operator_collect`
+    /// instead of `burn` and `collect`; the approval covers this one range.
+    pub fn approve_position_operator(
+        env: Env,
+        owner: Address,
+        operator: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+        approved: bool,
+    ) {
+        owner.require_auth();
+        let key = PositionKey {
+            owner: owner.clone(),
+            tick_lower,
+            tick_upper,
+        };
+        set_position_operator(&env, &key, &operator, approved);
 
-    /// Get tick info
-    pub fn get_tick(env: Env, tick: i32) -> TickInfo {
-        get_tick(&env, tick)
+        env.events().publish(
+            (Symbol::new(&env, "approve_operator"), owner, operator),
+            (tick_lower, tick_upper, approved),
+        );
     }
 
-    /// Get position info
-    pub fn get_position(
+    /// Whether `operator` is approved to burn and collect `owner`'s position
+    pub fn is_position_operator(
         env: Env,
         owner: Address,
+        operator: Address,
         tick_lower: i32,
         tick_upper: i32,
-    ) -> PositionInfo {
+    ) -> bool {
         let key = PositionKey {
             owner,
             tick_lower,
             tick_upper,
         };
-        get_position(&env, &key)
+        is_position_operator(&env, &key, &operator)
     }
 
-    /// Get current sqrt price
-    pub fn sqrt_price_x96(env: Env) -> u128 {
-        get_state(&env).sqrt_price_x96
+    /// Remove liquidity from `owner`'s position as an approved operator
+    ///
+    /// # Returns
+    /// (amount0, amount1) - Token amounts withdrawn
+    pub fn operator_burn(
+        env: Env,
+        operator: Address,
+        owner: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount: u128,
+    ) -> (u128, u128) {
+        require_position_operator(&env, &operator, &owner, tick_lower, tick_upper);
+        lock(&env);
+        let result = liquidity::burn(&env, owner, tick_lower, tick_upper, amount);
+        unlock(&env);
+        result
     }
 
-    /// Get current tick
-    pub fn tick(env: Env) -> i32 {
-        get_state(&env).tick
+    /// Collect fees from `owner`'s position as an approved operator, sending them to `recipient`
+    ///
+    /// # Returns
+    /// (amount0, amount1) - Fee amounts collected
+    pub fn operator_collect(
+        env: Env,
+        operator: Address,
+        owner: Address,
+        recipient: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount0_requested: u128,
+        amount1_requested: u128,
+    ) -> (u128, u128) {
+        require_position_operator(&env, &operator, &owner, tick_lower, tick_upper);
+        lock(&env);
+        let result = liquidity::collect(
+            &env,
+            owner,
+            recipient,
+            tick_lower,
+            tick_upper,
+            amount0_requested,
+            amount1_requested,
+        );
+        unlock(&env);
+        result
     }
 
-    /// Get current liquidity
-    pub fn liquidity(env: Env) -> u128 {
-        get_state(&env).liquidity
+    /// Borrow pool tokens, repaying them plus the pool fee within the callback
+    ///
+    /// Transfers the amounts to `recipient`, then calls
+    /// `dex_flash_callback(fee0, fee1, callback_data)` on `callback_contract`.
+    /// Reverts unless the pool's balances grew by at least the fees.
+    ///
+    /// # Returns
+    /// (paid0, paid1) - Amounts repaid above the loan, credited as fees
+    pub fn flash(
+        env: Env,
+        recipient: Address,
+        amount0: i128,
+        amount1: i128,
+        callback_contract: Address,
+        callback_data: Bytes,
+    ) -> (u128, u128) {
+        lock(&env);
+        let result = flash::execute_flash(
+            &env,
+            recipient,
+            amount0,
+            amount1,
+            callback_contract,
+            callback_data,
+        );
+        unlock(&env);
+        result
     }
 
-    /// Get token0 address
-    pub fn token0(env: Env) -> Address {
-        get_config(&env).token0
-    }
+    /// Distribute tokens to in-range liquidity as if they were swap fees
+    ///
+    /// Pulls the amounts from `donor` and credits them to fee growth, so
+    /// positions in range at the current price earn them pro rata.
+    /// Panics if no liquidity is in range.
+    pub fn donate(env: Env, donor: Address, amount0: u128, amount1: u128) {
+        donor.require_auth();
+        lock(&env);
 
-    /// Get token1 address
-    pub fn token1(env: Env) -> Address {
-        get_config(&env).token1
-    }
+        let config = get_config(&env);
+        let mut state = get_state(&env);
+        if state.liquidity == 0 {
+            panic_with_error!(&env, PoolError::NoLiquidity);
+        }
 
-    /// Get fee
-    pub fn fee(env: Env) -> u32 {
-        get_config(&env).fee
-    }
+        // Credit fee growth only once each donation has arrived in full
+        if amount0 > 0 {
+            pull_donation(&env, &config.token0, &donor, amount0);
+            state.fee_growth_global_0_x128 = state
+                .fee_growth_global_0_x128
+                .wrapping_add(dex_math::div_shl_128(&env, amount0, state.liquidity));
+        }
+        if amount1 > 0 {
+            pull_donation(&env, &config.token1, &donor, amount1);
+            state.fee_growth_global_1_x128 = state
+                .fee_growth_global_1_x128
+                .wrapping_add(dex_math::div_shl_128(&env, amount1, state.liquidity));
+        }
+        set_state(&env, &state);
 
-    /// Get tick spacing
-    pub fn tick_spacing(env: Env) -> i32 {
-        get_config(&env).tick_spacing
+        storage::adjust_reserves(&env, amount0 as i128, amount1 as i128);
+
+        env.events().publish(
+            (Symbol::new(&env, "donate"),),
+            (donor, amount0, amount1),
+        );
+        unlock(&env);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use dex_types::Q96;
-    use soroban_sdk::testutils::Address as _;
-    use soroban_sdk::{Address, Env};
+    /// Withdraw accumulated protocol fees
+    ///
+    /// Only the factory's fee recipient may call this.
+    ///
+    /// # Returns
+    /// (amount0, amount1) - Protocol fee amounts transferred
+    pub fn collect_protocol(
+        env: Env,
+        recipient: Address,
+        amount0_requested: u128,
+        amount1_requested: u128,
+    ) -> (u128, u128) {
+        let config = get_config(&env);
 
-    #[allow(dead_code)]
-    fn setup_pool(env: &Env) -> (Address, Address, Address, Address) {
-        let factory = Address::generate(env);
-        let token0 = Address::generate(env);
-        let token1 = Address::generate(env);
+        let fee_recipient: Option<Address> = env.invoke_contract(
+            &config.factory,
+            &Symbol::new(&env, "get_fee_recipient"),
+            ().into_val(&env),
+        );
+        fee_recipient
+            .unwrap_or_else(|| panic_with_error!(&env, PoolError::FeeRecipientNotSet))
+            .require_auth();
 
-        // Ensure token0 < token1
-        let (t0, t1) = if token0 < token1 {
-            (token0, token1)
-        } else {
-            (token1, token0)
-        };
+        if !dex_types::is_valid_recipient(
+            &env,
+            &recipient,
+            &[&config.token0, &config.token1, &config.factory],
+        ) {
+            panic_with_error!(&env, PoolError::InvalidRecipient);
+        }
 
-        let contract_id = env.register(DexPool, ());
+        let mut state = get_state(&env);
+        let amount0 = amount0_requested.min(state.protocol_fees_0 as u128);
+        let amount1 = amount1_requested.min(state.protocol_fees_1 as u128);
 
-        (t0, t1, factory, contract_id)
-    }
+        state.protocol_fees_0 -= amount0 as i128;
+        state.protocol_fees_1 -= amount1 as i128;
+        set_state(&env, &state);
 
-    #[allow(dead_code)]
-    fn init_pool(env: &Env, contract_id: &Address, factory: &Address, token0: &Address, token1: &Address) {
-        let client = DexPoolClient::new(env, contract_id);
-        client.initialize(factory, token0, token1, &3000u32, &60i32, &Q96);
+        let contract_address = env.current_contract_address();
+        if amount0 > 0 {
+            token::Client::new(&env, &config.token0).transfer(
+                &contract_address,
+                &recipient,
+                &(amount0 as i128),
+            );
+        }
+        if amount1 > 0 {
+            token::Client::new(&env, &config.token1).transfer(
+                &contract_address,
+                &recipient,
+                &(amount1 as i128),
+            );
+        }
+
+        storage::adjust_reserves(&env, -(amount0 as i128), -(amount1 as i128));
+
+        env.events().publish(
+            (Symbol::new(&env, "collect_protocol"),),
+            (recipient, amount0, amount1),
+        );
+
+        (amount0, amount1)
     }
 
-    // === Initialization Tests ===
+    /// Sweep token balances above the accounted reserves to `recipient`
+    ///
+    /// Only the factory may call this, passing its fee recipient (the pool can't
+    /// call back into the factory mid-relay). Refuses to run on pools whose
+    /// solvency counters were never enabled, since excess can't be measured.
+    ///
+    /// # Returns
+    /// (amount0, amount1) - Excess amounts transferred
+    pub fn skim(env: Env, recipient: Address) -> (u128, u128) {
+        let config = get_config(&env);
+        config.factory.require_auth();
 
-    #[test]
-    fn test_initialize_pool() {
-        let env = Env::default();
-        let factory = Address::generate(&env);
-        let token0 = Address::generate(&env);
-        let token1 = Address::generate(&env);
+        let (reserve0, reserve1) = get_reserves(&env)
+            .unwrap_or_else(|| panic_with_error!(&env, PoolError::SolvencyCountersNotEnabled));
 
-        let (t0, t1) = if token0 < token1 {
-            (token0, token1)
-        } else {
-            (token1, token0)
-        };
+        let contract_address = env.current_contract_address();
+        let token0_client = token::Client::new(&env, &config.token0);
+        let token1_client = token::Client::new(&env, &config.token1);
 
-        let contract_id = env.register(DexPool, ());
-        let client = DexPoolClient::new(&env, &contract_id);
+        let excess0 = (token0_client.balance(&contract_address) as u128).saturating_sub(reserve0);
+        let excess1 = (token1_client.balance(&contract_address) as u128).saturating_sub(reserve1);
 
-        // Initialize pool
-        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
+        if excess0 > 0 {
+            token0_client.transfer(&contract_address, &recipient, &(excess0 as i128));
+        }
+        if excess1 > 0 {
+            token1_client.transfer(&contract_address, &recipient, &(excess1 as i128));
+        }
 
-        // Verify state
-        let state = client.get_state();
-        assert_eq!(state.sqrt_price_x96, Q96);
-        assert_eq!(state.tick, 0);
-        assert_eq!(state.liquidity, 0);
+        env.events().publish(
+            (Symbol::new(&env, "skim"),),
+            (recipient, excess0, excess1),
+        );
 
-        // Verify config
-        let config = client.get_config();
-        assert_eq!(config.factory, factory);
-        assert_eq!(config.token0, t0);
-        assert_eq!(config.token1, t1);
-        assert_eq!(config.fee, 3000);
-        assert_eq!(config.tick_spacing, 60);
+        (excess0, excess1)
     }
 
-    #[test]
-    #[should_panic(expected = "Already initialized")]
-    fn test_initialize_twice_fails() {
-        let env = Env::default();
-        let factory = Address::generate(&env);
-        let token0 = Address::generate(&env);
-        let token1 = Address::generate(&env);
+    /// Set the protocol's share of swap fees for each token
+    ///
+    /// Only the factory may call this. Each value is 0 (off) or a
+    /// denominator in 4..=10, so the protocol takes 1/N of the fee.
+    pub fn set_fee_protocol(env: Env, fee_protocol_0: u32, fee_protocol_1: u32) {
+        let config = get_config(&env);
+        config.factory.require_auth();
 
-        let (t0, t1) = if token0 < token1 {
-            (token0, token1)
-        } else {
-            (token1, token0)
-        };
+        if !valid_fee_protocol(fee_protocol_0) || !valid_fee_protocol(fee_protocol_1) {
+            panic_with_error!(&env, PoolError::InvalidFeeProtocol);
+        }
 
-        let contract_id = env.register(DexPool, ());
-        let client = DexPoolClient::new(&env, &contract_id);
+        let old = get_fee_protocol(&env);
+        set_fee_protocol(&env, &(fee_protocol_0, fee_protocol_1));
 
-        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
-        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
+        env.events().publish(
+            (Symbol::new(&env, "set_fee_protocol"),),
+            (old.0, old.1, fee_protocol_0, fee_protocol_1),
+        );
     }
 
-    #[test]
-    #[should_panic(expected = "token0 must be less than token1")]
-    fn test_initialize_wrong_token_order() {
-        let env = Env::default();
-        let factory = Address::generate(&env);
-        let token0 = Address::generate(&env);
-        let token1 = Address::generate(&env);
-
-        let (t0, t1) = if token0 < token1 {
-            (token0, token1)
-        } else {
-            (token1, token0)
-        };
+    /// Pause or resume swaps in this pool
+    ///
+    /// Only the factory may call this. Mint, burn and collect stay available
+    /// so LPs can always exit.
+    pub fn set_paused(env: Env, paused: bool) {
+        let config = get_config(&env);
+        config.factory.require_auth();
 
-        let contract_id = env.register(DexPool, ());
-        let client = DexPoolClient::new(&env, &contract_id);
+        set_paused(&env, paused);
 
-        // Pass tokens in wrong order
-        client.initialize(&factory, &t1, &t0, &3000u32, &60i32, &Q96);
+        env.events().publish((Symbol::new(&env, "set_paused"),), (paused,));
     }
 
-    // === View Function Tests ===
+    /// Whether swaps are paused in this pool
+    pub fn is_paused(env: Env) -> bool {
+        get_paused(&env)
+    }
 
-    #[test]
-    fn test_view_functions() {
-        let env = Env::default();
-        let factory = Address::generate(&env);
-        let token0 = Address::generate(&env);
-        let token1 = Address::generate(&env);
+    /// Replace this pool's code with an uploaded WASM
+    ///
+    /// Only the factory may call this. Storage is left untouched; the new code
+    /// takes effect after this call returns, so follow up with `migrate`.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        let config = get_config(&env);
+        config.factory.require_auth();
 
-        let (t0, t1) = if token0 < token1 {
-            (token0, token1)
-        } else {
-            (token1, token0)
-        };
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
 
-        let contract_id = env.register(DexPool, ());
-        let client = DexPoolClient::new(&env, &contract_id);
+        env.events()
+            .publish((Symbol::new(&env, "upgraded"),), (new_wasm_hash,));
+    }
 
-        let initial_price = Q96 * 2; // Price = 4 (sqrt = 2)
-        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &initial_price);
+    /// Bring storage up to the layout this code expects
+    ///
+    /// Only the factory may call this. Runs every step from the recorded
+    /// storage version to `CURRENT_STORAGE_VERSION`, and is a no-op when the
+    /// pool is already current.
+    ///
+    /// # Returns
+    /// The storage version after migration
+    pub fn migrate(env: Env) -> u32 {
+        let config = get_config(&env);
+        config.factory.require_auth();
 
-        // Test individual view functions
-        assert_eq!(client.sqrt_price_x96(), initial_price);
-        assert!(client.tick() > 0); // Price > 1 means tick > 0
-        assert_eq!(client.liquidity(), 0);
-        assert_eq!(client.token0(), t0);
-        assert_eq!(client.token1(), t1);
-        assert_eq!(client.fee(), 3000);
-        assert_eq!(client.tick_spacing(), 60);
+        let from = get_storage_version(&env);
+        if from > CURRENT_STORAGE_VERSION {
+            panic_with_error!(&env, PoolError::StorageVersionAhead);
+        }
+        if from == CURRENT_STORAGE_VERSION {
+            return from;
+        }
+
+        // v0 -> v1: pools created before versioning already use the v1 layout,
+        // only the version marker is missing. Ticks written before the oracle
+        // outside values are upgraded lazily by get_tick instead, since
+        // rewriting every tick here would not fit in one transaction.
+        set_storage_version(&env, CURRENT_STORAGE_VERSION);
+
+        env.events().publish(
+            (Symbol::new(&env, "migrate"),),
+            (from, CURRENT_STORAGE_VERSION),
+        );
+        CURRENT_STORAGE_VERSION
     }
 
-    #[test]
-    fn test_get_tick_uninitialized() {
-        let env = Env::default();
-        let factory = Address::generate(&env);
-        let token0 = Address::generate(&env);
-        let token1 = Address::generate(&env);
+    /// Get the storage layout version (0 for pools created before versioning)
+    pub fn storage_version(env: Env) -> u32 {
+        get_storage_version(&env)
+    }
+
+    /// Pre-allocate oracle slots so the buffer can hold `n` observations
+    /// Capped at 100 slots to bound the number of persistent entries
+    pub fn increase_observation_cardinality(env: Env, n: u32) {
+        let mut state = get_state(&env);
+        let old = state.observation_cardinality_next;
+        let new = oracle::grow(&env, old, n);
+        state.observation_cardinality_next = new;
+        set_state(&env, &state);
+
+        if new != old {
+            env.events().publish(
+                (Symbol::new(&env, "increase_observation_cardinality"),),
+                (old, new),
+            );
+        }
+    }
+
+    /// Set how many price checkpoints the pool keeps (default 16, max 64)
+    ///
+    /// Only the factory may call this. Shrinking drops the oldest checkpoints.
+    pub fn set_price_checkpoint_capacity(env: Env, capacity: u32) {
+        let config = get_config(&env);
+        config.factory.require_auth();
+
+        let old = storage::get_price_checkpoint_capacity(&env);
+        oracle::resize_price_checkpoints(&env, capacity);
+
+        env.events().publish(
+            (Symbol::new(&env, "set_price_checkpoint_capacity"),),
+            (old, capacity),
+        );
+    }
+
+    // === View Functions ===
+
+    /// Get recent post-swap prices, oldest first, at most one per ledger timestamp
+    pub fn get_price_checkpoints(env: Env) -> Vec<PriceCheckpoint> {
+        storage::get_price_checkpoints(&env)
+    }
+
+    /// Get how many price checkpoints the pool keeps
+    pub fn price_checkpoint_capacity(env: Env) -> u32 {
+        storage::get_price_checkpoint_capacity(&env)
+    }
+
+    /// Get tick cumulatives `seconds_agos` seconds before now
+    ///
+    /// The average tick between two entries is the difference of their
+    /// cumulatives divided by the difference of their seconds_agos.
+    pub fn observe(env: Env, seconds_agos: Vec<u32>) -> Vec<i64> {
+        let state = get_state(&env);
+        oracle::observe(&env, &state, env.ledger().timestamp(), &seconds_agos)
+    }
+
+    /// Snapshot of oracle cumulatives inside a tick range
+    ///
+    /// Only differences between two snapshots of the same range are meaningful,
+    /// e.g. seconds_inside deltas measure how long the price spent in the range.
+    /// Both ticks must be initialized.
+    ///
+    /// # Returns
+    /// (tick_cumulative_inside, seconds_per_liquidity_inside_x128, seconds_inside)
+    pub fn snapshot_cumulatives_inside(
+        env: Env,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> (i64, u128, u64) {
+        let config = get_config(&env);
+        liquidity::validate_ticks(&env, tick_lower, tick_upper, config.tick_spacing);
+
+        let state = get_state(&env);
+        let cumulatives = oracle::current(&env, &state, env.ledger().timestamp());
+        tick::get_cumulatives_inside(&env, tick_lower, tick_upper, state.tick, &cumulatives)
+    }
+
+    /// Get the oracle observation stored at `index`
+    pub fn get_observation(env: Env, index: u32) -> Observation {
+        storage::get_observation(&env, index)
+    }
+
+    /// Get current pool state
+    ///
+    /// Kept for compatibility; prefer `get_slot0` for price, liquidity and fee reads.
+    pub fn get_state(env: Env) -> PoolState {
+        get_state(&env)
+    }
+
+    /// Get price, tick, liquidity and fee accumulators in one call
+    ///
+    /// Preferred over calling `sqrt_price_x96`, `tick` and `liquidity` separately.
+    pub fn get_slot0(env: Env) -> Slot0 {
+        Slot0::from(&get_state(&env))
+    }
+
+    /// Get pool configuration
+    pub fn get_config(env: Env) -> PoolConfig {
+        get_config(&env)
+    }
+
+    /// Get tick info
+    pub fn get_tick(env: Env, tick: i32) -> TickInfo {
+        get_tick(&env, tick)
+    }
+
+    /// Get the raw tick bitmap word at `word_pos` (bit i = tick (word_pos * 128 + i) * spacing)
+    pub fn get_tick_bitmap_word(env: Env, word_pos: i32) -> u128 {
+        get_tick_bitmap_word(&env, word_pos)
+    }
+
+    /// Get initialized ticks in one bitmap word, starting at bit `start_bit`
+    /// Returns at most 50 ticks per call to stay within Soroban's read entry limits;
+    /// pass `next_start_bit` back in to continue through the word
+    pub fn get_populated_ticks_in_word(
+        env: Env,
+        word_pos: i32,
+        start_bit: u32,
+    ) -> PopulatedTickPage {
+        let config = get_config(&env);
+        tick::get_populated_ticks_in_word(&env, word_pos, start_bit, config.tick_spacing)
+    }
+
+    /// Get initialized ticks in [start_tick, end_tick] in ascending order, across words
+    /// Returns at most `limit` ticks (at least 1, capped at 50) and scans at most 50 bitmap words;
+    /// pass `next_start_tick` back in as `start_tick` to continue
+    pub fn get_ticks_paginated(
+        env: Env,
+        start_tick: i32,
+        end_tick: i32,
+        limit: u32,
+    ) -> PopulatedTickRange {
+        let config = get_config(&env);
+        tick::get_ticks_paginated(&env, start_tick, end_tick, limit, config.tick_spacing)
+    }
+
+    /// Get position info
+    pub fn get_position(
+        env: Env,
+        owner: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> PositionInfo {
+        let key = PositionKey {
+            owner,
+            tick_lower,
+            tick_upper,
+        };
+        get_position(&env, &key)
+    }
+
+    /// Get the number of positions holding liquidity
+    pub fn position_count(env: Env) -> u32 {
+        storage::get_position_count(&env)
+    }
+
+    /// Get up to `limit` keys (max 50) of positions holding liquidity, from registry index `start`
+    ///
+    /// A position is listed while it holds liquidity. Removing one moves the
+    /// last key into its slot, so page from 0 again after burns. Positions
+    /// opened before the registry existed appear once re-opened.
+    pub fn get_position_keys_paginated(env: Env, start: u32, limit: u32) -> Vec<PositionKey> {
+        storage::get_position_keys(&env, start, limit)
+    }
+
+    /// Extend the TTL of a position and its tick and bitmap entries
+    ///
+    /// Anyone may pay to keep a dormant position from being archived.
+    pub fn extend_position_ttl(env: Env, owner: Address, tick_lower: i32, tick_upper: i32) {
+        let config = get_config(&env);
+        let key = PositionKey {
+            owner,
+            tick_lower,
+            tick_upper,
+        };
+        storage::extend_position_ttl(&env, &key, config.tick_spacing);
+    }
+
+    /// Get solvency counters (reserve0, reserve1)
+    /// Returns None for pools initialized before the counters existed
+    pub fn get_reserves(env: Env) -> Option<(u128, u128)> {
+        get_reserves(&env)
+    }
+
+    /// Get protocol fee denominators (fee_protocol_0, fee_protocol_1)
+    pub fn fee_protocol(env: Env) -> (u32, u32) {
+        get_fee_protocol(&env)
+    }
+
+    /// Get uncollected protocol fees (protocol_fees_0, protocol_fees_1)
+    pub fn protocol_fees(env: Env) -> (u128, u128) {
+        let state = get_state(&env);
+        (state.protocol_fees_0 as u128, state.protocol_fees_1 as u128)
+    }
+
+    /// Get global fee growth accumulators (fee_growth_global_0_x128, fee_growth_global_1_x128)
+    pub fn fee_growth_globals(env: Env) -> (u128, u128) {
+        let state = get_state(&env);
+        (state.fee_growth_global_0_x128, state.fee_growth_global_1_x128)
+    }
+
+    /// Get fee growth inside a tick range
+    ///
+    /// # Returns
+    /// (fee_growth_inside_0_x128, fee_growth_inside_1_x128) at the current state
+    pub fn get_fee_growth_inside(env: Env, tick_lower: i32, tick_upper: i32) -> (u128, u128) {
+        let state = get_state(&env);
+        tick::get_fee_growth_inside(
+            &env,
+            tick_lower,
+            tick_upper,
+            state.tick,
+            state.fee_growth_global_0_x128,
+            state.fee_growth_global_1_x128,
+        )
+    }
+
+    /// Get a position's uncollected tokens without modifying state
+    ///
+    /// # Returns
+    /// (amount0, amount1) - Tokens owed plus fees earned since the last poke,
+    /// i.e. what `collect` would pay after a zero-amount `burn`
+    pub fn get_pending_fees(
+        env: Env,
+        owner: Address,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> (u128, u128) {
+        let key = PositionKey {
+            owner,
+            tick_lower,
+            tick_upper,
+        };
+        let position = get_position(&env, &key);
+        if position.liquidity == 0 {
+            return (position.tokens_owed_0, position.tokens_owed_1);
+        }
+
+        let state = get_state(&env);
+        let (fee_growth_inside_0, fee_growth_inside_1) = tick::get_fee_growth_inside(
+            &env,
+            tick_lower,
+            tick_upper,
+            state.tick,
+            state.fee_growth_global_0_x128,
+            state.fee_growth_global_1_x128,
+        );
+        liquidity::compute_tokens_owed(&env, &position, fee_growth_inside_0, fee_growth_inside_1)
+    }
+
+    /// Simulate a swap against the current state without executing it
+    ///
+    /// Runs the same loop as `swap`, including bitmap walks and tick crossings,
+    /// but writes nothing. Capped or short fills are reported rather than
+    /// panicking, and pause flags are ignored.
+    pub fn simulate_swap(
+        env: Env,
+        zero_for_one: bool,
+        amount_specified: i128,
+        sqrt_price_limit_x96: u128,
+    ) -> SwapSimulation {
+        swap::simulate_swap(&env, zero_for_one, amount_specified, sqrt_price_limit_x96)
+    }
+
+    /// Get current sqrt price
+    pub fn sqrt_price_x96(env: Env) -> u128 {
+        get_state(&env).sqrt_price_x96
+    }
+
+    /// Get current tick
+    pub fn tick(env: Env) -> i32 {
+        get_state(&env).tick
+    }
+
+    /// Get current liquidity
+    pub fn liquidity(env: Env) -> u128 {
+        get_state(&env).liquidity
+    }
+
+    /// Get token0 address
+    pub fn token0(env: Env) -> Address {
+        get_config(&env).token0
+    }
+
+    /// Get token1 address
+    pub fn token1(env: Env) -> Address {
+        get_config(&env).token1
+    }
+
+    /// Get fee
+    pub fn fee(env: Env) -> u32 {
+        get_config(&env).fee
+    }
+
+    /// Get tick spacing
+    pub fn tick_spacing(env: Env) -> i32 {
+        get_config(&env).tick_spacing
+    }
+
+    /// Get the maximum gross liquidity any single tick may reference
+    pub fn max_liquidity_per_tick(env: Env) -> u128 {
+        get_config(&env).max_liquidity_per_tick
+    }
+
+    /// Get how much more liquidity can reference `tick` before a mint
+    /// using it as a boundary panics with `PoolError::LiquidityOverflow`
+    pub fn tick_liquidity_headroom(env: Env, tick: i32) -> u128 {
+        let config = get_config(&env);
+        config
+            .max_liquidity_per_tick
+            .saturating_sub(get_tick(&env, tick).liquidity_gross)
+    }
+
+    /// Get the smallest liquidity a single mint may add
+    pub fn min_liquidity(_env: Env) -> u128 {
+        dex_types::MIN_LIQUIDITY
+    }
+}
 
+/// Pull a donation from the donor and verify it all arrived (side effect)
+/// A fee-on-transfer token would otherwise credit LPs fees the pool never received
+fn pull_donation(env: &Env, token: &Address, donor: &Address, amount: u128) {
+    let token_client = token::Client::new(env, token);
+    let contract_address = env.current_contract_address();
+    let balance_before = token_client.balance(&contract_address);
+    token_client.transfer(donor, &contract_address, &(amount as i128));
+    if token_client.balance(&contract_address) < balance_before + amount as i128 {
+        panic_with_error!(env, PoolError::DonationShort);
+    }
+}
+
+/// A protocol fee denominator is 0 (off) or between 4 and 10
+fn valid_fee_protocol(fee_protocol: u32) -> bool {
+    fee_protocol == 0 || (4..=10).contains(&fee_protocol)
+}
+
+/// Require `operator`'s auth and its approval on `owner`'s position
+fn require_position_operator(
+    env: &Env,
+    operator: &Address,
+    owner: &Address,
+    tick_lower: i32,
+    tick_upper: i32,
+) {
+    operator.require_auth();
+    let key = PositionKey {
+        owner: owner.clone(),
+        tick_lower,
+        tick_upper,
+    };
+    if !is_position_operator(env, &key, operator) {
+        panic_with_error!(env, PoolError::NotPositionOperator);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MAX_TICK_CROSSINGS_PER_SWAP;
+    use dex_types::{PopulatedTick, Q96};
+    use soroban_sdk::testutils::storage::Persistent as _;
+    use soroban_sdk::testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke};
+    use soroban_sdk::token::StellarAssetClient;
+    use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, TryFromVal, Val};
+
+    /// Factory stand-in answering the pool's cross-contract reads
+    #[contract]
+    struct MockFactory;
+
+    #[contractimpl]
+    impl MockFactory {
+        pub fn set_fee_recipient(env: Env, recipient: Address) {
+            env.storage().instance().set(&Symbol::new(&env, "recipient"), &recipient);
+        }
+
+        pub fn get_fee_recipient(env: Env) -> Option<Address> {
+            env.storage().instance().get(&Symbol::new(&env, "recipient"))
+        }
+
+        pub fn get_global_pause(_env: Env) -> bool {
+            false
+        }
+    }
+
+    /// Pool backed by SAC tokens and a MockFactory, with 1e9 liquidity on [-600, 600]
+    /// Returns (pool client, factory address, token0, token1, lp)
+    fn setup_live_pool<'a>(env: &Env) -> (DexPoolClient<'a>, Address, Address, Address, Address) {
+        let token_a = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let token_b = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        let factory = env.register(MockFactory, ());
+        let client = DexPoolClient::new(env, &env.register(DexPool, ()));
+        client.initialize(&factory, &token0, &token1, &3000u32, &60i32, &Q96);
+
+        let lp = Address::generate(env);
+        StellarAssetClient::new(env, &token0).mint(&lp, &1_000_000_000_000);
+        StellarAssetClient::new(env, &token1).mint(&lp, &1_000_000_000_000);
+        client.mint(&lp, &lp, &-600, &600, &1_000_000_000);
+
+        (client, factory, token0, token1, lp)
+    }
+
+    #[allow(dead_code)]
+    fn setup_pool(env: &Env) -> (Address, Address, Address, Address) {
+        let factory = Address::generate(env);
+        let token0 = Address::generate(env);
+        let token1 = Address::generate(env);
+
+        // Ensure token0 < token1
         let (t0, t1) = if token0 < token1 {
             (token0, token1)
         } else {
@@ -340,19 +1013,20 @@ mod tests {
         };
 
         let contract_id = env.register(DexPool, ());
-        let client = DexPoolClient::new(&env, &contract_id);
 
-        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
+        (t0, t1, factory, contract_id)
+    }
 
-        // Get uninitialized tick
-        let tick_info = client.get_tick(&100);
-        assert_eq!(tick_info.liquidity_gross, 0);
-        assert_eq!(tick_info.liquidity_net, 0);
-        assert!(!tick_info.initialized);
+    #[allow(dead_code)]
+    fn init_pool(env: &Env, contract_id: &Address, factory: &Address, token0: &Address, token1: &Address) {
+        let client = DexPoolClient::new(env, contract_id);
+        client.initialize(factory, token0, token1, &3000u32, &60i32, &Q96);
     }
 
+    // === Initialization Tests ===
+
     #[test]
-    fn test_get_position_empty() {
+    fn test_initialize_pool() {
         let env = Env::default();
         let factory = Address::generate(&env);
         let token0 = Address::generate(&env);
@@ -367,13 +1041,2488 @@ mod tests {
         let contract_id = env.register(DexPool, ());
         let client = DexPoolClient::new(&env, &contract_id);
 
+        // Initialize pool
         client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
 
-        let owner = Address::generate(&env);
-        let position = client.get_position(&owner, &-120, &120);
-        assert_eq!(position.liquidity, 0);
-        assert_eq!(position.tokens_owed_0, 0);
-        assert_eq!(position.tokens_owed_1, 0);
+        // Verify state
+        let state = client.get_state();
+        assert_eq!(state.sqrt_price_x96, Q96);
+        assert_eq!(state.tick, 0);
+        assert_eq!(state.liquidity, 0);
+
+        // Verify config
+        let config = client.get_config();
+        assert_eq!(config.factory, factory);
+        assert_eq!(config.token0, t0);
+        assert_eq!(config.token1, t1);
+        assert_eq!(config.fee, 3000);
+        assert_eq!(config.tick_spacing, 60);
+    }
+
+    /// Events the last invocation published from `contract`
+    fn contract_events(env: &Env, contract: &Address) -> Vec<(Address, Vec<Val>, Val)> {
+        let mut events = Vec::new(env);
+        for event in env.events().all().iter() {
+            if event.0 == *contract {
+                events.push_back(event);
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_initialize_emits_initialized_event() {
+        let env = Env::default();
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        let client = DexPoolClient::new(&env, &contract_id);
+
+        // Price 4 (token1 per token0) sits at tick 13863
+        let sqrt_price_x96 = 2 * Q96;
+        client.initialize(&factory, &t0, &t1, &500u32, &10i32, &sqrt_price_x96);
+
+        assert_eq!(
+            contract_events(&env, &contract_id),
+            soroban_sdk::vec![
+                &env,
+                (
+                    contract_id.clone(),
+                    (Symbol::new(&env, "initialized"),).into_val(&env),
+                    (t0, t1, 500u32, 10i32, sqrt_price_x96, 13863i32).into_val(&env),
+                ),
+            ]
+        );
+        assert_eq!(client.tick(), 13863);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #100)")]
+    fn test_initialize_twice_fails() {
+        let env = Env::default();
+        let factory = Address::generate(&env);
+        let token0 = Address::generate(&env);
+        let token1 = Address::generate(&env);
+
+        let (t0, t1) = if token0 < token1 {
+            (token0, token1)
+        } else {
+            (token1, token0)
+        };
+
+        let contract_id = env.register(DexPool, ());
+        let client = DexPoolClient::new(&env, &contract_id);
+
+        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
+        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #102)")]
+    fn test_initialize_wrong_token_order() {
+        let env = Env::default();
+        let factory = Address::generate(&env);
+        let token0 = Address::generate(&env);
+        let token1 = Address::generate(&env);
+
+        let (t0, t1) = if token0 < token1 {
+            (token0, token1)
+        } else {
+            (token1, token0)
+        };
+
+        let contract_id = env.register(DexPool, ());
+        let client = DexPoolClient::new(&env, &contract_id);
+
+        // Pass tokens in wrong order
+        client.initialize(&factory, &t1, &t0, &3000u32, &60i32, &Q96);
+    }
+
+    /// Try to initialize a fresh pool, reporting whether it succeeded
+    fn try_init(env: &Env, fee: u32, tick_spacing: i32, sqrt_price_x96: u128) -> bool {
+        let (t0, t1, factory, contract_id) = setup_pool(env);
+        let client = DexPoolClient::new(env, &contract_id);
+        let ok = client
+            .try_initialize(&factory, &t0, &t1, &fee, &tick_spacing, &sqrt_price_x96)
+            .is_ok();
+        // A rejected initialize leaves nothing behind
+        assert_eq!(client.try_get_config().is_ok(), ok);
+        ok
+    }
+
+    #[test]
+    fn test_initialize_sqrt_price_bounds() {
+        let env = Env::default();
+        assert!(!try_init(&env, 3000, 60, 0));
+        assert!(!try_init(&env, 3000, 60, dex_types::MIN_SQRT_RATIO));
+        assert!(try_init(&env, 3000, 60, dex_types::MIN_SQRT_RATIO + 1));
+        assert!(try_init(&env, 3000, 60, dex_types::MAX_SQRT_RATIO - 1));
+        assert!(!try_init(&env, 3000, 60, dex_types::MAX_SQRT_RATIO));
+        assert!(!try_init(&env, 3000, 60, u128::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #103)")]
+    fn test_initialize_max_sqrt_price_message() {
+        let env = Env::default();
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        DexPoolClient::new(&env, &contract_id).initialize(
+            &factory,
+            &t0,
+            &t1,
+            &3000u32,
+            &60i32,
+            &dex_types::MAX_SQRT_RATIO,
+        );
+    }
+
+    #[test]
+    fn test_initialize_fee_bounds() {
+        let env = Env::default();
+        assert!(try_init(&env, 0, 60, Q96));
+        assert!(try_init(&env, 999_999, 60, Q96));
+        assert!(!try_init(&env, 1_000_000, 60, Q96));
+        assert!(!try_init(&env, u32::MAX, 60, Q96));
+    }
+
+    #[test]
+    fn test_initialize_tick_spacing_bounds() {
+        let env = Env::default();
+        assert!(!try_init(&env, 3000, 0, Q96));
+        assert!(!try_init(&env, 3000, -60, Q96));
+        assert!(try_init(&env, 3000, 1, Q96));
+    }
+
+    // === View Function Tests ===
+
+    #[test]
+    fn test_view_functions() {
+        let env = Env::default();
+        let factory = Address::generate(&env);
+        let token0 = Address::generate(&env);
+        let token1 = Address::generate(&env);
+
+        let (t0, t1) = if token0 < token1 {
+            (token0, token1)
+        } else {
+            (token1, token0)
+        };
+
+        let contract_id = env.register(DexPool, ());
+        let client = DexPoolClient::new(&env, &contract_id);
+
+        let initial_price = Q96 * 2; // Price = 4 (sqrt = 2)
+        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &initial_price);
+
+        // Test individual view functions
+        assert_eq!(client.sqrt_price_x96(), initial_price);
+        assert!(client.tick() > 0); // Price > 1 means tick > 0
+        assert_eq!(client.liquidity(), 0);
+        assert_eq!(client.token0(), t0);
+        assert_eq!(client.token1(), t1);
+        assert_eq!(client.fee(), 3000);
+        assert_eq!(client.tick_spacing(), 60);
+    }
+
+    #[test]
+    fn test_get_slot0_matches_individual_getters() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.swap(&lp, &false, &100_000, &0, &false);
+
+        let slot0 = client.get_slot0();
+        let state = client.get_state();
+        assert_eq!(slot0.sqrt_price_x96, client.sqrt_price_x96());
+        assert_eq!(slot0.tick, client.tick());
+        assert_eq!(slot0.liquidity, client.liquidity());
+        assert_eq!(slot0.fee_growth_global_0_x128, state.fee_growth_global_0_x128);
+        assert_eq!(slot0.fee_growth_global_1_x128, state.fee_growth_global_1_x128);
+        assert_eq!(slot0.protocol_fees_0, state.protocol_fees_0);
+        assert_eq!(slot0.protocol_fees_1, state.protocol_fees_1);
+
+        // The swap moved the price and accrued fees in token1
+        assert!(slot0.sqrt_price_x96 > Q96);
+        assert!(slot0.fee_growth_global_1_x128 > 0);
+    }
+
+    #[test]
+    fn test_get_tick_uninitialized() {
+        let env = Env::default();
+        let factory = Address::generate(&env);
+        let token0 = Address::generate(&env);
+        let token1 = Address::generate(&env);
+
+        let (t0, t1) = if token0 < token1 {
+            (token0, token1)
+        } else {
+            (token1, token0)
+        };
+
+        let contract_id = env.register(DexPool, ());
+        let client = DexPoolClient::new(&env, &contract_id);
+
+        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
+
+        // Get uninitialized tick
+        let tick_info = client.get_tick(&100);
+        assert_eq!(tick_info.liquidity_gross, 0);
+        assert_eq!(tick_info.liquidity_net, 0);
+        assert!(!tick_info.initialized);
+    }
+
+    #[test]
+    fn test_get_position_empty() {
+        let env = Env::default();
+        let factory = Address::generate(&env);
+        let token0 = Address::generate(&env);
+        let token1 = Address::generate(&env);
+
+        let (t0, t1) = if token0 < token1 {
+            (token0, token1)
+        } else {
+            (token1, token0)
+        };
+
+        let contract_id = env.register(DexPool, ());
+        let client = DexPoolClient::new(&env, &contract_id);
+
+        client.initialize(&factory, &t0, &t1, &3000u32, &60i32, &Q96);
+
+        let owner = Address::generate(&env);
+        let position = client.get_position(&owner, &-120, &120);
+        assert_eq!(position.liquidity, 0);
+        assert_eq!(position.tokens_owed_0, 0);
+        assert_eq!(position.tokens_owed_1, 0);
+    }
+
+    // === Tick Lens Tests ===
+
+    #[test]
+    fn test_tick_lens_lists_minted_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &60, &120, &1_000);
+        client.mint(&lp, &lp, &120, &300, &2_000);
+
+        // Word 0 holds ticks 60, 120, 300 and the live range's upper tick 600
+        let word = client.get_tick_bitmap_word(&0);
+        assert_eq!(word, (1 << 1) | (1 << 2) | (1 << 5) | (1 << 10));
+
+        let page = client.get_populated_ticks_in_word(&0, &0);
+        assert_eq!(page.next_start_bit, None);
+        assert_eq!(
+            page.ticks,
+            soroban_sdk::vec![
+                &env,
+                PopulatedTick { tick: 60, liquidity_gross: 1_000, liquidity_net: 1_000 },
+                PopulatedTick { tick: 120, liquidity_gross: 3_000, liquidity_net: 1_000 },
+                PopulatedTick { tick: 300, liquidity_gross: 2_000, liquidity_net: -2_000 },
+                PopulatedTick {
+                    tick: 600,
+                    liquidity_gross: 1_000_000_000,
+                    liquidity_net: -1_000_000_000,
+                },
+            ]
+        );
+
+        // The lower tick -600 compresses to -10, bit 118 of word -1
+        assert_eq!(client.get_tick_bitmap_word(&-1), 1 << 118);
+        let page = client.get_populated_ticks_in_word(&-1, &0);
+        assert_eq!(page.ticks.len(), 1);
+        assert_eq!(page.ticks.get(0).unwrap().tick, -600);
+    }
+
+    #[test]
+    fn test_tick_lens_paginates_a_dense_word() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // Adjacent ranges initialize every tick from 0 to 3600: bits 0..=60 of word 0
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        for i in 0..60 {
+            client.mint(&lp, &lp, &(i * 60), &(i * 60 + 60), &1_000);
+        }
+
+        let first = client.get_populated_ticks_in_word(&0, &0);
+        assert_eq!(first.ticks.len(), 50);
+        assert_eq!(first.next_start_bit, Some(50));
+
+        let second = client.get_populated_ticks_in_word(&0, &50);
+        assert_eq!(second.ticks.len(), 11);
+        assert_eq!(second.next_start_bit, None);
+
+        let mut expected_tick = 0;
+        for populated in first.ticks.iter().chain(second.ticks.iter()) {
+            let info = client.get_tick(&populated.tick);
+            assert_eq!(populated.tick, expected_tick);
+            assert_eq!(populated.liquidity_gross, info.liquidity_gross);
+            assert_eq!(populated.liquidity_net, info.liquidity_net);
+            expected_tick += 60;
+        }
+    }
+
+    #[test]
+    fn test_tick_lens_empty_word_and_past_last_bit() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        assert_eq!(client.get_tick_bitmap_word(&5), 0);
+        assert_eq!(client.get_populated_ticks_in_word(&5, &0).ticks.len(), 0);
+
+        let page = client.get_populated_ticks_in_word(&0, &128);
+        assert_eq!(page.ticks.len(), 0);
+        assert_eq!(page.next_start_bit, None);
+    }
+
+    #[test]
+    fn test_ticks_paginated_reconstructs_liquidity_histogram() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // Three overlapping ranges on top of the live [-600, 600] position
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let positions = [
+            (-600, 600, 1_000_000_000u128),
+            (-120, 120, 1_000_000),
+            (60, 300, 2_000_000),
+            (-60, 180, 500_000),
+        ];
+        for (tick_lower, tick_upper, liquidity) in &positions[1..] {
+            client.mint(&lp, &lp, tick_lower, tick_upper, liquidity);
+        }
+
+        let page = client.get_ticks_paginated(&-600, &600, &50);
+        assert_eq!(page.next_start_tick, None);
+        let expected_ticks = [-600, -120, -60, 60, 120, 180, 300, 600];
+        assert_eq!(page.ticks.len(), expected_ticks.len() as u32);
+
+        // Summing liquidity_net left to right gives the liquidity over each interval
+        let mut liquidity: i128 = 0;
+        for (populated, expected_tick) in page.ticks.iter().zip(expected_ticks) {
+            assert_eq!(populated.tick, expected_tick);
+            liquidity += populated.liquidity_net;
+
+            let in_range: u128 = positions
+                .iter()
+                .filter(|(lower, upper, _)| *lower <= populated.tick && populated.tick < *upper)
+                .map(|(_, _, amount)| amount)
+                .sum();
+            assert_eq!(liquidity, in_range as i128);
+            if populated.tick == -60 {
+                // The interval [-60, 60) holds the current tick
+                assert_eq!(liquidity, client.liquidity() as i128);
+            }
+        }
+        assert_eq!(liquidity, 0);
+    }
+
+    #[test]
+    fn test_ticks_paginated_continues_across_pages_and_words() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &-120, &120, &1_000);
+        client.mint(&lp, &lp, &60, &300, &2_000);
+        client.mint(&lp, &lp, &-60, &180, &3_000);
+
+        // Pages of three resume from the first tick not returned
+        let first = client.get_ticks_paginated(&-600, &600, &3);
+        assert_eq!(first.ticks.len(), 3);
+        assert_eq!(first.next_start_tick, Some(60));
+        let second = client.get_ticks_paginated(&60, &600, &3);
+        assert_eq!(second.ticks.get(0).unwrap().tick, 60);
+        assert_eq!(second.next_start_tick, Some(300));
+        let third = client.get_ticks_paginated(&300, &600, &3);
+        assert_eq!(third.ticks.len(), 2);
+        assert_eq!(third.next_start_tick, None);
+
+        // Bounds are inclusive and unaligned ones round inward
+        let inner = client.get_ticks_paginated(&-599, &599, &50);
+        assert_eq!(inner.ticks.len(), 6);
+        assert_eq!(inner.ticks.get(0).unwrap().tick, -120);
+        assert_eq!(inner.ticks.get(5).unwrap().tick, 300);
+
+        // From MIN_TICK to 0 spans 59 words, so the scan stops once for the word cap
+        let mut start = dex_types::MIN_TICK;
+        let (mut found, mut calls) = (0, 0);
+        loop {
+            let page = client.get_ticks_paginated(&start, &0, &50);
+            found += page.ticks.len();
+            calls += 1;
+            match page.next_start_tick {
+                Some(next) => start = next,
+                None => break,
+            }
+        }
+        assert_eq!((found, calls), (3, 2));
+    }
+
+    #[test]
+    fn test_ticks_paginated_zero_limit_still_makes_progress() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &-120, &120, &1_000);
+        client.mint(&lp, &lp, &60, &300, &2_000);
+
+        // A zero limit is treated as one, so paging visits each tick once and ends
+        let first = client.get_ticks_paginated(&-600, &600, &0);
+        assert_eq!(first.ticks.len(), 1);
+        assert_eq!(first.ticks.get(0).unwrap().tick, -600);
+        assert_eq!(first.next_start_tick, Some(-120));
+
+        let mut start = -600;
+        let mut ticks: Vec<i32> = Vec::new(&env);
+        loop {
+            let page = client.get_ticks_paginated(&start, &600, &0);
+            for populated in page.ticks.iter() {
+                ticks.push_back(populated.tick);
+            }
+            match page.next_start_tick {
+                Some(next) => start = next,
+                None => break,
+            }
+        }
+        assert_eq!(ticks, soroban_sdk::vec![&env, -600, -120, 60, 120, 300, 600]);
+    }
+
+    // === Position Registry Tests ===
+
+    #[test]
+    fn test_position_keys_enumerate_open_positions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let other = Address::generate(&env);
+        client.mint(&lp, &lp, &-120, &120, &1_000_000);
+        client.mint(&lp, &other, &-1200, &-600, &1_000_000);
+        // Adding to an open position does not list it twice
+        client.mint(&lp, &lp, &-600, &600, &1_000_000);
+
+        let key = |owner: &Address, tick_lower: i32, tick_upper: i32| PositionKey {
+            owner: owner.clone(),
+            tick_lower,
+            tick_upper,
+        };
+        assert_eq!(client.position_count(), 3);
+        assert_eq!(
+            client.get_position_keys_paginated(&0, &10),
+            soroban_sdk::vec![
+                &env,
+                key(&lp, -600, 600),
+                key(&lp, -120, 120),
+                key(&other, -1200, -600),
+            ]
+        );
+        assert_eq!(client.get_position_keys_paginated(&1, &1).len(), 1);
+        assert_eq!(client.get_position_keys_paginated(&3, &10).len(), 0);
+
+        // A full burn drops the position; its owed tokens don't keep it listed
+        client.burn(&lp, &-600, &600, &1_001_000_000);
+        assert!(client.get_position(&lp, &-600, &600).tokens_owed_0 > 0);
+        assert_eq!(client.position_count(), 2);
+        let keys = client.get_position_keys_paginated(&0, &10);
+        assert_eq!(keys.len(), 2);
+        assert!(!keys.contains(key(&lp, -600, 600)));
+        assert!(keys.contains(key(&lp, -120, 120)) && keys.contains(key(&other, -1200, -600)));
+
+        // Partial burns keep it, re-opening lists it again
+        client.burn(&other, &-1200, &-600, &500_000);
+        client.mint(&lp, &lp, &-600, &600, &1_000);
+        assert_eq!(client.position_count(), 3);
+    }
+
+    #[test]
+    fn test_position_keys_page_is_capped() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.cost_estimate().budget().reset_unlimited();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        for i in 0..55 {
+            let owner = Address::generate(&env);
+            client.mint(&lp, &owner, &(i * 60), &(i * 60 + 60), &1_000);
+        }
+        assert_eq!(client.position_count(), 56);
+        assert_eq!(client.get_position_keys_paginated(&0, &100).len(), 50);
+        assert_eq!(client.get_position_keys_paginated(&50, &100).len(), 6);
+    }
+
+    // === Storage TTL Tests ===
+
+    fn persistent_ttl(env: &Env, client: &DexPoolClient, key: &DataKey) -> u32 {
+        env.as_contract(&client.address, || env.storage().persistent().get_ttl(key))
+    }
+
+    #[test]
+    fn test_extend_position_ttl_keeps_entries_past_original_expiry() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let keys = [
+            DataKey::Position(PositionKey {
+                owner: lp.clone(),
+                tick_lower: -600,
+                tick_upper: 600,
+            }),
+            DataKey::Tick(-600),
+            DataKey::Tick(600),
+            DataKey::TickBitmap(-1),
+            DataKey::TickBitmap(0),
+        ];
+
+        // Close to expiry, a bump restores the full TTL on every backing entry
+        let minted_at = env.ledger().sequence();
+        env.ledger().with_mut(|li| li.sequence_number = minted_at + 518_000);
+        for key in keys.iter() {
+            assert_eq!(persistent_ttl(&env, &client, key), 400);
+        }
+        client.extend_position_ttl(&lp, &-600, &600);
+        for key in keys.iter() {
+            assert_eq!(persistent_ttl(&env, &client, key), 518_400);
+        }
+
+        // Past the original expiry the position is still readable
+        env.ledger().with_mut(|li| li.sequence_number = minted_at + 528_000);
+        assert_eq!(client.get_position(&lp, &-600, &600).liquidity, 1_000_000_000);
+        assert_eq!(client.get_tick(&600).liquidity_gross, 1_000_000_000);
+    }
+
+    #[test]
+    fn test_extend_position_ttl_rejects_missing_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        let stranger = Address::generate(&env);
+        assert!(client.try_extend_position_ttl(&stranger, &-600, &600).is_err());
+    }
+
+    // === Recipient Validation Tests ===
+
+    #[test]
+    fn test_degenerate_recipients_rejected_by_every_entrypoint() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        init_pool(&env, &contract_id, &factory, &t0, &t1);
+        let client = DexPoolClient::new(&env, &contract_id);
+
+        let payer = Address::generate(&env);
+        for recipient in [&contract_id, &t0, &t1, &factory] {
+            assert!(client.try_swap(recipient, &true, &1_000, &0, &false).is_err());
+            assert!(client.try_mint(&payer, recipient, &-60, &60, &1_000).is_err());
+            assert!(client.try_collect(&payer, recipient, &-60, &60, &1, &1).is_err());
+        }
+
+        // An ordinary recipient passes validation (nothing owed, so nothing moves)
+        let user = Address::generate(&env);
+        assert_eq!(client.collect(&user, &user, &-60, &60, &1, &1), (0, 0));
+    }
+
+    #[test]
+    fn test_swap_to_pool_itself_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        init_pool(&env, &contract_id, &factory, &t0, &t1);
+
+        let client = DexPoolClient::new(&env, &contract_id);
+        let result = client.try_swap(&contract_id, &true, &1_000, &0, &false);
+        assert_eq!(result, Err(Ok(pool_error(PoolError::InvalidRecipient))));
+    }
+
+    // === Mint Payment Tests ===
+
+    #[test]
+    fn test_mint_pulls_exact_amounts_from_payer() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, token1, _) = setup_live_pool(&env);
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+
+        let payer = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&payer, &1_000_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&payer, &1_000_000_000);
+        let recipient = Address::generate(&env);
+
+        let pool0_before = token0_client.balance(&client.address);
+        let pool1_before = token1_client.balance(&client.address);
+
+        let (amount0, amount1) = client.mint(&payer, &recipient, &-120, &120, &5_000_000);
+        assert!(amount0 > 0 && amount1 > 0);
+
+        // The pool received exactly what mint reported, all of it from the payer
+        assert_eq!(token0_client.balance(&client.address), pool0_before + amount0 as i128);
+        assert_eq!(token1_client.balance(&client.address), pool1_before + amount1 as i128);
+        assert_eq!(token0_client.balance(&payer), 1_000_000_000 - amount0 as i128);
+        assert_eq!(token1_client.balance(&payer), 1_000_000_000 - amount1 as i128);
+        assert_eq!(token0_client.balance(&recipient), 0);
+
+        // The position belongs to the recipient, not the payer
+        assert_eq!(client.get_position(&recipient, &-120, &120).liquidity, 5_000_000);
+        assert_eq!(client.get_position(&payer, &-120, &120).liquidity, 0);
+    }
+
+    #[test]
+    fn test_mint_amounts_round_up() {
+        let env = Env::default();
+        let lower = dex_math::get_sqrt_ratio_at_tick(&env, -60);
+        let upper = dex_math::get_sqrt_ratio_at_tick(&env, 60);
+
+        // A single unit of liquidity still costs at least one unit of each token
+        assert_eq!(liquidity::compute_mint_amounts(&env, Q96, lower, upper, 1), (1, 1));
+
+        let (rounded0, rounded1) =
+            liquidity::compute_mint_amounts(&env, Q96, lower, upper, 1_000_000_000);
+        let (floor0, floor1) =
+            dex_math::get_amounts_for_liquidity(&env, Q96, lower, upper, 1_000_000_000);
+        assert!(rounded0 >= floor0 && rounded1 >= floor1);
+    }
+
+    #[test]
+    fn test_burn_amounts_round_down() {
+        let env = Env::default();
+        let lower = dex_math::get_sqrt_ratio_at_tick(&env, -60);
+        let upper = dex_math::get_sqrt_ratio_at_tick(&env, 60);
+
+        // A single unit of liquidity is worth less than one unit of each token
+        assert_eq!(liquidity::compute_burn_amounts(&env, Q96, lower, upper, 1), (0, 0));
+
+        for amount in [7u128, 1_000, 123_456_789, 1_000_000_000_000] {
+            let (paid0, paid1) = liquidity::compute_mint_amounts(&env, Q96, lower, upper, amount);
+            let (out0, out1) = liquidity::compute_burn_amounts(&env, Q96, lower, upper, amount);
+            assert!(out0 <= paid0 && out1 <= paid1);
+            assert!(paid0 - out0 <= 1 && paid1 - out1 <= 1);
+        }
+    }
+
+    #[test]
+    fn test_mint_burn_cycles_never_drain_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.cost_estimate().budget().reset_unlimited();
+
+        let (client, _, token0, token1, lp) = setup_live_pool(&env);
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        let lp_lower = dex_math::get_sqrt_ratio_at_tick(&env, -600);
+        let lp_upper = dex_math::get_sqrt_ratio_at_tick(&env, 600);
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&user, &1_000_000_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&user, &1_000_000_000_000);
+
+        // Deterministic xorshift so failures reproduce
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = |bound: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % bound
+        };
+
+        for i in 0..1000 {
+            // Move the price now and then so ranges land below, in and above it
+            if i % 50 == 25 {
+                client.swap(&user, &(i % 100 == 25), &5_000_000, &0, &false);
+            }
+
+            let tick_lower = (next(40) as i32 - 20) * 60;
+            let tick_upper = tick_lower + (next(10) as i32 + 1) * 60;
+            let amount = next(10_000_000) as u128 + 1;
+
+            let (paid0, paid1) = client.mint(&user, &user, &tick_lower, &tick_upper, &amount);
+            let (out0, out1) = client.burn(&user, &tick_lower, &tick_upper, &amount);
+            assert!(out0 <= paid0 && out1 <= paid1);
+            client.collect(&user, &user, &tick_lower, &tick_upper, &u128::MAX, &u128::MAX);
+
+            // The pool always holds at least what it owes the remaining LP
+            let (owed0, owed1) = liquidity::compute_burn_amounts(
+                &env,
+                client.sqrt_price_x96(),
+                lp_lower,
+                lp_upper,
+                1_000_000_000,
+            );
+            let (fees0, fees1) = client.get_pending_fees(&lp, &-600, &600);
+            assert!(token0_client.balance(&client.address) as u128 >= owed0 + fees0);
+            assert!(token1_client.balance(&client.address) as u128 >= owed1 + fees1);
+        }
+    }
+
+    #[test]
+    fn test_mint_requires_payer_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let recipient = Address::generate(&env);
+
+        env.set_auths(&[]);
+        assert!(client.try_mint(&lp, &recipient, &-120, &120, &1_000).is_err());
+    }
+
+    // === Tick Validation Tests ===
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #116)")]
+    fn test_mint_rejects_inverted_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &120, &60, &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #116)")]
+    fn test_mint_rejects_equal_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &60, &60, &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #117)")]
+    fn test_mint_rejects_below_min_tick_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &(dex_types::MIN_TICK - 4), &60, &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #118)")]
+    fn test_mint_rejects_above_max_tick_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &-60, &(dex_types::MAX_TICK + 4), &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #119)")]
+    fn test_mint_rejects_unaligned_lower_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &-59, &60, &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #119)")]
+    fn test_mint_rejects_unaligned_upper_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &-60, &61, &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #119)")]
+    fn test_burn_rejects_unaligned_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.burn(&lp, &-600, &601, &0);
+    }
+
+    #[test]
+    fn test_mint_accepts_widest_aligned_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let tick_lower = (dex_types::MIN_TICK / 60) * 60;
+        let tick_upper = (dex_types::MAX_TICK / 60) * 60;
+        assert_eq!((tick_lower, tick_upper), (-443580, 443580));
+
+        let (amount0, amount1) = client.mint(&lp, &lp, &tick_lower, &tick_upper, &1_000_000);
+        assert!(amount0 > 0 && amount1 > 0);
+        assert_eq!(client.get_position(&lp, &tick_lower, &tick_upper).liquidity, 1_000_000);
+
+        // One spacing further out on either side is rejected
+        assert!(client
+            .try_mint(&lp, &lp, &(tick_lower - 60), &tick_upper, &1_000_000)
+            .is_err());
+        assert!(client
+            .try_mint(&lp, &lp, &tick_lower, &(tick_upper + 60), &1_000_000)
+            .is_err());
+    }
+
+    // === Position Authorization Tests ===
+
+    #[test]
+    fn test_owner_can_burn() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let (amount0, amount1) = client.burn(&lp, &-600, &600, &400_000_000);
+
+        assert!(amount0 > 0 && amount1 > 0);
+        let position = client.get_position(&lp, &-600, &600);
+        assert_eq!(position.liquidity, 600_000_000);
+        assert_eq!(position.tokens_owed_0, amount0);
+        assert_eq!(position.tokens_owed_1, amount1);
+        assert_eq!(client.liquidity(), 600_000_000);
+    }
+
+    #[test]
+    fn test_full_burn_uninitializes_range_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &-120, &120, &1_000_000);
+        for tick in [-120, 120] {
+            assert!(client.get_tick(&tick).initialized);
+            let (word_pos, bit_pos) = tick::tick_to_bitmap_position(tick, 60);
+            assert_ne!(client.get_tick_bitmap_word(&word_pos) & (1 << bit_pos), 0);
+        }
+
+        client.burn(&lp, &-120, &120, &1_000_000);
+
+        for tick in [-120, 120] {
+            let info = client.get_tick(&tick);
+            assert!(!info.initialized);
+            assert_eq!(info.liquidity_gross, 0);
+            let (word_pos, bit_pos) = tick::tick_to_bitmap_position(tick, 60);
+            assert_eq!(client.get_tick_bitmap_word(&word_pos) & (1 << bit_pos), 0);
+        }
+        // The live range's ticks are untouched
+        assert!(client.get_tick(&-600).initialized);
+        assert!(client.get_tick(&600).initialized);
+    }
+
+    #[test]
+    fn test_burn_rejects_non_owner() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let attacker = Address::generate(&env);
+
+        // The attacker signs for itself but names the owner's position
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &attacker,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "burn",
+                    args: (lp.clone(), -600i32, 600i32, 1_000u128).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_burn(&lp, &-600, &600, &1_000);
+        assert!(result.is_err());
+        assert_eq!(client.get_position(&lp, &-600, &600).liquidity, 1_000_000_000);
+    }
+
+    #[test]
+    fn test_collect_rejects_non_owner() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, _, lp) = setup_live_pool(&env);
+        client.burn(&lp, &-600, &600, &1_000_000);
+        let owed = client.get_position(&lp, &-600, &600).tokens_owed_0;
+        assert!(owed > 0);
+
+        // A third party tries to send the LP's owed tokens to itself
+        let attacker = Address::generate(&env);
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &attacker,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "collect",
+                    args: (lp.clone(), attacker.clone(), -600i32, 600i32, u128::MAX, u128::MAX)
+                        .into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_collect(&lp, &attacker, &-600, &600, &u128::MAX, &u128::MAX);
+        assert!(result.is_err());
+
+        assert_eq!(client.get_position(&lp, &-600, &600).tokens_owed_0, owed);
+        assert_eq!(soroban_sdk::token::Client::new(&env, &token0).balance(&attacker), 0);
+
+        // Collecting under the attacker's own key finds nothing
+        env.mock_all_auths();
+        assert_eq!(
+            client.collect(&attacker, &attacker, &-600, &600, &u128::MAX, &u128::MAX),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_operator_collects_to_owner_chosen_recipient() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, lp) = setup_live_pool(&env);
+        let operator = Address::generate(&env);
+        let vault = Address::generate(&env);
+        client.approve_position_operator(&lp, &operator, &-600, &600, &true);
+        assert!(client.is_position_operator(&lp, &operator, &-600, &600));
+
+        generate_token1_fees(&env, &client);
+        client.operator_burn(&operator, &lp, &-600, &600, &0);
+        let owed = client.get_position(&lp, &-600, &600).tokens_owed_1;
+        assert!(owed > 0);
+
+        let (_, amount1) =
+            client.operator_collect(&operator, &lp, &vault, &-600, &600, &u128::MAX, &u128::MAX);
+        assert_eq!(amount1, owed);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        assert_eq!(token1_client.balance(&vault), owed as i128);
+        assert_eq!(client.get_position(&lp, &-600, &600).tokens_owed_1, 0);
+    }
+
+    #[test]
+    fn test_revoked_operator_is_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let operator = Address::generate(&env);
+        client.approve_position_operator(&lp, &operator, &-600, &600, &true);
+        client.operator_burn(&operator, &lp, &-600, &600, &1_000);
+
+        client.approve_position_operator(&lp, &operator, &-600, &600, &false);
+        assert!(!client.is_position_operator(&lp, &operator, &-600, &600));
+        assert_eq!(
+            client.try_operator_burn(&operator, &lp, &-600, &600, &1_000),
+            Err(Ok(pool_error(PoolError::NotPositionOperator)))
+        );
+        assert_eq!(client.get_position(&lp, &-600, &600).liquidity, 999_999_000);
+    }
+
+    #[test]
+    fn test_unapproved_operator_is_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let operator = Address::generate(&env);
+        client.burn(&lp, &-600, &600, &1_000_000);
+
+        assert_eq!(
+            client.try_operator_burn(&operator, &lp, &-600, &600, &1_000),
+            Err(Ok(pool_error(PoolError::NotPositionOperator)))
+        );
+        assert_eq!(
+            client.try_operator_collect(&operator, &lp, &operator, &-600, &600, &1, &1),
+            Err(Ok(pool_error(PoolError::NotPositionOperator)))
+        );
+
+        // An approval covers only the range it names
+        client.approve_position_operator(&lp, &operator, &-1200, &1200, &true);
+        assert_eq!(
+            client.try_operator_burn(&operator, &lp, &-600, &600, &1_000),
+            Err(Ok(pool_error(PoolError::NotPositionOperator)))
+        );
+
+        // The operator cannot approve itself on the owner's behalf
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &operator,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "approve_position_operator",
+                    args: (lp.clone(), operator.clone(), -600i32, 600i32, true).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_approve_position_operator(&lp, &operator, &-600, &600, &true);
+        assert!(result.is_err());
+        assert!(!client.is_position_operator(&lp, &operator, &-600, &600));
+    }
+
+    // === Fee-on-Transfer Tests ===
+
+    /// Token that burns `fee_bps` of every transfer once a fee is set,
+    /// and reports success without moving anything while silenced
+    #[contract]
+    struct FeeOnTransferToken;
+
+    #[contractimpl]
+    impl FeeOnTransferToken {
+        pub fn set_fee_bps(env: Env, fee_bps: i128) {
+            env.storage().instance().set(&Symbol::new(&env, "fee"), &fee_bps);
+        }
+
+        pub fn set_silent(env: Env, silent: bool) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "silent"), &silent);
+        }
+
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            let silent: bool = env
+                .storage()
+                .instance()
+                .get(&Symbol::new(&env, "silent"))
+                .unwrap_or(false);
+            if silent {
+                return;
+            }
+            let fee_bps: i128 = env
+                .storage()
+                .instance()
+                .get(&Symbol::new(&env, "fee"))
+                .unwrap_or(0);
+            let balance = Self::balance(env.clone(), from.clone());
+            env.storage().persistent().set(&from, &(balance - amount));
+            Self::mint(env, to, amount - amount * fee_bps / 10_000);
+        }
+    }
+
+    /// Live pool pairing a fee-on-transfer token (fee still off) with a SAC token
+    fn setup_fee_on_transfer_pool<'a>(
+        env: &Env,
+    ) -> (DexPoolClient<'a>, FeeOnTransferTokenClient<'a>, Address) {
+        let taxed = env.register(FeeOnTransferToken, ());
+        let other = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let (token0, token1) = if taxed < other {
+            (taxed.clone(), other.clone())
+        } else {
+            (other.clone(), taxed.clone())
+        };
+
+        let factory = env.register(MockFactory, ());
+        let client = DexPoolClient::new(env, &env.register(DexPool, ()));
+        client.initialize(&factory, &token0, &token1, &3000u32, &60i32, &Q96);
+
+        let taxed_client = FeeOnTransferTokenClient::new(env, &taxed);
+        let lp = Address::generate(env);
+        let trader = Address::generate(env);
+        for user in [&lp, &trader] {
+            taxed_client.mint(user, &1_000_000_000_000);
+            StellarAssetClient::new(env, &other).mint(user, &1_000_000_000_000);
+        }
+        client.mint(&lp, &lp, &-600, &600, &1_000_000_000);
+
+        (client, taxed_client, trader)
+    }
+
+    #[test]
+    fn test_swap_rejects_short_paying_input_token() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, taxed, trader) = setup_fee_on_transfer_pool(&env);
+        taxed.set_fee_bps(&100);
+        let taxed_is_token0 = client.token0() == taxed.address;
+
+        // Paying in the taxed token delivers 1% less than the swap owes
+        assert_eq!(
+            client.try_swap(&trader, &taxed_is_token0, &1_000_000, &0, &false),
+            Err(Ok(pool_error(PoolError::SwapPaymentShort)))
+        );
+        assert_eq!(client.sqrt_price_x96(), Q96);
+
+        // Receiving it is the recipient's loss, not the pool's
+        let result = client.swap(&trader, &!taxed_is_token0, &1_000_000, &0, &false);
+        assert!(result.amount0 != 0 && result.amount1 != 0);
+    }
+
+    #[test]
+    fn test_swap_rejects_input_transfer_that_moves_nothing() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, silent, trader) = setup_fee_on_transfer_pool(&env);
+        silent.set_silent(&true);
+        let silent_is_token0 = client.token0() == silent.address;
+        let output_token = if silent_is_token0 {
+            client.token1()
+        } else {
+            client.token0()
+        };
+        let output = soroban_sdk::token::Client::new(&env, &output_token);
+        let output_before = output.balance(&trader);
+
+        // The transfer call succeeds but the pool's balance never moves
+        assert_eq!(
+            client.try_swap(&trader, &silent_is_token0, &1_000_000, &0, &false),
+            Err(Ok(pool_error(PoolError::SwapPaymentShort)))
+        );
+        assert_eq!(output.balance(&trader), output_before);
+        assert_eq!(silent.balance(&trader), 1_000_000_000_000);
+        assert_eq!(client.sqrt_price_x96(), Q96);
+    }
+
+    #[test]
+    fn test_mint_rejects_short_paying_token() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, taxed, trader) = setup_fee_on_transfer_pool(&env);
+        taxed.set_fee_bps(&100);
+
+        assert_eq!(
+            client.try_mint(&trader, &trader, &-120, &120, &1_000_000),
+            Err(Ok(pool_error(PoolError::MintPaymentShort)))
+        );
+    }
+
+    // === Reentrancy Tests ===
+
+    /// Token that calls back into the pool's swap from `transfer` once armed
+    #[contract]
+    struct ReentrantToken;
+
+    #[contractimpl]
+    impl ReentrantToken {
+        pub fn arm(env: Env, pool: Address) {
+            env.storage().instance().set(&Symbol::new(&env, "pool"), &pool);
+        }
+
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), from.clone());
+            env.storage().persistent().set(&from, &(balance - amount));
+            Self::mint(env.clone(), to, amount);
+
+            let pool: Option<Address> = env.storage().instance().get(&Symbol::new(&env, "pool"));
+            if let Some(pool) = pool {
+                env.invoke_contract::<SwapResult>(
+                    &pool,
+                    &Symbol::new(&env, "swap"),
+                    (from, false, 1_000i128, 0u128, false).into_val(&env),
+                );
+            }
+        }
+    }
+
+    fn is_locked(env: &Env, client: &DexPoolClient) -> bool {
+        env.as_contract(&client.address, || env.storage().instance().has(&DataKey::Locked))
+    }
+
+    #[test]
+    fn test_reentrant_token_cannot_reenter_swap() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let evil = env.register(ReentrantToken, ());
+        let other = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let (token0, token1) = if evil < other {
+            (evil.clone(), other.clone())
+        } else {
+            (other.clone(), evil.clone())
+        };
+
+        let factory = env.register(MockFactory, ());
+        let client = DexPoolClient::new(&env, &env.register(DexPool, ()));
+        client.initialize(&factory, &token0, &token1, &3000u32, &60i32, &Q96);
+
+        let evil_client = ReentrantTokenClient::new(&env, &evil);
+        let lp = Address::generate(&env);
+        let trader = Address::generate(&env);
+        for user in [&lp, &trader] {
+            evil_client.mint(user, &1_000_000_000_000);
+            StellarAssetClient::new(&env, &other).mint(user, &1_000_000_000_000);
+        }
+        client.mint(&lp, &lp, &-600, &600, &1_000_000_000);
+
+        // Both tokens move during a swap, so the armed token re-enters either way
+        evil_client.arm(&client.address);
+        assert!(client.try_swap(&trader, &false, &10_000, &0, &false).is_err());
+
+        assert_eq!(client.sqrt_price_x96(), Q96);
+        assert!(!is_locked(&env, &client));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #122)")]
+    fn test_locked_pool_rejects_swap() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        env.as_contract(&client.address, || lock(&env));
+
+        client.swap(&lp, &false, &1_000, &0, &false);
+    }
+
+    #[test]
+    fn test_lock_released_after_each_operation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        assert!(!is_locked(&env, &client));
+
+        client.swap(&lp, &false, &10_000, &0, &false);
+        assert!(!is_locked(&env, &client));
+        client.mint(&lp, &lp, &-120, &120, &1_000_000);
+        assert!(!is_locked(&env, &client));
+        client.burn(&lp, &-120, &120, &1_000_000);
+        assert!(!is_locked(&env, &client));
+        client.collect(&lp, &lp, &-120, &120, &u128::MAX, &u128::MAX);
+        assert!(!is_locked(&env, &client));
+
+        // Operations keep working once the lock is released
+        client.swap(&lp, &false, &10_000, &0, &false);
+    }
+
+    // === Skim Tests ===
+
+    #[test]
+    fn test_initialize_enables_solvency_counters() {
+        let env = Env::default();
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        init_pool(&env, &contract_id, &factory, &t0, &t1);
+
+        let client = DexPoolClient::new(&env, &contract_id);
+        assert_eq!(client.get_reserves(), Some((0, 0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #130)")]
+    fn test_skim_refuses_without_solvency_counters() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        init_pool(&env, &contract_id, &factory, &t0, &t1);
+
+        // Simulate a pool deployed before the counters existed
+        env.as_contract(&contract_id, || {
+            env.storage().instance().remove(&DataKey::Reserves);
+        });
+
+        DexPoolClient::new(&env, &contract_id).skim(&factory);
+    }
+
+    // === Protocol Fee Tests ===
+
+    #[test]
+    fn test_set_fee_protocol_by_factory() {
+        let env = Env::default();
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        init_pool(&env, &contract_id, &factory, &t0, &t1);
+
+        let client = DexPoolClient::new(&env, &contract_id);
+        assert_eq!(client.fee_protocol(), (0, 0));
+
+        client
+            .mock_auths(&[MockAuth {
+                address: &factory,
+                invoke: &MockAuthInvoke {
+                    contract: &contract_id,
+                    fn_name: "set_fee_protocol",
+                    args: (4u32, 10u32).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .set_fee_protocol(&4, &10);
+        assert_eq!(client.fee_protocol(), (4, 10));
+    }
+
+    #[test]
+    fn test_set_fee_protocol_rejects_non_factory() {
+        let env = Env::default();
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        init_pool(&env, &contract_id, &factory, &t0, &t1);
+
+        let client = DexPoolClient::new(&env, &contract_id);
+        let attacker = Address::generate(&env);
+
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &attacker,
+                invoke: &MockAuthInvoke {
+                    contract: &contract_id,
+                    fn_name: "set_fee_protocol",
+                    args: (4u32, 4u32).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_set_fee_protocol(&4, &4);
+        assert!(result.is_err());
+        assert_eq!(client.fee_protocol(), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #131)")]
+    fn test_set_fee_protocol_rejects_out_of_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (t0, t1, factory, contract_id) = setup_pool(&env);
+        init_pool(&env, &contract_id, &factory, &t0, &t1);
+
+        DexPoolClient::new(&env, &contract_id).set_fee_protocol(&3, &0);
+    }
+
+    /// Fee the swap loop will charge for a swap that stays within one step
+    fn expected_step_fee(env: &Env, client: &DexPoolClient, zero_for_one: bool, amount: i128) -> u128 {
+        let target_tick = if zero_for_one { -600 } else { 600 };
+        dex_math::compute_swap_step(
+            env,
+            client.sqrt_price_x96(),
+            dex_math::get_sqrt_ratio_at_tick(env, target_tick),
+            client.liquidity(),
+            amount,
+            3000,
+        )
+        .fee_amount
+    }
+
+    #[test]
+    fn test_protocol_fee_split_both_directions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        client.set_fee_protocol(&4, &6);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &client.token0()).mint(&trader, &1_000_000_000);
+        StellarAssetClient::new(&env, &client.token1()).mint(&trader, &1_000_000_000);
+        let liquidity = client.liquidity();
+
+        // token1 in: protocol takes 1/6 of the token1 fee
+        let fee1 = expected_step_fee(&env, &client, false, 1_000_000);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+        let state = client.get_state();
+        assert_eq!(state.protocol_fees_1 as u128, fee1 / 6);
+        assert_eq!(
+            state.fee_growth_global_1_x128,
+            dex_math::div_shl_128(&env, fee1 - fee1 / 6, liquidity)
+        );
+        let lp_share1 = dex_math::mul_shr_128(&env, state.fee_growth_global_1_x128, liquidity);
+        assert!(fee1 - (state.protocol_fees_1 as u128 + lp_share1) <= 1);
+
+        // token0 in: protocol takes 1/4 of the token0 fee
+        let fee0 = expected_step_fee(&env, &client, true, 100_000);
+        client.swap(&trader, &true, &100_000, &0, &false);
+        let state = client.get_state();
+        assert_eq!(state.protocol_fees_0 as u128, fee0 / 4);
+        assert_eq!(
+            state.fee_growth_global_0_x128,
+            dex_math::div_shl_128(&env, fee0 - fee0 / 4, liquidity)
+        );
+        let lp_share0 = dex_math::mul_shr_128(&env, state.fee_growth_global_0_x128, liquidity);
+        assert!(fee0 - (state.protocol_fees_0 as u128 + lp_share0) <= 1);
+
+        // The token1 side is untouched by the token0 swap
+        assert_eq!(state.protocol_fees_1 as u128, fee1 / 6);
+    }
+
+    #[test]
+    fn test_protocol_fees_and_fee_growth_views_track_swaps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        client.set_fee_protocol(&4, &4);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &client.token0()).mint(&trader, &1_000_000_000);
+        StellarAssetClient::new(&env, &client.token1()).mint(&trader, &1_000_000_000);
+        assert_eq!(client.protocol_fees(), (0, 0));
+        assert_eq!(client.fee_growth_globals(), (0, 0));
+
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+        let (protocol0, protocol1) = client.protocol_fees();
+        let (growth0, growth1) = client.fee_growth_globals();
+        assert_eq!(protocol0, 0);
+        assert!(protocol1 > 0);
+        assert_eq!(growth0, 0);
+        assert!(growth1 > 0);
+
+        client.swap(&trader, &true, &100_000, &0, &false);
+        assert!(client.protocol_fees().0 > 0);
+        assert_eq!(client.protocol_fees().1, protocol1);
+        assert!(client.fee_growth_globals().0 > 0);
+        assert_eq!(client.fee_growth_globals().1, growth1);
+
+        // Views agree with the full state and slot0 structs
+        let state = client.get_state();
+        let slot0 = client.get_slot0();
+        assert_eq!(
+            client.protocol_fees(),
+            (state.protocol_fees_0 as u128, state.protocol_fees_1 as u128)
+        );
+        assert_eq!(
+            client.fee_growth_globals(),
+            (slot0.fee_growth_global_0_x128, slot0.fee_growth_global_1_x128)
+        );
+    }
+
+    #[test]
+    fn test_fee_protocol_off_gives_all_fees_to_lps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &client.token1()).mint(&trader, &1_000_000_000);
+
+        let fee1 = expected_step_fee(&env, &client, false, 1_000_000);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+
+        let state = client.get_state();
+        assert_eq!(state.protocol_fees_1, 0);
+        assert_eq!(
+            state.fee_growth_global_1_x128,
+            dex_math::div_shl_128(&env, fee1, client.liquidity())
+        );
+    }
+
+    #[test]
+    fn test_swap_result_reports_fees_and_final_state() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        client.set_fee_protocol(&0, &6);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &client.token1()).mint(&trader, &1_000_000_000);
+
+        let fee1 = expected_step_fee(&env, &client, false, 1_000_000);
+        let result = client.swap(&trader, &false, &1_000_000, &0, &false);
+
+        // The reported fee includes the protocol share
+        assert_eq!(result.fee_amount_total, fee1);
+        assert_eq!(client.get_state().protocol_fees_1 as u128, fee1 / 6);
+
+        let slot0 = client.get_slot0();
+        assert_eq!(result.sqrt_price_after_x96, slot0.sqrt_price_x96);
+        assert_eq!(result.tick_after, slot0.tick);
+        assert_eq!(result.liquidity_after, slot0.liquidity);
+        assert_eq!(result.ticks_crossed, 0);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn test_collect_protocol_by_fee_recipient() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, factory, _, token1, _) = setup_live_pool(&env);
+        let fee_recipient = Address::generate(&env);
+        MockFactoryClient::new(&env, &factory).set_fee_recipient(&fee_recipient);
+
+        client.set_fee_protocol(&4, &4);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+
+        let owed1 = client.get_state().protocol_fees_1 as u128;
+        assert!(owed1 > 0);
+        let reserves_before = client.get_reserves().unwrap();
+
+        // Partial, then capped at what is owed
+        assert_eq!(client.collect_protocol(&fee_recipient, &0, &1), (0, 1));
+        assert_eq!(
+            client.collect_protocol(&fee_recipient, &u128::MAX, &u128::MAX),
+            (0, owed1 - 1)
+        );
+
+        assert_eq!(client.get_state().protocol_fees_1, 0);
+        assert_eq!(
+            soroban_sdk::token::TokenClient::new(&env, &token1).balance(&fee_recipient),
+            owed1 as i128
+        );
+        assert_eq!(
+            client.get_reserves().unwrap(),
+            (reserves_before.0, reserves_before.1 - owed1)
+        );
+    }
+
+    #[test]
+    fn test_collect_protocol_requires_fee_recipient_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, factory, _, _, _) = setup_live_pool(&env);
+        let fee_recipient = Address::generate(&env);
+        MockFactoryClient::new(&env, &factory).set_fee_recipient(&fee_recipient);
+
+        env.set_auths(&[]);
+        let thief = Address::generate(&env);
+        assert!(client.try_collect_protocol(&thief, &1, &1).is_err());
+    }
+
+    // === Position Fee Accrual Tests ===
+
+    /// Swap token1 into the live pool so lp's [-600, 600] position earns fees
+    fn generate_token1_fees(env: &Env, client: &DexPoolClient) -> u128 {
+        let trader = Address::generate(env);
+        StellarAssetClient::new(env, &client.token1()).mint(&trader, &1_000_000_000);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+        client.get_state().fee_growth_global_1_x128
+    }
+
+    #[test]
+    fn test_full_burn_of_sole_range_owes_no_fees_from_before_mint() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, token1, _) = setup_live_pool(&env);
+        assert!(generate_token1_fees(&env, &client) > 0);
+
+        // [-120, 120] ticks are referenced by this position alone
+        let lp = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&lp, &1_000_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&lp, &1_000_000_000);
+        client.mint(&lp, &lp, &-120, &120, &1_000_000_000);
+        let (amount0, amount1) = client.burn(&lp, &-120, &120, &1_000_000_000);
+        assert!(!client.get_tick(&-120).initialized && !client.get_tick(&120).initialized);
+
+        // Fee growth from before the mint stays outside the range
+        let position = client.get_position(&lp, &-120, &120);
+        assert_eq!(position.tokens_owed_0, amount0);
+        assert_eq!(position.tokens_owed_1, amount1);
+    }
+
+    #[test]
+    fn test_tick_crossed_mid_swap_snapshots_current_fee_growth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, lp) = setup_live_pool(&env);
+        client.mint(&lp, &lp, &600, &1200, &1_000_000_000);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &100_000_000);
+
+        // Fee charged on the way up to tick 600, all earned by [-600, 600]
+        let amount = 50_000_000;
+        let fee_to_cross = dex_math::compute_swap_step(
+            &env,
+            Q96,
+            dex_math::get_sqrt_ratio_at_tick(&env, 600),
+            1_000_000_000,
+            amount,
+            3000,
+        )
+        .fee_amount;
+        let growth_at_cross = dex_math::div_shl_128(&env, fee_to_cross, 1_000_000_000);
+
+        let result = client.swap(&trader, &false, &amount, &0, &false);
+        assert_eq!(result.ticks_crossed, 1);
+        assert!((600..1200).contains(&result.tick_after));
+        assert_eq!(client.get_tick(&600).fee_growth_outside_1_x128, growth_at_cross);
+        let growth_after = client.get_state().fee_growth_global_1_x128;
+
+        // Each range is paid only for the part of the swap it was in range for
+        client.burn(&lp, &-600, &600, &0);
+        client.burn(&lp, &600, &1200, &0);
+        assert_eq!(
+            client.get_position(&lp, &-600, &600).tokens_owed_1,
+            dex_math::mul_shr_128(&env, growth_at_cross, 1_000_000_000)
+        );
+        assert_eq!(
+            client.get_position(&lp, &600, &1200).tokens_owed_1,
+            dex_math::mul_shr_128(&env, growth_after - growth_at_cross, 1_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_burn_zero_settles_earned_fees() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let liquidity = client.liquidity();
+        let fee1 = expected_step_fee(&env, &client, false, 1_000_000);
+        let fee_growth_1 = generate_token1_fees(&env, &client);
+
+        client.burn(&lp, &-600, &600, &0);
+
+        let position = client.get_position(&lp, &-600, &600);
+        let earned = dex_math::mul_shr_128(&env, fee_growth_1, liquidity);
+        assert_eq!(position.tokens_owed_0, 0);
+        assert_eq!(position.tokens_owed_1, earned);
+        assert!(fee1 - earned <= 1);
+        assert_eq!(position.fee_growth_inside_1_last_x128, fee_growth_1);
+
+        // The settled fees can be withdrawn
+        assert_eq!(client.collect(&lp, &lp, &-600, &600, &u128::MAX, &u128::MAX), (0, earned));
+    }
+
+    #[test]
+    fn test_burn_zero_leaves_liquidity_and_ticks_untouched() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        generate_token1_fees(&env, &client);
+        let liquidity = client.liquidity();
+        let lower = client.get_tick(&-600);
+        let upper = client.get_tick(&600);
+
+        assert_eq!(client.burn(&lp, &-600, &600, &0), (0, 0));
+        assert_eq!(client.liquidity(), liquidity);
+        assert_eq!(client.get_position(&lp, &-600, &600).liquidity, 1_000_000_000);
+        assert_eq!(client.get_tick(&-600).liquidity_gross, lower.liquidity_gross);
+        assert_eq!(client.get_tick(&600).liquidity_net, upper.liquidity_net);
+        assert_eq!(
+            client.get_tick(&600).fee_growth_outside_1_x128,
+            upper.fee_growth_outside_1_x128
+        );
+    }
+
+    #[test]
+    fn test_burn_zero_rejects_empty_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let stranger = Address::generate(&env);
+        assert!(client.try_burn(&stranger, &-600, &600, &0).is_err());
+
+        // Once fully burned the position can no longer be poked either
+        client.burn(&lp, &-600, &600, &1_000_000_000);
+        assert!(client.try_burn(&lp, &-600, &600, &0).is_err());
+    }
+
+    #[test]
+    fn test_mint_settles_fees_before_adding_liquidity() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let liquidity = client.liquidity();
+        let fee_growth_1 = generate_token1_fees(&env, &client);
+
+        // Fees are credited at the old liquidity, not the increased one
+        client.mint(&lp, &lp, &-600, &600, &1_000_000_000);
+        let position = client.get_position(&lp, &-600, &600);
+        let earned = dex_math::mul_shr_128(&env, fee_growth_1, liquidity);
+        assert_eq!(position.liquidity, liquidity + 1_000_000_000);
+        assert_eq!(position.tokens_owed_1, earned);
+
+        // A later poke does not credit the same fees again
+        client.burn(&lp, &-600, &600, &0);
+        assert_eq!(client.get_position(&lp, &-600, &600).tokens_owed_1, earned);
+    }
+
+    #[test]
+    fn test_new_position_does_not_earn_past_fees() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, token1, _) = setup_live_pool(&env);
+        generate_token1_fees(&env, &client);
+
+        let late_lp = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&late_lp, &1_000_000_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&late_lp, &1_000_000_000_000);
+        client.mint(&late_lp, &late_lp, &-600, &600, &1_000_000_000);
+        client.burn(&late_lp, &-600, &600, &0);
+
+        let position = client.get_position(&late_lp, &-600, &600);
+        assert_eq!((position.tokens_owed_0, position.tokens_owed_1), (0, 0));
+    }
+
+    #[test]
+    fn test_pending_fees_matches_collect_after_poke() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        assert_eq!(client.get_pending_fees(&lp, &-600, &600), (0, 0));
+        generate_token1_fees(&env, &client);
+
+        // The view sees the fees before anything is settled into the position
+        let pending = client.get_pending_fees(&lp, &-600, &600);
+        assert!(pending.1 > 0);
+        assert_eq!(client.get_position(&lp, &-600, &600).tokens_owed_1, 0);
+
+        client.burn(&lp, &-600, &600, &0);
+        assert_eq!(client.collect(&lp, &lp, &-600, &600, &u128::MAX, &u128::MAX), pending);
+        assert_eq!(client.get_pending_fees(&lp, &-600, &600), (0, 0));
+    }
+
+    #[test]
+    fn test_pending_fees_includes_burned_amounts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        generate_token1_fees(&env, &client);
+
+        // Half burned: owed principal plus all fees, the rest still accruing
+        let burned = client.burn(&lp, &-600, &600, &500_000_000);
+        let fees = client.get_position(&lp, &-600, &600).tokens_owed_1 - burned.1;
+        assert_eq!(client.get_pending_fees(&lp, &-600, &600), (burned.0, burned.1 + fees));
+        generate_token1_fees(&env, &client);
+
+        let pending = client.get_pending_fees(&lp, &-600, &600);
+        assert!(pending.1 > burned.1 + fees);
+        client.burn(&lp, &-600, &600, &0);
+        assert_eq!(client.collect(&lp, &lp, &-600, &600, &u128::MAX, &u128::MAX), pending);
+    }
+
+    #[test]
+    fn test_pending_fees_unknown_position_is_zero() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        generate_token1_fees(&env, &client);
+        let stranger = Address::generate(&env);
+        assert_eq!(client.get_pending_fees(&stranger, &-600, &600), (0, 0));
+    }
+
+    // === Pool Pause Tests ===
+
+    #[test]
+    fn test_pool_pause_blocks_swaps_but_not_exits() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, lp) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000);
+        client.swap(&trader, &false, &1_000, &0, &false);
+
+        assert!(!client.is_paused());
+        client.set_paused(&true);
+        assert!(client.is_paused());
+        assert!(client.try_swap(&trader, &false, &1_000, &0, &false).is_err());
+
+        // LPs can still add, remove and withdraw while paused
+        client.mint(&lp, &lp, &-600, &600, &1_000);
+        let (amount0, amount1) = client.burn(&lp, &-600, &600, &1_000_000);
+        let collected = client.collect(&lp, &lp, &-600, &600, &u128::MAX, &u128::MAX);
+        assert!(collected.0 >= amount0 && collected.1 >= amount1);
+
+        // Resuming re-enables swaps
+        client.set_paused(&false);
+        client.swap(&trader, &false, &1_000, &0, &false);
+    }
+
+    #[test]
+    fn test_set_paused_rejects_non_factory() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        let attacker = Address::generate(&env);
+
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &attacker,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "set_paused",
+                    args: (true,).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_set_paused(&true);
+        assert!(result.is_err());
+        assert!(!client.is_paused());
+    }
+
+    // === Upgrade Tests ===
+
+    #[test]
+    fn test_upgrade_and_migrate_reject_non_factory() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        env.set_auths(&[]);
+        assert!(client
+            .try_upgrade(&BytesN::from_array(&env, &[7u8; 32]))
+            .is_err());
+        assert!(client.try_migrate().is_err());
+    }
+
+    #[test]
+    fn test_migrate_stamps_unversioned_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        assert_eq!(client.storage_version(), CURRENT_STORAGE_VERSION);
+        // Already current: nothing to do
+        assert_eq!(client.migrate(), CURRENT_STORAGE_VERSION);
+
+        // Simulate a pool created before storage versioning
+        env.as_contract(&client.address, || {
+            env.storage().instance().remove(&DataKey::StorageVersion);
+        });
+        assert_eq!(client.storage_version(), 0);
+
+        let state_before = client.get_state();
+        let position_before = client.get_position(&lp, &-600, &600).liquidity;
+        assert_eq!(client.migrate(), CURRENT_STORAGE_VERSION);
+        assert_single_event(
+            &env,
+            &client,
+            "migrate",
+            (0u32, CURRENT_STORAGE_VERSION).into_val(&env),
+        );
+        assert_eq!(client.storage_version(), CURRENT_STORAGE_VERSION);
+
+        // Existing state and positions are untouched
+        assert_eq!(client.get_state().liquidity, state_before.liquidity);
+        assert_eq!(client.get_position(&lp, &-600, &600).liquidity, position_before);
+    }
+
+    // === Donate Tests ===
+
+    #[test]
+    fn test_donate_goes_to_in_range_positions_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, token1, lp) = setup_live_pool(&env);
+
+        // A second LP entirely above the current price
+        let out_of_range = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&out_of_range, &1_000_000_000);
+        client.mint(&out_of_range, &out_of_range, &600, &1200, &1_000_000);
+
+        let donor = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&donor, &1_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&donor, &2_000_000);
+        client.donate(&donor, &1_000_000, &2_000_000);
+        assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token0).balance(&donor), 0);
+        assert_eq!(soroban_sdk::token::TokenClient::new(&env, &token1).balance(&donor), 0);
+
+        // The only in-range position receives the whole donation, less rounding
+        client.burn(&lp, &-600, &600, &0);
+        let (fees0, fees1) = client.collect(&lp, &lp, &-600, &600, &u128::MAX, &u128::MAX);
+        assert!(1_000_000 - fees0 <= 1);
+        assert!(2_000_000 - fees1 <= 1);
+
+        client.burn(&out_of_range, &600, &1200, &0);
+        assert_eq!(
+            client.collect(&out_of_range, &out_of_range, &600, &1200, &u128::MAX, &u128::MAX),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_donate_without_liquidity_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _) = setup_distant_liquidity(&env, 38400, 38460);
+        let donor = Address::generate(&env);
+        StellarAssetClient::new(&env, &client.token0()).mint(&donor, &1_000);
+        assert!(client.try_donate(&donor, &1_000, &0).is_err());
+    }
+
+    #[test]
+    fn test_donate_rejects_short_paying_token() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, taxed, donor) = setup_fee_on_transfer_pool(&env);
+        taxed.set_fee_bps(&100);
+        let (amount0, amount1) = if client.token0() == taxed.address {
+            (1_000_000, 0)
+        } else {
+            (0, 1_000_000)
+        };
+
+        assert_eq!(
+            client.try_donate(&donor, &amount0, &amount1),
+            Err(Ok(pool_error(PoolError::DonationShort)))
+        );
+        assert_eq!(client.fee_growth_globals(), (0, 0));
+    }
+
+    #[test]
+    fn test_donate_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, _) = setup_live_pool(&env);
+        let donor = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&donor, &5_000);
+        client.donate(&donor, &0, &5_000);
+
+        assert_single_event(
+            &env,
+            &client,
+            "donate",
+            (donor, 0u128, 5_000u128).into_val(&env),
+        );
+    }
+
+    // === Swap Event Tests ===
+
+    /// Assert the pool's last invocation published exactly the expected swap event
+    fn assert_swap_event(env: &Env, client: &DexPoolClient, recipient: &Address, result: &SwapResult) {
+        // Read events before any further invocation replaces them
+        let events = contract_events(env, &client.address);
+
+        let config = client.get_config();
+        let state = client.get_state();
+        assert_eq!(
+            events,
+            soroban_sdk::vec![
+                env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(env, "swap"), config.token0, config.token1).into_val(env),
+                    (
+                        recipient.clone(),
+                        result.amount0,
+                        result.amount1,
+                        state.sqrt_price_x96,
+                        state.liquidity,
+                        state.tick,
+                        result.fee_amount_total,
+                    )
+                        .into_val(env),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_swap_event_payload_both_directions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, token1, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&trader, &1_000_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+
+        let result = client.swap(&trader, &false, &1_000_000, &0, &false);
+        assert!(result.amount0 < 0 && result.amount1 == 1_000_000);
+        assert_swap_event(&env, &client, &trader, &result);
+
+        let result = client.swap(&trader, &true, &100_000, &0, &false);
+        assert!(result.amount0 == 100_000 && result.amount1 < 0);
+        assert_swap_event(&env, &client, &trader, &result);
+    }
+
+    #[test]
+    fn test_swap_event_payload_exact_output() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+
+        // Exact output of 500_000 token0, paid in token1
+        let result = client.swap(&trader, &false, &-500_000, &0, &false);
+        assert_eq!(result.amount0, -500_000);
+        assert!(result.amount1 > 500_000);
+        assert_swap_event(&env, &client, &trader, &result);
+    }
+
+    #[test]
+    fn test_swap_event_decodes_price_liquidity_and_fee() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+
+        let fee = expected_step_fee(&env, &client, false, 1_000_000);
+        let result = client.swap(&trader, &false, &1_000_000, &0, &false);
+        let events = contract_events(&env, &client.address);
+        let (_, _, data) = events.get(0).unwrap();
+
+        // Indexers decode the payload without reading pool state
+        let (recipient, amount0, amount1, sqrt_price, liquidity, tick, fee_amount): (
+            Address,
+            i128,
+            i128,
+            u128,
+            u128,
+            i32,
+            u128,
+        ) = TryFromVal::try_from_val(&env, &data).unwrap();
+        assert_eq!(recipient, trader);
+        assert_eq!((amount0, amount1), (result.amount0, result.amount1));
+        assert_eq!(sqrt_price, client.sqrt_price_x96());
+        assert_eq!(liquidity, client.liquidity());
+        assert_eq!(tick, client.tick());
+        assert_eq!(fee_amount, fee);
+        assert_eq!(fee_amount, result.fee_amount_total);
+    }
+
+    // === Liquidity Event Tests ===
+
+    /// Assert the pool's last invocation published exactly one event
+    fn assert_single_event(env: &Env, client: &DexPoolClient, topic: &str, data: Val) {
+        assert_eq!(
+            contract_events(env, &client.address),
+            soroban_sdk::vec![
+                env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(env, topic),).into_val(env),
+                    data,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mint_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let (amount0, amount1) = client.mint(&lp, &lp, &-120, &120, &5_000_000);
+
+        assert_single_event(
+            &env,
+            &client,
+            "mint",
+            (lp, -120i32, 120i32, 5_000_000u128, amount0, amount1).into_val(&env),
+        );
+        assert!(amount0 > 0 && amount1 > 0);
+    }
+
+    #[test]
+    fn test_burn_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+
+        // A zero burn pokes the position
+        let (amount0, amount1) = client.burn(&lp, &-600, &600, &0);
+
+        assert_single_event(
+            &env,
+            &client,
+            "burn",
+            (lp, -600i32, 600i32, 0u128, amount0, amount1).into_val(&env),
+        );
+    }
+
+    #[test]
+    fn test_collect_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        client.burn(&lp, &-600, &600, &1_000_000);
+        let recipient = Address::generate(&env);
+        let (amount0, amount1) =
+            client.collect(&lp, &recipient, &-600, &600, &u128::MAX, &u128::MAX);
+
+        assert_single_event(
+            &env,
+            &client,
+            "collect",
+            (lp, recipient, -600i32, 600i32, amount0, amount1).into_val(&env),
+        );
+        assert!(amount0 > 0 && amount1 > 0);
+    }
+
+    // === Flash Loan Tests ===
+
+    /// Borrower that repays `loan + fee - short` from its own balance
+    /// (the pool can't be re-entered, so tokens are stored up front)
+    #[contract]
+    struct MockBorrower;
+
+    #[contractimpl]
+    impl MockBorrower {
+        pub fn setup(
+            env: Env,
+            pool: Address,
+            tokens: (Address, Address),
+            amount0: i128,
+            amount1: i128,
+            short: i128,
+        ) {
+            let storage = env.storage().instance();
+            storage.set(&Symbol::new(&env, "pool"), &pool);
+            storage.set(&Symbol::new(&env, "tokens"), &tokens);
+            storage.set(&Symbol::new(&env, "amounts"), &(amount0, amount1));
+            storage.set(&Symbol::new(&env, "short"), &short);
+        }
+
+        pub fn dex_flash_callback(env: Env, fee0: i128, fee1: i128, data: Bytes) {
+            let storage = env.storage().instance();
+            let pool: Address = storage.get(&Symbol::new(&env, "pool")).unwrap();
+            let (token0, token1): (Address, Address) =
+                storage.get(&Symbol::new(&env, "tokens")).unwrap();
+            let (amount0, amount1): (i128, i128) =
+                storage.get(&Symbol::new(&env, "amounts")).unwrap();
+            let short: i128 = storage.get(&Symbol::new(&env, "short")).unwrap();
+            storage.set(&Symbol::new(&env, "data"), &data);
+
+            let me = env.current_contract_address();
+            token::Client::new(&env, &token0).transfer(&me, &pool, &(amount0 + fee0 - short));
+            token::Client::new(&env, &token1).transfer(&me, &pool, &(amount1 + fee1));
+        }
+
+        pub fn last_data(env: Env) -> Option<Bytes> {
+            env.storage().instance().get(&Symbol::new(&env, "data"))
+        }
+    }
+
+    fn setup_borrower<'a>(
+        env: &Env,
+        client: &DexPoolClient,
+        amount0: i128,
+        amount1: i128,
+        short: i128,
+    ) -> MockBorrowerClient<'a> {
+        let borrower = MockBorrowerClient::new(env, &env.register(MockBorrower, ()));
+        let tokens = (client.token0(), client.token1());
+        borrower.setup(&client.address, &tokens, &amount0, &amount1, &short);
+
+        // Float to cover fees
+        StellarAssetClient::new(env, &client.token0()).mint(&borrower.address, &1_000_000);
+        StellarAssetClient::new(env, &client.token1()).mint(&borrower.address, &1_000_000);
+        borrower
+    }
+
+    #[test]
+    fn test_flash_full_repayment_credits_fee_growth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, token1, _) = setup_live_pool(&env);
+        let liquidity = client.liquidity();
+        let borrower = setup_borrower(&env, &client, 1_000_000, 500_000, 0);
+        let reserves_before = client.get_reserves().unwrap();
+
+        let data = Bytes::from_array(&env, &[1, 2, 3]);
+        let (paid0, paid1) =
+            client.flash(&borrower.address, &1_000_000, &500_000, &borrower.address, &data);
+
+        // 0.3% fee, rounded up
+        assert_eq!((paid0, paid1), (3000, 1500));
+        assert_eq!(borrower.last_data(), Some(data));
+
+        let state = client.get_state();
+        assert_eq!(state.fee_growth_global_0_x128, dex_math::div_shl_128(&env, 3000, liquidity));
+        assert_eq!(state.fee_growth_global_1_x128, dex_math::div_shl_128(&env, 1500, liquidity));
+        assert_eq!(
+            client.get_reserves().unwrap(),
+            (reserves_before.0 + 3000, reserves_before.1 + 1500)
+        );
+        assert_eq!(
+            soroban_sdk::token::TokenClient::new(&env, &token0).balance(&client.address) as u128,
+            reserves_before.0 + 3000
+        );
+        assert_eq!(
+            soroban_sdk::token::TokenClient::new(&env, &token1).balance(&client.address) as u128,
+            reserves_before.1 + 1500
+        );
+    }
+
+    #[test]
+    fn test_flash_fee_protocol_share() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        client.set_fee_protocol(&5, &0);
+        let liquidity = client.liquidity();
+        let borrower = setup_borrower(&env, &client, 1_000_000, 0, 0);
+
+        client.flash(
+            &borrower.address,
+            &1_000_000,
+            &0,
+            &borrower.address,
+            &Bytes::new(&env),
+        );
+
+        let state = client.get_state();
+        assert_eq!(state.protocol_fees_0, 600);
+        assert_eq!(state.fee_growth_global_0_x128, dex_math::div_shl_128(&env, 2400, liquidity));
+        assert_eq!(state.fee_growth_global_1_x128, 0);
+    }
+
+    #[test]
+    fn test_flash_under_repayment_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        let borrower = setup_borrower(&env, &client, 1_000_000, 0, 1);
+        let state_before = client.get_state();
+        let reserves_before = client.get_reserves();
+
+        let result = client.try_flash(
+            &borrower.address,
+            &1_000_000,
+            &0,
+            &borrower.address,
+            &Bytes::new(&env),
+        );
+        assert!(result.is_err());
+
+        // Nothing changed
+        assert_eq!(
+            client.get_state().fee_growth_global_0_x128,
+            state_before.fee_growth_global_0_x128
+        );
+        assert_eq!(client.get_reserves(), reserves_before);
+        assert_eq!(borrower.last_data(), None);
+    }
+
+    // === Oracle Tests ===
+
+    #[test]
+    fn test_observe_sixty_second_twap() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, _) = setup_live_pool(&env);
+        client.increase_observation_cardinality(&10);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+        let tick_a = client.tick();
+
+        env.ledger().with_mut(|li| li.timestamp = 1060);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+        let tick_b = client.tick();
+        assert!(tick_b > tick_a && tick_a > 0);
+
+        // Window [1030, 1090]: 30s at tick_a, then 30s at tick_b
+        env.ledger().with_mut(|li| li.timestamp = 1090);
+        let cumulatives = client.observe(&soroban_sdk::vec![&env, 60u32, 0u32]);
+        let twap = (cumulatives.get(1).unwrap() - cumulatives.get(0).unwrap()) / 60;
+        assert_eq!(twap, (tick_a as i64 * 30 + tick_b as i64 * 30) / 60);
+
+        // Newest observation holds tick_a accrued over [1000, 1060]
+        let newest = client.get_observation(&client.get_state().observation_index);
+        assert_eq!(newest.block_timestamp, 1060);
+        assert_eq!(newest.tick_cumulative, tick_a as i64 * 60);
+        assert_eq!(client.get_state().observation_cardinality, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #126)")]
+    fn test_observe_before_oldest_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+
+        client.observe(&soroban_sdk::vec![&env, 101u32]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #125)")]
+    fn test_observation_cardinality_is_bounded() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        client.increase_observation_cardinality(&100);
+        assert_eq!(client.get_state().observation_cardinality_next, 100);
+
+        client.increase_observation_cardinality(&101);
+    }
+
+    #[test]
+    fn test_price_checkpoints_record_one_price_per_ledger_in_order() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+        assert_eq!(client.get_price_checkpoints().len(), 0);
+
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+        let first = client.get_slot0();
+
+        // A second swap in the same ledger leaves the checkpoint alone
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+
+        env.ledger().with_mut(|li| li.timestamp = 1005);
+        client.swap(&trader, &false, &1_000_000, &0, &false);
+        let third = client.get_slot0();
+
+        assert_eq!(
+            client.get_price_checkpoints(),
+            soroban_sdk::vec![
+                &env,
+                PriceCheckpoint {
+                    timestamp: 1000,
+                    sqrt_price_x96: first.sqrt_price_x96,
+                    tick: first.tick,
+                },
+                PriceCheckpoint {
+                    timestamp: 1005,
+                    sqrt_price_x96: third.sqrt_price_x96,
+                    tick: third.tick,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_price_checkpoints_overwrite_oldest_past_capacity() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+        assert_eq!(client.price_checkpoint_capacity(), 16);
+
+        for ledger in 1..=20u64 {
+            env.ledger().with_mut(|li| li.timestamp = ledger * 5);
+            client.swap(&trader, &false, &100_000, &0, &false);
+        }
+
+        // The 16 newest survive, oldest first, with rising prices
+        let checkpoints = client.get_price_checkpoints();
+        assert_eq!(checkpoints.len(), 16);
+        for (i, checkpoint) in checkpoints.iter().enumerate() {
+            assert_eq!(checkpoint.timestamp, (i as u64 + 5) * 5);
+        }
+        for i in 1..checkpoints.len() {
+            let (prev, next) = (checkpoints.get(i - 1).unwrap(), checkpoints.get(i).unwrap());
+            assert!(next.sqrt_price_x96 > prev.sqrt_price_x96);
+        }
+        let newest = checkpoints.last().unwrap();
+        assert_eq!(newest.sqrt_price_x96, client.sqrt_price_x96());
+
+        // Shrinking keeps the newest entries
+        client.set_price_checkpoint_capacity(&4);
+        let shrunk = client.get_price_checkpoints();
+        assert_eq!(shrunk.len(), 4);
+        assert_eq!(shrunk.first().unwrap().timestamp, 85);
+        assert_eq!(shrunk.last().unwrap(), newest);
+    }
+
+    #[test]
+    fn test_price_checkpoint_capacity_is_factory_only_and_bounded() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        assert_eq!(
+            client.try_set_price_checkpoint_capacity(&0),
+            Err(Ok(pool_error(PoolError::InvalidCheckpointCapacity)))
+        );
+        assert_eq!(
+            client.try_set_price_checkpoint_capacity(&65),
+            Err(Ok(pool_error(PoolError::InvalidCheckpointCapacity)))
+        );
+        client.set_price_checkpoint_capacity(&64);
+        assert_eq!(client.price_checkpoint_capacity(), 64);
+
+        let attacker = Address::generate(&env);
+        let result = client
+            .mock_auths(&[MockAuth {
+                address: &attacker,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "set_price_checkpoint_capacity",
+                    args: (1u32,).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_set_price_checkpoint_capacity(&1);
+        assert!(result.is_err());
+        assert_eq!(client.price_checkpoint_capacity(), 64);
+    }
+
+    #[test]
+    fn test_snapshot_cumulatives_inside_tracks_time_in_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, token1, lp) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+
+        // Range order above the current price
+        env.ledger().with_mut(|li| li.timestamp = 1000);
+        client.mint(&lp, &lp, &60, &120, &1_000_000);
+        let (tick_before, spl_before, seconds_before) =
+            client.snapshot_cumulatives_inside(&60, &120);
+
+        // Price enters the range at 1000 and stays for 100 seconds
+        let tick_90 = dex_math::get_sqrt_ratio_at_tick(&env, 90);
+        client.swap(&trader, &false, &1_000_000_000, &tick_90, &false);
+        assert_eq!(client.tick(), 90);
+
+        env.ledger().with_mut(|li| li.timestamp = 1100);
+        let (tick_inside, spl_inside, seconds_inside) =
+            client.snapshot_cumulatives_inside(&60, &120);
+        assert_eq!(seconds_inside - seconds_before, 100);
+        assert_eq!(tick_inside - tick_before, 90 * 100);
+        assert_eq!(
+            spl_inside.wrapping_sub(spl_before),
+            dex_math::div_shl_128(&env, 100, client.liquidity())
+        );
+
+        // Price leaves the range at 1100; time above it doesn't count
+        let tick_150 = dex_math::get_sqrt_ratio_at_tick(&env, 150);
+        client.swap(&trader, &false, &1_000_000_000, &tick_150, &false);
+        assert_eq!(client.tick(), 150);
+
+        env.ledger().with_mut(|li| li.timestamp = 1500);
+        let (_, _, seconds_after) = client.snapshot_cumulatives_inside(&60, &120);
+        assert_eq!(seconds_after - seconds_before, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #121)")]
+    fn test_snapshot_cumulatives_inside_requires_initialized_ticks() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        client.snapshot_cumulatives_inside(&60, &120);
     }
 
     // === Different Fee Tier Tests ===
@@ -488,6 +3637,464 @@ mod tests {
         }
     }
 
+    // === Empty Word Traversal Tests ===
+
+    /// Live pool whose only position sits 5 bitmap words from the price
+    fn setup_distant_liquidity<'a>(
+        env: &Env,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> (DexPoolClient<'a>, Address) {
+        let (client, _, token0, token1, lp) = setup_live_pool(env);
+        client.burn(&lp, &-600, &600, &1_000_000_000);
+        client.mint(&lp, &lp, &tick_lower, &tick_upper, &1_000_000);
+        assert_eq!(client.liquidity(), 0);
+
+        let trader = Address::generate(env);
+        StellarAssetClient::new(env, &token0).mint(&trader, &1_000_000);
+        StellarAssetClient::new(env, &token1).mint(&trader, &1_000_000);
+        (client, trader)
+    }
+
+    #[test]
+    fn test_swap_up_reaches_liquidity_five_words_away() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        let SwapResult { amount0, amount1, .. } =
+            client.swap(&trader, &false, &1_000, &0, &false);
+
+        assert!(amount0 < 0);
+        assert_eq!(amount1, 1_000);
+        assert!((38400..38460).contains(&client.tick()));
+        assert_eq!(client.liquidity(), 1_000_000);
+    }
+
+    #[test]
+    fn test_swap_down_reaches_liquidity_five_words_away() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_distant_liquidity(&env, -38460, -38400);
+        let SwapResult { amount0, amount1, .. } =
+            client.swap(&trader, &true, &1_000, &0, &false);
+
+        assert_eq!(amount0, 1_000);
+        assert!(amount1 < 0);
+        assert!((-38460..-38400).contains(&client.tick()));
+        assert_eq!(client.liquidity(), 1_000_000);
+    }
+
+    #[test]
+    fn test_swap_with_liquidity_beyond_price_limit_has_no_liquidity() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // The only liquidity sits past the price limit
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        let limit = dex_math::get_sqrt_ratio_at_tick(&env, 20_000);
+        assert!(client.try_swap(&trader, &false, &1_000, &limit, &false).is_err());
+
+        // With partial fills nothing trades and the price stays put
+        let result = client.swap(&trader, &false, &1_000, &limit, &true);
+        assert_eq!((result.amount0, result.amount1), (0, 0));
+        assert_eq!(result.sqrt_price_after_x96, Q96);
+        assert_eq!(client.sqrt_price_x96(), Q96);
+        assert_eq!(client.tick(), 0);
+    }
+
+    // === No Liquidity Tests ===
+
+    fn pool_error(error: PoolError) -> soroban_sdk::Error {
+        error.into()
+    }
+
+    /// Live pool with all liquidity burned: no liquidity and no initialized ticks
+    fn setup_empty_pool<'a>(env: &Env) -> (DexPoolClient<'a>, Address) {
+        let (client, _, token0, token1, lp) = setup_live_pool(env);
+        client.burn(&lp, &-600, &600, &1_000_000_000);
+        assert_eq!(client.get_tick_bitmap_word(&0), 0);
+
+        let trader = Address::generate(env);
+        StellarAssetClient::new(env, &token0).mint(&trader, &1_000_000);
+        StellarAssetClient::new(env, &token1).mint(&trader, &1_000_000);
+        (client, trader)
+    }
+
+    #[test]
+    fn test_swap_empty_pool_fails_both_directions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_empty_pool(&env);
+        let no_liquidity = Err(Ok(pool_error(PoolError::NoLiquidity)));
+        assert_eq!(client.try_swap(&trader, &true, &1_000, &0, &false), no_liquidity);
+        assert_eq!(client.try_swap(&trader, &false, &1_000, &0, &false), no_liquidity);
+        assert_eq!(client.try_swap(&trader, &true, &-1_000, &0, &false), no_liquidity);
+        assert_eq!(client.sqrt_price_x96(), Q96);
+    }
+
+    #[test]
+    fn test_swap_empty_pool_partial_fill_returns_zero() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_empty_pool(&env);
+        for zero_for_one in [true, false] {
+            let result = client.swap(&trader, &zero_for_one, &1_000, &0, &true);
+            assert_eq!((result.amount0, result.amount1), (0, 0));
+            assert_eq!(result.fee_amount_total, 0);
+            assert_eq!(result.tick_after, 0);
+        }
+
+        // No tokens moved and the price never left its start
+        let token0 = soroban_sdk::token::TokenClient::new(&env, &client.token0());
+        assert_eq!(token0.balance(&trader), 1_000_000);
+        assert_eq!(client.sqrt_price_x96(), Q96);
+    }
+
+    #[test]
+    fn test_swap_away_from_liquidity_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // Liquidity only above the price: selling token0 pushes the price down into nothing
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        assert!(client.try_swap(&trader, &true, &1_000, &0, &false).is_err());
+
+        // Liquidity only below the price: selling token1 pushes the price up into nothing
+        let (client, trader) = setup_distant_liquidity(&env, -38460, -38400);
+        assert!(client.try_swap(&trader, &false, &1_000, &0, &false).is_err());
+        client.swap(&trader, &true, &1_000, &0, &false);
+    }
+
+    // === Exact Output Fill Tests ===
+
+    #[test]
+    fn test_exact_output_beyond_liquidity_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // The only range holds far less than 1_000_000 token0
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        let tick = client.tick();
+        assert_eq!(
+            client.try_swap(&trader, &false, &-1_000_000, &0, &false),
+            Err(Ok(pool_error(PoolError::InsufficientLiquidity)))
+        );
+        assert_eq!(client.tick(), tick);
+    }
+
+    #[test]
+    fn test_exact_output_stopped_by_price_limit_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        let limit = dex_math::get_sqrt_ratio_at_tick(&env, 38430);
+        assert_eq!(
+            client.try_swap(&trader, &false, &-1_000_000, &limit, &false),
+            Err(Ok(pool_error(PoolError::PriceLimitReached)))
+        );
+    }
+
+    #[test]
+    fn test_exact_output_partial_fill_allowed() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        let limit = dex_math::get_sqrt_ratio_at_tick(&env, 38460);
+        let result = client.swap(&trader, &false, &-1_000_000, &limit, &true);
+
+        // The whole range is drained and the trader pays only for what came out
+        assert!(result.amount0 < 0 && result.amount0 > -1_000_000);
+        assert!(result.amount1 > 0);
+        assert!(!result.truncated);
+        assert_eq!(client.sqrt_price_x96(), limit);
+    }
+
+    #[test]
+    fn test_exact_output_within_liquidity_is_filled() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        let result = client.swap(&trader, &false, &-10, &0, &false);
+        assert_eq!(result.amount0, -10);
+        assert!(result.amount1 > 0);
+    }
+
+    // === Tick Crossing Cap Tests ===
+
+    /// Pool with 50 adjacent 60-tick positions above the price, one initialized tick per spacing
+    fn setup_many_ticks<'a>(env: &Env) -> (DexPoolClient<'a>, Address) {
+        // Crossing every tick up to the cap takes more than the default budget
+        env.cost_estimate().budget().reset_unlimited();
+
+        let (client, _, _, token1, lp) = setup_live_pool(env);
+        client.burn(&lp, &-600, &600, &1_000_000_000);
+        for i in 0..50 {
+            client.mint(&lp, &lp, &(i * 60), &(i * 60 + 60), &1_000_000);
+        }
+
+        let trader = Address::generate(env);
+        StellarAssetClient::new(env, &token1).mint(&trader, &1_000_000_000);
+        (client, trader)
+    }
+
+    #[test]
+    fn test_swap_past_crossing_cap_fails_without_partial_fill() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_many_ticks(&env);
+        assert!(client.try_swap(&trader, &false, &1_000_000, &0, &false).is_err());
+        assert_eq!(client.tick(), 0);
+    }
+
+    #[test]
+    fn test_swap_past_crossing_cap_returns_partial_fill() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_many_ticks(&env);
+        let result = client.swap(&trader, &false, &1_000_000, &0, &true);
+        let events = contract_events(&env, &client.address);
+
+        assert!(result.truncated);
+        assert_eq!(result.ticks_crossed, MAX_TICK_CROSSINGS_PER_SWAP);
+        assert_eq!(result.tick_after, 2400);
+        assert_eq!(result.tick_after, client.tick());
+        assert!(result.amount0 < 0);
+        assert!(result.amount1 > 0 && result.amount1 < 1_000_000);
+
+        // The truncation is flagged ahead of the regular swap event
+        let config = client.get_config();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events.slice(0..1),
+            soroban_sdk::vec![
+                &env,
+                (
+                    client.address.clone(),
+                    (Symbol::new(&env, "swap_truncated"), config.token0, config.token1)
+                        .into_val(&env),
+                    (trader.clone(), MAX_TICK_CROSSINGS_PER_SWAP, 1_000_000 - result.amount1)
+                        .into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_swap_under_crossing_cap_is_not_truncated() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_many_ticks(&env);
+        let limit = dex_math::get_sqrt_ratio_at_tick(&env, 1230);
+        let result = client.swap(&trader, &false, &1_000_000, &limit, &true);
+
+        // Ticks 60 through 1200 crossed, then the price limit stops the swap
+        assert!(!result.truncated);
+        assert_eq!(result.ticks_crossed, 20);
+        assert_eq!(result.sqrt_price_after_x96, limit);
+        assert_eq!(contract_events(&env, &client.address).len(), 1);
+    }
+
+    // === Swap Simulation Tests ===
+
+    /// Simulate, then execute the same swap and assert both agree
+    fn assert_simulation_matches(
+        client: &DexPoolClient,
+        trader: &Address,
+        zero_for_one: bool,
+        amount_specified: i128,
+        sqrt_price_limit_x96: u128,
+    ) -> SwapResult {
+        let quote = client.simulate_swap(&zero_for_one, &amount_specified, &sqrt_price_limit_x96);
+        let result = client.swap(
+            trader,
+            &zero_for_one,
+            &amount_specified,
+            &sqrt_price_limit_x96,
+            &true,
+        );
+        assert_eq!(
+            quote,
+            SwapSimulation {
+                amount0: result.amount0,
+                amount1: result.amount1,
+                sqrt_price_after_x96: result.sqrt_price_after_x96,
+                tick_after: result.tick_after,
+                liquidity_after: result.liquidity_after,
+                fee_amount_total: result.fee_amount_total,
+                ticks_crossed: result.ticks_crossed,
+                truncated: result.truncated,
+            }
+        );
+        result
+    }
+
+    #[test]
+    fn test_simulate_swap_matches_single_range_swaps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, token0, token1, _) = setup_live_pool(&env);
+        client.set_fee_protocol(&5, &5);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&trader, &1_000_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000);
+
+        assert_simulation_matches(&client, &trader, false, 1_000_000, 0);
+        assert_simulation_matches(&client, &trader, true, 250_000, 0);
+        assert_simulation_matches(&client, &trader, false, -300_000, 0);
+        assert_simulation_matches(&client, &trader, true, -300_000, 0);
+        let limit = dex_math::get_sqrt_ratio_at_tick(&env, -30);
+        let result = assert_simulation_matches(&client, &trader, true, 1_000_000_000, limit);
+        assert_eq!(result.sqrt_price_after_x96, limit);
+    }
+
+    #[test]
+    fn test_simulate_swap_matches_multi_tick_crossings() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_many_ticks(&env);
+        StellarAssetClient::new(&env, &client.token0()).mint(&trader, &1_000_000_000);
+
+        // Crosses a dozen initialized ticks, then comes back down across them
+        let result = assert_simulation_matches(&client, &trader, false, 40_000, 0);
+        assert!(result.ticks_crossed > 10 && !result.truncated);
+        let result = assert_simulation_matches(&client, &trader, true, -30_000, 0);
+        assert!(result.ticks_crossed > 5);
+
+        // Hits the crossing cap: the quote reports the truncated fill
+        let result = assert_simulation_matches(&client, &trader, false, 1_000_000, 0);
+        assert!(result.truncated);
+        assert_eq!(result.ticks_crossed, MAX_TICK_CROSSINGS_PER_SWAP);
+    }
+
+    #[test]
+    fn test_simulate_swap_matches_empty_word_traversal() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_distant_liquidity(&env, 38400, 38460);
+
+        // Nothing reachable below: a zero quote, like a partial-fill swap
+        let quote = client.simulate_swap(&true, &1_000, &0);
+        assert_eq!((quote.amount0, quote.amount1), (0, 0));
+        assert_eq!(quote.sqrt_price_after_x96, client.sqrt_price_x96());
+
+        let result = assert_simulation_matches(&client, &trader, false, 1_000, 0);
+        assert!((38400..38460).contains(&result.tick_after));
+    }
+
+    #[test]
+    fn test_simulate_swap_writes_nothing() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _) = setup_many_ticks(&env);
+        let slot0 = client.get_slot0();
+        let tick = client.get_tick(&600);
+        let reserves = client.get_reserves();
+
+        let quote = client.simulate_swap(&false, &40_000, &0);
+        assert_eq!(contract_events(&env, &client.address).len(), 0);
+        assert!(quote.ticks_crossed > 10);
+
+        assert_eq!(client.get_slot0(), slot0);
+        assert_eq!(client.get_reserves(), reserves);
+        let after = client.get_tick(&600);
+        assert_eq!(after.fee_growth_outside_1_x128, tick.fee_growth_outside_1_x128);
+        assert_eq!(after.seconds_outside, tick.seconds_outside);
+    }
+
+    // === Checked Swap Tests ===
+
+    #[test]
+    fn test_checked_swap_matches_swap() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (plain, _, _, plain_token1, _) = setup_live_pool(&env);
+        let (checked, _, _, checked_token1, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &plain_token1).mint(&trader, &1_000_000_000);
+        StellarAssetClient::new(&env, &checked_token1).mint(&trader, &1_000_000_000);
+
+        let expected = plain.swap(&trader, &false, &5_000_000, &0, &false);
+        let result = checked.checked_swap(&trader, &false, &5_000_000, &0, &false);
+        assert_eq!(result, expected);
+        assert_eq!(checked.get_slot0(), plain.get_slot0());
+        assert_eq!(checked.get_reserves(), plain.get_reserves());
+    }
+
+    #[test]
+    fn test_checked_swap_returns_parameter_errors() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, _) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        assert_eq!(
+            client.try_checked_swap(&trader, &true, &0, &0, &false),
+            Err(Ok(PoolError::ZeroAmount))
+        );
+        // A zero_for_one limit must sit below the current price
+        assert_eq!(
+            client.try_checked_swap(&trader, &true, &1_000, &(Q96 + 1), &false),
+            Err(Ok(PoolError::InvalidPriceLimit))
+        );
+
+        client.set_paused(&true);
+        assert_eq!(
+            client.try_checked_swap(&trader, &true, &1_000, &0, &false),
+            Err(Ok(PoolError::PoolPaused))
+        );
+    }
+
+    #[test]
+    fn test_checked_swap_returns_liquidity_errors() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (empty, trader) = setup_empty_pool(&env);
+        assert_eq!(
+            empty.try_checked_swap(&trader, &true, &1_000, &0, &false),
+            Err(Ok(PoolError::NoLiquidity))
+        );
+
+        let (distant, trader) = setup_distant_liquidity(&env, 38400, 38460);
+        let tick = distant.tick();
+        assert_eq!(
+            distant.try_checked_swap(&trader, &false, &-1_000_000, &0, &false),
+            Err(Ok(PoolError::InsufficientLiquidity))
+        );
+        assert_eq!(distant.tick(), tick);
+    }
+
+    #[test]
+    fn test_checked_swap_returns_crossing_cap_error() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, trader) = setup_many_ticks(&env);
+        assert_eq!(
+            client.try_checked_swap(&trader, &false, &1_000_000, &0, &false),
+            Err(Ok(PoolError::TooManyTicksCrossed))
+        );
+        assert_eq!(client.tick(), 0);
+
+        let result = client.checked_swap(&trader, &false, &1_000_000, &0, &true);
+        assert!(result.truncated);
+    }
+
     // === Max Liquidity Per Tick Tests ===
 
     #[test]
@@ -529,4 +4136,336 @@ mod tests {
             "Wider spacing should allow more liquidity per tick"
         );
     }
+
+    #[test]
+    fn test_tick_liquidity_headroom_tracks_mints() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.cost_estimate().budget().reset_unlimited();
+
+        let (client, _, token0, token1, lp) = setup_live_pool(&env);
+        let max = client.max_liquidity_per_tick();
+        assert_eq!(max, client.get_config().max_liquidity_per_tick);
+        assert_eq!(client.tick_liquidity_headroom(&-600), max - 1_000_000_000);
+        assert_eq!(client.tick_liquidity_headroom(&600), max - 1_000_000_000);
+        assert_eq!(client.tick_liquidity_headroom(&-120), max);
+
+        client.mint(&lp, &lp, &-600, &120, &1_000);
+        assert_eq!(client.tick_liquidity_headroom(&-600), max - 1_000_001_000);
+        assert_eq!(client.tick_liquidity_headroom(&120), max - 1_000);
+        assert_eq!(client.tick_liquidity_headroom(&600), max - 1_000_000_000);
+
+        // Filling the headroom exactly succeeds, one more unit overflows
+        let whale = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&whale, &(i128::MAX / 2));
+        StellarAssetClient::new(&env, &token1).mint(&whale, &(i128::MAX / 2));
+        let headroom = client.tick_liquidity_headroom(&600);
+        assert!(client
+            .try_mint(&whale, &whale, &-60, &600, &(headroom + 1))
+            .is_err());
+        client.mint(&whale, &whale, &-60, &600, &headroom);
+        assert_eq!(client.tick_liquidity_headroom(&600), 0);
+        let overflow = Err(Ok(pool_error(PoolError::LiquidityOverflow)));
+        assert_eq!(
+            client.try_mint(&whale, &whale, &-60, &600, &1_000),
+            overflow
+        );
+        assert_eq!(client.try_mint(&lp, &lp, &0, &600, &1_000), overflow);
+        client.mint(&lp, &lp, &-600, &0, &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #120)")]
+    fn test_mint_past_headroom_panics() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let headroom = client.tick_liquidity_headroom(&600);
+        client.mint(&lp, &lp, &-60, &600, &(headroom + 1));
+    }
+
+    // === Liquidity Conservation Tests ===
+
+    /// Sum liquidity_net over every initialized tick, walking the bitmap page by page
+    fn sum_liquidity_net(client: &DexPoolClient) -> i128 {
+        let mut total = 0i128;
+        let mut start = Some(dex_types::MIN_TICK);
+        while let Some(start_tick) = start {
+            let page = client.get_ticks_paginated(&start_tick, &dex_types::MAX_TICK, &50);
+            for populated in page.ticks.iter() {
+                total += populated.liquidity_net;
+            }
+            start = page.next_start_tick;
+        }
+        total
+    }
+
+    /// Deterministic xorshift generator so failures replay from the seed
+    struct TestRng(u64);
+
+    impl TestRng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    #[test]
+    fn test_random_mints_and_burns_conserve_liquidity_net() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.cost_estimate().budget().reset_unlimited();
+
+        let (client, _, token0, token1, lp) = setup_live_pool(&env);
+        let trader = Address::generate(&env);
+        StellarAssetClient::new(&env, &token0).mint(&trader, &1_000_000_000_000);
+        StellarAssetClient::new(&env, &token1).mint(&trader, &1_000_000_000_000);
+
+        let owners = [lp.clone(), Address::generate(&env), Address::generate(&env)];
+        let mut positions: Vec<(Address, i32, i32)> = Vec::new(&env);
+        positions.push_back((lp.clone(), -600, 600));
+        let mut rng = TestRng(0x9E37_79B9_7F4A_7C15);
+
+        for _ in 0..60 {
+            match rng.below(5) {
+                // Mint a random aligned range, some of which share ticks
+                0..=2 => {
+                    let owner = owners[rng.below(3) as usize].clone();
+                    let tick_lower = (rng.below(40) as i32 - 20) * 60;
+                    let tick_upper = tick_lower + (rng.below(10) as i32 + 1) * 60;
+                    let amount = (rng.below(100) as u128 + 1) * 1_000_000;
+                    client.mint(&lp, &owner, &tick_lower, &tick_upper, &amount);
+                    if !positions.contains((owner.clone(), tick_lower, tick_upper)) {
+                        positions.push_back((owner, tick_lower, tick_upper));
+                    }
+                }
+                // Burn part or all of a random position
+                3 => {
+                    let index = rng.below(positions.len() as u64) as u32;
+                    let (owner, lower, upper) = positions.get(index).unwrap();
+                    let held = client.get_position(&owner, &lower, &upper).liquidity;
+                    // All of it or half, rounded up
+                    let amount = held.div_ceil(rng.below(2) as u128 + 1);
+                    if held > 0 {
+                        client.burn(&owner, &lower, &upper, &amount);
+                    }
+                }
+                // Move the price so later updates land on both sides and ticks get crossed
+                _ => {
+                    let amount = (rng.below(5) as i128 + 1) * 1_000_000;
+                    client.swap(&trader, &(rng.below(2) == 0), &amount, &0, &true);
+                }
+            }
+
+            let net = sum_liquidity_net(&client);
+            assert!(invariants::liquidity_net_sums_to_zero(net));
+
+            let tick = client.tick();
+            let mut in_range = 0u128;
+            for (owner, lower, upper) in positions.iter() {
+                if lower <= tick && tick < upper {
+                    in_range += client.get_position(&owner, &lower, &upper).liquidity;
+                }
+            }
+            assert_eq!(client.liquidity(), in_range);
+        }
+    }
+
+    // === Swap Invariant Property Tests ===
+
+    /// Pool at `sqrt_price_x96` with a funded LP and trader but no liquidity yet
+    fn setup_pool_at_price<'a>(
+        env: &Env,
+        sqrt_price_x96: u128,
+    ) -> (DexPoolClient<'a>, Address, Address) {
+        let token_a = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let token_b = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        let factory = env.register(MockFactory, ());
+        let client = DexPoolClient::new(env, &env.register(DexPool, ()));
+        client.initialize(
+            &factory,
+            &token0,
+            &token1,
+            &3000u32,
+            &60i32,
+            &sqrt_price_x96,
+        );
+
+        let lp = Address::generate(env);
+        let trader = Address::generate(env);
+        for user in [&lp, &trader] {
+            StellarAssetClient::new(env, &token0).mint(user, &1_000_000_000_000_000_000);
+            StellarAssetClient::new(env, &token1).mint(user, &1_000_000_000_000_000_000);
+        }
+        (client, lp, trader)
+    }
+
+    /// The post-state checks the end-to-end swap rules in `swap_specs.rs` prove
+    fn assert_swap_invariants(
+        zero_for_one: bool,
+        limit: u128,
+        before: &PoolState,
+        after: &PoolState,
+    ) {
+        assert!(invariants::swap_direction_consistent(
+            zero_for_one,
+            before.sqrt_price_x96,
+            after.sqrt_price_x96
+        ));
+        assert!(invariants::swap_respects_limit(
+            zero_for_one,
+            after.sqrt_price_x96,
+            limit
+        ));
+        assert!(invariants::tick_in_bounds(after));
+        assert!(invariants::price_in_bounds(after));
+    }
+
+    #[test]
+    fn test_random_swaps_respect_direction_limit_and_bounds() {
+        let mut rng = TestRng(0xD1B5_4A32_D192_ED03);
+        let mut moved = 0u32;
+
+        for _ in 0..24 {
+            let env = Env::default();
+            env.mock_all_auths();
+            env.cost_estimate().budget().reset_unlimited();
+
+            let start_tick = rng.below(200_001) as i32 - 100_000;
+            let sqrt_price = dex_math::get_sqrt_ratio_at_tick(&env, start_tick);
+            let (client, lp, trader) = setup_pool_at_price(&env, sqrt_price);
+
+            // An aligned range near the price, which may or may not contain it
+            let tick_lower = start_tick.div_euclid(60) * 60 + (rng.below(20) as i32 - 15) * 60;
+            let tick_upper = tick_lower + (rng.below(20) as i32 + 1) * 60;
+            let liquidity = (rng.below(1_000) as u128 + 1) * 1_000_000;
+            client.mint(&lp, &lp, &tick_lower, &tick_upper, &liquidity);
+
+            let zero_for_one = rng.below(2) == 0;
+            let distance = rng.below(5_000) as i32 + 1;
+            let limit = match (zero_for_one, rng.below(4)) {
+                (true, 0) => dex_types::MIN_SQRT_RATIO + 1,
+                (false, 0) => dex_types::MAX_SQRT_RATIO - 1,
+                (true, _) => dex_math::get_sqrt_ratio_at_tick(&env, start_tick - distance),
+                (false, _) => dex_math::get_sqrt_ratio_at_tick(&env, start_tick + distance),
+            };
+            let magnitude = rng.below(1_000_000_000) as i128 + 1;
+            let amount = if rng.below(2) == 0 {
+                magnitude
+            } else {
+                -magnitude
+            };
+
+            let before = client.get_state();
+            // Only swaps that go through are constrained, as in the prover rules
+            if client
+                .try_swap(&trader, &zero_for_one, &amount, &limit, &true)
+                .is_err()
+            {
+                continue;
+            }
+            let after = client.get_state();
+
+            assert_swap_invariants(zero_for_one, limit, &before, &after);
+            if after.sqrt_price_x96 != before.sqrt_price_x96 {
+                moved += 1;
+            }
+        }
+
+        // Sanity: the properties were checked against swaps that moved the price
+        assert!(moved > 0);
+    }
+
+    #[test]
+    fn test_swaps_to_the_extreme_limits_stay_in_bounds() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.cost_estimate().budget().reset_unlimited();
+
+        let (client, lp, trader) = setup_pool_at_price(&env, Q96);
+        client.mint(&lp, &lp, &-600, &600, &1_000_000_000);
+
+        // Past the only range the price runs through empty space to the limit
+        for (zero_for_one, limit) in [
+            (true, dex_types::MIN_SQRT_RATIO + 1),
+            (false, dex_types::MAX_SQRT_RATIO - 1),
+        ] {
+            let before = client.get_state();
+            let amount = 1_000_000_000_000_000i128;
+            client.swap(&trader, &zero_for_one, &amount, &limit, &true);
+            let after = client.get_state();
+
+            assert_swap_invariants(zero_for_one, limit, &before, &after);
+            // The price can't fall below MIN_TICK's, so it stops just above it
+            assert_eq!(
+                after.sqrt_price_x96,
+                swap::clamp_limit_to_min_tick(limit, zero_for_one)
+            );
+        }
+    }
+
+    // === Minimum Liquidity Tests ===
+
+    #[test]
+    fn test_mint_below_min_liquidity_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let min = client.min_liquidity();
+        assert_eq!(min, dex_types::MIN_LIQUIDITY);
+        assert_eq!(
+            client.try_mint(&lp, &lp, &-120, &120, &(min - 1)),
+            Err(Ok(pool_error(PoolError::LiquidityTooSmall)))
+        );
+        assert!(!client.get_tick(&-120).initialized);
+    }
+
+    #[test]
+    fn test_mint_at_min_liquidity_succeeds() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let min = client.min_liquidity();
+        let (amount0, amount1) = client.mint(&lp, &lp, &-120, &120, &min);
+        assert!(amount0 > 0 && amount1 > 0);
+        assert_eq!(client.get_position(&lp, &-120, &120).liquidity, min);
+    }
+
+    #[test]
+    fn test_mint_far_out_of_range_still_owes_tokens() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // A thin range far above the price is the closest a mint gets to rounding to nothing
+        let (client, _, _, _, lp) = setup_live_pool(&env);
+        let (amount0, amount1) = client.mint(&lp, &lp, &443_520, &443_580, &1_000);
+        assert_eq!((amount0, amount1), (1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #106)")]
+    fn test_mint_owing_no_tokens_fails() {
+        let env = Env::default();
+        let pool = env.register(DexPool, ());
+        env.as_contract(&pool, || liquidity::validate_mint_amounts(&env, 0, 0));
+    }
 }
@@ -1,69 +1,94 @@
-use crate::storage::{get_config, get_position, get_state, set_position, set_state};
-use crate::tick::{flip_tick, get_fee_growth_inside, update as update_tick};
-use dex_math::{add_delta, get_sqrt_ratio_at_tick};
-use dex_types::{PositionInfo, PositionKey};
-use soroban_sdk::{token, Address, Env};
-
-/// Mint (add) liquidity to a position
+use crate::oracle;
+use crate::storage::{
+    adjust_reserves, get_config, get_position, get_state, register_position, set_position,
+    set_state, unregister_position,
+};
+use crate::tick::{
+    compute_fee_growth_inside, flip_range_ticks, get_fee_growth_inside, update_with_info,
+};
+use dex_math::{add_delta, get_amount0_delta, get_amount1_delta, get_sqrt_ratio_at_tick};
+use dex_types::{PoolError, PositionInfo, PositionKey, MIN_LIQUIDITY};
+use soroban_sdk::{panic_with_error, token, Address, Env, Symbol};
+
+/// Mint (add) liquidity to `recipient`'s position, pulling payment from `payer`
 pub fn mint(
     env: &Env,
+    payer: Address,
     recipient: Address,
     tick_lower: i32,
     tick_upper: i32,
     amount: u128,
 ) -> (u128, u128) {
     if amount == 0 {
-        panic!("Amount must be non-zero");
+        panic_with_error!(env, PoolError::ZeroAmount);
+    }
+    if amount < MIN_LIQUIDITY {
+        panic_with_error!(env, PoolError::LiquidityTooSmall);
     }
 
     let config = get_config(env);
-    let mut state = get_state(env);
 
-    // Validate ticks
-    validate_ticks(tick_lower, tick_upper, config.tick_spacing);
+    // Validate ticks before touching any state
+    validate_ticks(env, tick_lower, tick_upper, config.tick_spacing);
 
-    // Calculate amounts needed
-    let sqrt_ratio_lower = get_sqrt_ratio_at_tick(env, tick_lower);
-    let sqrt_ratio_upper = get_sqrt_ratio_at_tick(env, tick_upper);
+    let mut state = get_state(env);
 
-    let (amount0, amount1) = dex_math::get_amounts_for_liquidity(
+    if !dex_types::is_valid_recipient(
+        env,
+        &recipient,
+        &[&config.token0, &config.token1, &config.factory],
+    ) {
+        panic_with_error!(env, PoolError::InvalidRecipient);
+    }
+
+    // Calculate amounts owed, rounded up in the pool's favour
+    let (amount0, amount1) = compute_mint_amounts(
         env,
         state.sqrt_price_x96,
-        sqrt_ratio_lower,
-        sqrt_ratio_upper,
+        get_sqrt_ratio_at_tick(env, tick_lower),
+        get_sqrt_ratio_at_tick(env, tick_upper),
         amount,
     );
+    validate_mint_amounts(env, amount0, amount1);
+
+    // Oracle cumulatives checkpoint newly initialized ticks
+    let now = env.ledger().timestamp();
+    let cumulatives = oracle::current(env, &state, now);
 
     // Update ticks
-    let flipped_lower = update_tick(
+    let (flipped_lower, lower) = update_with_info(
         env,
         tick_lower,
         state.tick,
         amount as i128,
         state.fee_growth_global_0_x128,
         state.fee_growth_global_1_x128,
+        &cumulatives,
         false, // lower tick
         config.max_liquidity_per_tick,
     );
 
-    let flipped_upper = update_tick(
+    let (flipped_upper, upper) = update_with_info(
         env,
         tick_upper,
         state.tick,
         amount as i128,
         state.fee_growth_global_0_x128,
         state.fee_growth_global_1_x128,
+        &cumulatives,
         true, // upper tick
         config.max_liquidity_per_tick,
     );
 
     // Update tick bitmap if ticks were flipped
-    if flipped_lower {
-        flip_tick(env, tick_lower, config.tick_spacing);
-    }
-    if flipped_upper {
-        flip_tick(env, tick_upper, config.tick_spacing);
-    }
+    flip_range_ticks(
+        env,
+        tick_lower,
+        flipped_lower,
+        tick_upper,
+        flipped_upper,
+        config.tick_spacing,
+    );
 
     // Update position
     let position_key = PositionKey {
@@ -72,11 +97,13 @@ pub fn mint(
         tick_upper,
     };
 
-    let (fee_growth_inside_0, fee_growth_inside_1) = get_fee_growth_inside(
-        env,
+    // The ticks just written, rather than read back from storage
+    let (fee_growth_inside_0, fee_growth_inside_1) = compute_fee_growth_inside(
         tick_lower,
         tick_upper,
         state.tick,
+        &lower,
+        &upper,
         state.fee_growth_global_0_x128,
         state.fee_growth_global_1_x128,
     );
@@ -89,44 +116,78 @@ pub fn mint(
         fee_growth_inside_1,
     );
 
-    // Update liquidity if position is in range
+    // Update liquidity if position is in range, accruing the oracle first
     if state.tick >= tick_lower && state.tick < tick_upper {
+        oracle::write(env, &mut state, now);
         state.liquidity = add_delta(state.liquidity, amount as i128);
         set_state(env, &state);
     }
 
-    // Transfer tokens from user
+    // Pull payment from the payer and verify it arrived
     let contract_address = env.current_contract_address();
 
     if amount0 > 0 {
         let token0_client = token::Client::new(env, &config.token0);
-        token0_client.transfer(&recipient, &contract_address, &(amount0 as i128));
+        let balance_before = token0_client.balance(&contract_address);
+        token0_client.transfer(&payer, &contract_address, &(amount0 as i128));
+        if token0_client.balance(&contract_address) < balance_before + amount0 as i128 {
+            panic_with_error!(env, PoolError::MintPaymentShort);
+        }
     }
 
     if amount1 > 0 {
         let token1_client = token::Client::new(env, &config.token1);
-        token1_client.transfer(&recipient, &contract_address, &(amount1 as i128));
+        let balance_before = token1_client.balance(&contract_address);
+        token1_client.transfer(&payer, &contract_address, &(amount1 as i128));
+        if token1_client.balance(&contract_address) < balance_before + amount1 as i128 {
+            panic_with_error!(env, PoolError::MintPaymentShort);
+        }
     }
 
+    adjust_reserves(env, amount0 as i128, amount1 as i128);
+
+    env.events().publish(
+        (Symbol::new(env, "mint"),),
+        (recipient, tick_lower, tick_upper, amount, amount0, amount1),
+    );
+
     (amount0, amount1)
 }
 
 /// Burn (remove) liquidity from a position
-pub fn burn(env: &Env, tick_lower: i32, tick_upper: i32, amount: u128) -> (u128, u128) {
+///
+/// A zero `amount` pokes the position: earned fees are settled into
+/// `tokens_owed` while liquidity and ticks are left untouched.
+pub fn burn(
+    env: &Env,
+    owner: Address,
+    tick_lower: i32,
+    tick_upper: i32,
+    amount: u128,
+) -> (u128, u128) {
     let config = get_config(env);
-    let mut state = get_state(env);
 
-    // Get caller as position owner
-    let owner = env.current_contract_address(); // TODO: Get actual caller
+    // Validate ticks before touching any state
+    validate_ticks(env, tick_lower, tick_upper, config.tick_spacing);
 
-    // Validate ticks
-    validate_ticks(tick_lower, tick_upper, config.tick_spacing);
+    let position_key = PositionKey {
+        owner: owner.clone(),
+        tick_lower,
+        tick_upper,
+    };
+
+    // A zero burn only settles fees, which needs liquidity to have earned any
+    if amount == 0 && get_position(env, &position_key).liquidity == 0 {
+        panic_with_error!(env, PoolError::PositionHasNoLiquidity);
+    }
+
+    let mut state = get_state(env);
 
-    // Calculate amounts to return
+    // Calculate amounts to return, rounded down in the pool's favour
     let sqrt_ratio_lower = get_sqrt_ratio_at_tick(env, tick_lower);
     let sqrt_ratio_upper = get_sqrt_ratio_at_tick(env, tick_upper);
 
-    let (amount0, amount1) = dex_math::get_amounts_for_liquidity(
+    let (amount0, amount1) = compute_burn_amounts(
         env,
         state.sqrt_price_x96,
         sqrt_ratio_lower,
@@ -134,60 +195,75 @@ pub fn burn(env: &Env, tick_lower: i32, tick_upper: i32, amount: u128) -> (u128,
         amount,
     );
 
-    if amount > 0 {
+    let (fee_growth_inside_0, fee_growth_inside_1) = if amount > 0 {
+        let now = env.ledger().timestamp();
+        let cumulatives = oracle::current(env, &state, now);
+
         // Update ticks (negative liquidity delta)
-        let flipped_lower = update_tick(
+        let (flipped_lower, lower) = update_with_info(
             env,
             tick_lower,
             state.tick,
             -(amount as i128),
             state.fee_growth_global_0_x128,
             state.fee_growth_global_1_x128,
+            &cumulatives,
             false,
             config.max_liquidity_per_tick,
         );
 
-        let flipped_upper = update_tick(
+        let (flipped_upper, upper) = update_with_info(
             env,
             tick_upper,
             state.tick,
             -(amount as i128),
             state.fee_growth_global_0_x128,
             state.fee_growth_global_1_x128,
+            &cumulatives,
             true,
             config.max_liquidity_per_tick,
         );
 
         // Update tick bitmap if ticks were flipped
-        if flipped_lower {
-            flip_tick(env, tick_lower, config.tick_spacing);
-        }
-        if flipped_upper {
-            flip_tick(env, tick_upper, config.tick_spacing);
-        }
+        flip_range_ticks(
+            env,
+            tick_lower,
+            flipped_lower,
+            tick_upper,
+            flipped_upper,
+            config.tick_spacing,
+        );
 
-        // Update liquidity if position is in range
+        // Update liquidity if position is in range, accruing the oracle first
         if state.tick >= tick_lower && state.tick < tick_upper {
+            oracle::write(env, &mut state, now);
             state.liquidity = add_delta(state.liquidity, -(amount as i128));
             set_state(env, &state);
         }
-    }
 
-    // Update position and accumulate owed tokens
-    let position_key = PositionKey {
-        owner,
-        tick_lower,
-        tick_upper,
+        // An emptied tick is already gone from storage, but its outside
+        // values still price the fees earned up to this burn
+        compute_fee_growth_inside(
+            tick_lower,
+            tick_upper,
+            state.tick,
+            &lower,
+            &upper,
+            state.fee_growth_global_0_x128,
+            state.fee_growth_global_1_x128,
+        )
+    } else {
+        get_fee_growth_inside(
+            env,
+            tick_lower,
+            tick_upper,
+            state.tick,
+            state.fee_growth_global_0_x128,
+            state.fee_growth_global_1_x128,
+        )
     };
 
-    let (fee_growth_inside_0, fee_growth_inside_1) = get_fee_growth_inside(
-        env,
-        tick_lower,
-        tick_upper,
-        state.tick,
-        state.fee_growth_global_0_x128,
-        state.fee_growth_global_1_x128,
-    );
+    // Update position and accumulate owed tokens
 
     update_position(
         env,
@@ -203,12 +279,18 @@ pub fn burn(env: &Env, tick_lower: i32, tick_upper: i32, amount: u128) -> (u128,
     position.tokens_owed_1 += amount1;
     set_position(env, &position_key, &position);
 
+    env.events().publish(
+        (Symbol::new(env, "burn"),),
+        (owner, tick_lower, tick_upper, amount, amount0, amount1),
+    );
+
     (amount0, amount1)
 }
 
 /// Collect fees and withdrawn tokens from a position
 pub fn collect(
     env: &Env,
+    owner: Address,
     recipient: Address,
     tick_lower: i32,
     tick_upper: i32,
@@ -217,11 +299,16 @@ pub fn collect(
 ) -> (u128, u128) {
     let config = get_config(env);
 
-    // Get position owner (caller)
-    let owner = env.current_contract_address(); // TODO: Get actual caller
+    if !dex_types::is_valid_recipient(
+        env,
+        &recipient,
+        &[&config.token0, &config.token1, &config.factory],
+    ) {
+        panic_with_error!(env, PoolError::InvalidRecipient);
+    }
 
     let position_key = PositionKey {
-        owner,
+        owner: owner.clone(),
         tick_lower,
         tick_upper,
     };
@@ -256,10 +343,121 @@ pub fn collect(
         );
     }
 
+    adjust_reserves(env, -(amount0 as i128), -(amount1 as i128));
+
+    env.events().publish(
+        (Symbol::new(env, "collect"),),
+        (owner, recipient, tick_lower, tick_upper, amount0, amount1),
+    );
+
     (amount0, amount1)
 }
 
+/// Token amounts owed for minting `liquidity`, rounded up (pure computation with env for U256)
+pub fn compute_mint_amounts(
+    env: &Env,
+    sqrt_price_x96: u128,
+    sqrt_ratio_lower: u128,
+    sqrt_ratio_upper: u128,
+    liquidity: u128,
+) -> (u128, u128) {
+    compute_liquidity_amounts(
+        env,
+        sqrt_price_x96,
+        sqrt_ratio_lower,
+        sqrt_ratio_upper,
+        liquidity,
+        true,
+    )
+}
+
+/// Reject a mint that owes no tokens at all
+///
+/// Mint amounts round up, so this only trips if that rounding ever changes.
+pub fn validate_mint_amounts(env: &Env, amount0: u128, amount1: u128) {
+    if amount0 == 0 && amount1 == 0 {
+        panic_with_error!(env, PoolError::ZeroAmount);
+    }
+}
+
+/// Token amounts paid for burning `liquidity`, rounded down (pure computation with env for U256)
+pub fn compute_burn_amounts(
+    env: &Env,
+    sqrt_price_x96: u128,
+    sqrt_ratio_lower: u128,
+    sqrt_ratio_upper: u128,
+    liquidity: u128,
+) -> (u128, u128) {
+    compute_liquidity_amounts(
+        env,
+        sqrt_price_x96,
+        sqrt_ratio_lower,
+        sqrt_ratio_upper,
+        liquidity,
+        false,
+    )
+}
+
+/// Token amounts backing `liquidity` over a range at the current price
+///
+/// Mints round up and burns round down, so a mint/burn cycle can never take
+/// out more than it put in.
+fn compute_liquidity_amounts(
+    env: &Env,
+    sqrt_price_x96: u128,
+    sqrt_ratio_lower: u128,
+    sqrt_ratio_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> (u128, u128) {
+    if sqrt_price_x96 <= sqrt_ratio_lower {
+        // Below range - all token0
+        (
+            get_amount0_delta(env, sqrt_ratio_lower, sqrt_ratio_upper, liquidity, round_up),
+            0,
+        )
+    } else if sqrt_price_x96 < sqrt_ratio_upper {
+        // In range - both tokens
+        (
+            get_amount0_delta(env, sqrt_price_x96, sqrt_ratio_upper, liquidity, round_up),
+            get_amount1_delta(env, sqrt_ratio_lower, sqrt_price_x96, liquidity, round_up),
+        )
+    } else {
+        // Above range - all token1
+        (
+            0,
+            get_amount1_delta(env, sqrt_ratio_lower, sqrt_ratio_upper, liquidity, round_up),
+        )
+    }
+}
+
+/// Tokens owed to a position including fees earned since its last checkpoint
+///
+/// Returns `tokens_owed + (liquidity * (fee_growth_inside - last)) >> 128` for
+/// each token. Fee growth is Q128 per unit of liquidity and wraps by design.
+pub fn compute_tokens_owed(
+    env: &Env,
+    position: &PositionInfo,
+    fee_growth_inside_0_x128: u128,
+    fee_growth_inside_1_x128: u128,
+) -> (u128, u128) {
+    if position.liquidity == 0 {
+        return (position.tokens_owed_0, position.tokens_owed_1);
+    }
+
+    let fee_delta_0 = fee_growth_inside_0_x128.wrapping_sub(position.fee_growth_inside_0_last_x128);
+    let fee_delta_1 = fee_growth_inside_1_x128.wrapping_sub(position.fee_growth_inside_1_last_x128);
+
+    (
+        position.tokens_owed_0 + dex_math::mul_shr_128(env, fee_delta_0, position.liquidity),
+        position.tokens_owed_1 + dex_math::mul_shr_128(env, fee_delta_1, position.liquidity),
+    )
+}
+
 /// Update a position with liquidity change and fee accumulation
+///
+/// Called on every mint and burn (including zero-amount pokes) so fees are
+/// credited to `tokens_owed` before the position's liquidity changes.
 fn update_position(
     env: &Env,
     key: &PositionKey,
@@ -268,33 +466,17 @@ fn update_position(
     fee_growth_inside_1_x128: u128,
 ) {
     let mut position = get_position(env, key);
+    let had_liquidity = position.liquidity > 0;
 
-    // Calculate fees earned since last update
-    if position.liquidity > 0 {
-        // Use mul_div to compute (fee_delta * liquidity) / 2^128
-        // fee_growth is in X128 format, so we need to divide by 2^128
-        let fee_delta_0 = fee_growth_inside_0_x128.wrapping_sub(position.fee_growth_inside_0_last_x128);
-        let fee_delta_1 = fee_growth_inside_1_x128.wrapping_sub(position.fee_growth_inside_1_last_x128);
-
-        // Compute tokens_owed = (fee_delta * liquidity) >> 128
-        // Since we can't shift u128 by 128, we use mul_div with Q128
-        let q128: u128 = 1u128 << 64; // Use 2^64 as intermediate
-        let tokens_owed_0 = dex_math::mul_div(
-            env,
-            dex_math::mul_div(env, fee_delta_0, position.liquidity, q128),
-            1,
-            q128,
-        );
-        let tokens_owed_1 = dex_math::mul_div(
-            env,
-            dex_math::mul_div(env, fee_delta_1, position.liquidity, q128),
-            1,
-            q128,
-        );
-
-        position.tokens_owed_0 += tokens_owed_0;
-        position.tokens_owed_1 += tokens_owed_1;
-    }
+    // Settle fees earned since the last checkpoint at the old liquidity
+    let (tokens_owed_0, tokens_owed_1) = compute_tokens_owed(
+        env,
+        &position,
+        fee_growth_inside_0_x128,
+        fee_growth_inside_1_x128,
+    );
+    position.tokens_owed_0 = tokens_owed_0;
+    position.tokens_owed_1 = tokens_owed_1;
 
     // Update position liquidity
     position.liquidity = add_delta(position.liquidity, liquidity_delta);
@@ -304,23 +486,31 @@ fn update_position(
     position.fee_growth_inside_1_last_x128 = fee_growth_inside_1_x128;
 
     set_position(env, key, &position);
+
+    // Keep the position registry to positions holding liquidity
+    let has_liquidity = position.liquidity > 0;
+    if !had_liquidity && has_liquidity {
+        register_position(env, key);
+    } else if had_liquidity && !has_liquidity {
+        unregister_position(env, key);
+    }
 }
 
-/// Validate tick parameters
-fn validate_ticks(tick_lower: i32, tick_upper: i32, tick_spacing: i32) {
+/// Validate tick parameters: ordered, within MIN_TICK..=MAX_TICK and aligned to spacing
+pub fn validate_ticks(env: &Env, tick_lower: i32, tick_upper: i32, tick_spacing: i32) {
     if tick_lower >= tick_upper {
-        panic!("tick_lower must be less than tick_upper");
+        panic_with_error!(env, PoolError::InvalidTickRange);
     }
     if tick_lower < dex_types::MIN_TICK {
-        panic!("tick_lower too low");
+        panic_with_error!(env, PoolError::TickLowerTooLow);
     }
     if tick_upper > dex_types::MAX_TICK {
-        panic!("tick_upper too high");
+        panic_with_error!(env, PoolError::TickUpperTooHigh);
     }
     if tick_lower % tick_spacing != 0 {
-        panic!("tick_lower not on spacing");
+        panic_with_error!(env, PoolError::TickNotOnSpacing);
     }
     if tick_upper % tick_spacing != 0 {
-        panic!("tick_upper not on spacing");
+        panic_with_error!(env, PoolError::TickNotOnSpacing);
     }
 }
@@ -0,0 +1,379 @@
+// ============================================================================
+// ORACLE MODULE - Tick accumulator ring buffer for TWAPs
+// ============================================================================
+//
+// Each observation records the cumulative sum of tick * seconds, and of
+// seconds / in-range liquidity, at a ledger timestamp. The time-weighted
+// average tick between two timestamps is the difference of their cumulatives
+// divided by the elapsed seconds.
+//
+// 1. PURE FUNCTIONS (formally verifiable):
+//    - transform: Advance an observation to a later timestamp
+//    - interpolate_tick_cumulative: Cumulative between two observations
+//    - interpolate_seconds_per_liquidity: Same for seconds per liquidity
+//    - next_cardinality: Slot count after a write
+//
+// 2. SIDE EFFECT FUNCTIONS:
+//    - initialize: Write the first observation at the pool start timestamp
+//    - write: Append an observation (called before the tick or liquidity changes)
+//    - grow: Pre-allocate slots up to a new cardinality
+//    - observe: Read tick cumulatives for several seconds_agos
+//    - current: Cumulatives as of now, used to checkpoint ticks
+//    - write_price_checkpoint: Record the post-swap price, once per timestamp
+//    - resize_price_checkpoints: Change how many price checkpoints are kept
+//
+// The buffer is capped at MAX_OBSERVATION_CARDINALITY slots, each a separate
+// persistent entry, so a swap writes at most one observation entry.
+//
+// Price checkpoints are a lighter history kept as one instance entry: the
+// last few post-swap prices, oldest first, for sparklines and heuristics
+// that don't need accumulators.
+//
+// ============================================================================
+
+use crate::storage::{
+    get_observation, get_price_checkpoint_capacity, get_price_checkpoints, set_observation,
+    set_price_checkpoint_capacity, set_price_checkpoints, MAX_PRICE_CHECKPOINT_CAPACITY,
+};
+use dex_types::{Observation, PoolError, PoolState, PriceCheckpoint};
+use soroban_sdk::{panic_with_error, Env, Vec};
+
+/// Maximum number of observation slots per pool
+pub const MAX_OBSERVATION_CARDINALITY: u32 = 100;
+
+/// Oracle accumulators at a point in time, recorded on ticks as they are
+/// initialized and crossed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cumulatives {
+    /// Ledger timestamp the cumulatives are taken at
+    pub time: u64,
+    pub tick_cumulative: i64,
+    pub seconds_per_liquidity_x128: u128,
+}
+
+// ============================================================================
+// PURE FUNCTIONS
+// ============================================================================
+
+/// Advance an observation to `block_timestamp`, accruing `tick` and `liquidity`
+/// for the elapsed time (pure computation with env for U256)
+pub fn transform(
+    env: &Env,
+    last: &Observation,
+    block_timestamp: u64,
+    tick: i32,
+    liquidity: u128,
+) -> Observation {
+    let delta = block_timestamp - last.block_timestamp;
+    Observation {
+        block_timestamp,
+        tick_cumulative: last.tick_cumulative + tick as i64 * delta as i64,
+        seconds_per_liquidity_x128: last
+            .seconds_per_liquidity_x128
+            .wrapping_add(dex_math::div_shl_128(env, delta as u128, liquidity.max(1))),
+        initialized: true,
+    }
+}
+
+/// Tick cumulative at `target`, which lies between `before` and `after`
+pub fn interpolate_tick_cumulative(before: &Observation, after: &Observation, target: u64) -> i64 {
+    if target == before.block_timestamp {
+        return before.tick_cumulative;
+    }
+    if target == after.block_timestamp {
+        return after.tick_cumulative;
+    }
+
+    let observation_time_delta = (after.block_timestamp - before.block_timestamp) as i64;
+    let target_delta = (target - before.block_timestamp) as i64;
+    before.tick_cumulative
+        + ((after.tick_cumulative - before.tick_cumulative) / observation_time_delta) * target_delta
+}
+
+/// Seconds per liquidity cumulative at `target`, which lies between `before` and `after`
+/// (pure computation with env for U256)
+pub fn interpolate_seconds_per_liquidity(
+    env: &Env,
+    before: &Observation,
+    after: &Observation,
+    target: u64,
+) -> u128 {
+    if target == before.block_timestamp {
+        return before.seconds_per_liquidity_x128;
+    }
+    if target == after.block_timestamp {
+        return after.seconds_per_liquidity_x128;
+    }
+
+    let observation_time_delta = (after.block_timestamp - before.block_timestamp) as u128;
+    let target_delta = (target - before.block_timestamp) as u128;
+    let growth = after
+        .seconds_per_liquidity_x128
+        .wrapping_sub(before.seconds_per_liquidity_x128);
+    before
+        .seconds_per_liquidity_x128
+        .wrapping_add(dex_math::mul_div(env, growth, target_delta, observation_time_delta))
+}
+
+/// Cardinality after writing past `index`: grows only once the last slot is reached
+pub fn next_cardinality(index: u32, cardinality: u32, cardinality_next: u32) -> u32 {
+    if cardinality_next > cardinality && index == cardinality - 1 {
+        cardinality_next
+    } else {
+        cardinality
+    }
+}
+
+// ============================================================================
+// SIDE EFFECT FUNCTIONS
+// ============================================================================
+
+/// Write the first observation when the pool is initialized
+pub fn initialize(env: &Env, block_timestamp: u64) {
+    set_observation(
+        env,
+        0,
+        &Observation {
+            block_timestamp,
+            tick_cumulative: 0,
+            seconds_per_liquidity_x128: 0,
+            initialized: true,
+        },
+    );
+}
+
+/// Append an observation accruing `state.tick` and `state.liquidity` up to `block_timestamp`
+///
+/// Must be called before the tick or in-range liquidity changes. At most one
+/// observation is written per timestamp; updates `state`'s index and
+/// cardinality in place.
+pub fn write(env: &Env, state: &mut PoolState, block_timestamp: u64) {
+    let last = get_observation(env, state.observation_index);
+    if last.block_timestamp == block_timestamp {
+        return;
+    }
+
+    let cardinality = next_cardinality(
+        state.observation_index,
+        state.observation_cardinality,
+        state.observation_cardinality_next,
+    );
+    let index = (state.observation_index + 1) % cardinality;
+
+    set_observation(
+        env,
+        index,
+        &transform(env, &last, block_timestamp, state.tick, state.liquidity),
+    );
+
+    state.observation_index = index;
+    state.observation_cardinality = cardinality;
+}
+
+/// Pre-allocate observation slots so future writes don't pay for new entries
+/// Returns the new cardinality_next
+pub fn grow(env: &Env, current: u32, next: u32) -> u32 {
+    if next > MAX_OBSERVATION_CARDINALITY {
+        panic_with_error!(env, PoolError::CardinalityTooLarge);
+    }
+    if next <= current {
+        return current;
+    }
+
+    // Placeholder timestamps make the slots non-empty without marking them initialized
+    for index in current..next {
+        set_observation(
+            env,
+            index,
+            &Observation {
+                block_timestamp: 1,
+                tick_cumulative: 0,
+                seconds_per_liquidity_x128: 0,
+                initialized: false,
+            },
+        );
+    }
+    next
+}
+
+/// Tick and seconds per liquidity cumulatives `seconds_ago` before `time`
+fn observe_single(env: &Env, state: &PoolState, time: u64, seconds_ago: u32) -> (i64, u128) {
+    let last = get_observation(env, state.observation_index);
+
+    let target = time
+        .checked_sub(seconds_ago as u64)
+        .unwrap_or_else(|| panic_with_error!(env, PoolError::ObservationTooOld));
+
+    // At or after the newest observation: extrapolate with the current tick and liquidity
+    if target >= last.block_timestamp {
+        let observation = if target == last.block_timestamp {
+            last
+        } else {
+            transform(env, &last, target, state.tick, state.liquidity)
+        };
+        return (
+            observation.tick_cumulative,
+            observation.seconds_per_liquidity_x128,
+        );
+    }
+
+    let cardinality = state.observation_cardinality;
+    let oldest_index = (state.observation_index + 1) % cardinality;
+    let mut oldest = get_observation(env, oldest_index);
+    if !oldest.initialized {
+        oldest = get_observation(env, 0);
+    }
+    if target < oldest.block_timestamp {
+        panic_with_error!(env, PoolError::ObservationTooOld);
+    }
+
+    // Binary search the ring buffer for observations surrounding target
+    let mut l = oldest_index;
+    let mut r = l + cardinality - 1;
+    loop {
+        let i = (l + r) / 2;
+        let before = get_observation(env, i % cardinality);
+        if !before.initialized {
+            l = i + 1;
+            continue;
+        }
+        let after = get_observation(env, (i + 1) % cardinality);
+
+        let target_at_or_after = before.block_timestamp <= target;
+        if target_at_or_after && target <= after.block_timestamp {
+            return (
+                interpolate_tick_cumulative(&before, &after, target),
+                interpolate_seconds_per_liquidity(env, &before, &after, target),
+            );
+        }
+
+        if !target_at_or_after {
+            r = i - 1;
+        } else {
+            l = i + 1;
+        }
+    }
+}
+
+/// Tick cumulatives for each entry of `seconds_agos`, relative to `time`
+pub fn observe(env: &Env, state: &PoolState, time: u64, seconds_agos: &Vec<u32>) -> Vec<i64> {
+    let mut tick_cumulatives = Vec::new(env);
+    for seconds_ago in seconds_agos.iter() {
+        tick_cumulatives.push_back(observe_single(env, state, time, seconds_ago).0);
+    }
+    tick_cumulatives
+}
+
+/// Cumulatives as of `time` for the pool's current tick and liquidity
+pub fn current(env: &Env, state: &PoolState, time: u64) -> Cumulatives {
+    let (tick_cumulative, seconds_per_liquidity_x128) = observe_single(env, state, time, 0);
+    Cumulatives {
+        time,
+        tick_cumulative,
+        seconds_per_liquidity_x128,
+    }
+}
+
+/// Record the price a swap left the pool at
+///
+/// At most one checkpoint is written per timestamp; once the history holds
+/// `capacity` entries the oldest is dropped.
+pub fn write_price_checkpoint(env: &Env, block_timestamp: u64, sqrt_price_x96: u128, tick: i32) {
+    let mut checkpoints = get_price_checkpoints(env);
+    if let Some(last) = checkpoints.last() {
+        if last.timestamp == block_timestamp {
+            return;
+        }
+    }
+
+    let capacity = get_price_checkpoint_capacity(env);
+    while checkpoints.len() >= capacity {
+        checkpoints.pop_front();
+    }
+    checkpoints.push_back(PriceCheckpoint {
+        timestamp: block_timestamp,
+        sqrt_price_x96,
+        tick,
+    });
+    set_price_checkpoints(env, &checkpoints);
+}
+
+/// Keep `capacity` price checkpoints, dropping the oldest ones beyond it
+pub fn resize_price_checkpoints(env: &Env, capacity: u32) {
+    if capacity == 0 || capacity > MAX_PRICE_CHECKPOINT_CAPACITY {
+        panic_with_error!(env, PoolError::InvalidCheckpointCapacity);
+    }
+    set_price_checkpoint_capacity(env, capacity);
+
+    let mut checkpoints = get_price_checkpoints(env);
+    if checkpoints.len() > capacity {
+        while checkpoints.len() > capacity {
+            checkpoints.pop_front();
+        }
+        set_price_checkpoints(env, &checkpoints);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(block_timestamp: u64, tick_cumulative: i64) -> Observation {
+        Observation {
+            block_timestamp,
+            tick_cumulative,
+            seconds_per_liquidity_x128: 0,
+            initialized: true,
+        }
+    }
+
+    #[test]
+    fn test_transform_accrues_tick_times_elapsed() {
+        let env = Env::default();
+        let next = transform(&env, &observation(100, 500), 160, -20, 1 << 64);
+        assert_eq!(next.block_timestamp, 160);
+        assert_eq!(next.tick_cumulative, 500 - 20 * 60);
+        // 60 seconds over 2^64 liquidity, as Q128.128
+        assert_eq!(next.seconds_per_liquidity_x128, 60 << 64);
+    }
+
+    #[test]
+    fn test_transform_treats_zero_liquidity_as_one() {
+        let env = Env::default();
+        let last = observation(100, 0);
+        assert_eq!(
+            transform(&env, &last, 103, 0, 0),
+            transform(&env, &last, 103, 0, 1)
+        );
+    }
+
+    #[test]
+    fn test_interpolate_seconds_per_liquidity() {
+        let env = Env::default();
+        let before = observation(1000, 0);
+        let mut after = observation(1060, 0);
+        after.seconds_per_liquidity_x128 = 600;
+
+        assert_eq!(interpolate_seconds_per_liquidity(&env, &before, &after, 1000), 0);
+        assert_eq!(interpolate_seconds_per_liquidity(&env, &before, &after, 1030), 300);
+        assert_eq!(interpolate_seconds_per_liquidity(&env, &before, &after, 1060), 600);
+    }
+
+    #[test]
+    fn test_interpolate_between_observations() {
+        let before = observation(1000, 0);
+        let after = observation(1060, 600); // tick 10 for 60s
+
+        assert_eq!(interpolate_tick_cumulative(&before, &after, 1000), 0);
+        assert_eq!(interpolate_tick_cumulative(&before, &after, 1030), 300);
+        assert_eq!(interpolate_tick_cumulative(&before, &after, 1060), 600);
+    }
+
+    #[test]
+    fn test_next_cardinality_grows_only_at_last_slot() {
+        assert_eq!(next_cardinality(0, 1, 5), 5);
+        assert_eq!(next_cardinality(1, 3, 5), 3);
+        assert_eq!(next_cardinality(2, 3, 5), 5);
+        assert_eq!(next_cardinality(2, 3, 3), 3);
+    }
+}
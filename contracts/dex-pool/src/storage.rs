@@ -1,5 +1,8 @@
-use dex_types::{PoolConfig, PoolState, PositionInfo, PositionKey, TickInfo};
-use soroban_sdk::{contracttype, Env};
+use dex_types::{
+    Observation, PoolConfig, PoolError, PoolState, PositionInfo, PositionKey, PriceCheckpoint,
+    TickInfo,
+};
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, FromVal, Map, Symbol, Val, Vec};
 
 // ============================================================================
 // SOROBAN RESOURCE LIMITS - Critical constraints for pool operations:
@@ -32,6 +35,22 @@ use soroban_sdk::{contracttype, Env};
 /// We reserve some writes for state updates.
 pub const MAX_TICK_CROSSINGS_PER_SWAP: u32 = 40;
 
+/// Maximum number of ticks returned per tick lens page.
+/// Each populated tick costs one read entry on top of the bitmap word.
+pub const MAX_POPULATED_TICKS_PER_PAGE: u32 = 50;
+
+/// Maximum number of bitmap words scanned per tick range page.
+/// Empty words cost a read entry each, so sparse ranges are paged by words too.
+pub const MAX_BITMAP_WORDS_PER_PAGE: u32 = 50;
+
+/// Maximum number of position keys returned per page.
+/// Each key costs one read entry.
+pub const MAX_POSITIONS_PER_PAGE: u32 = 50;
+
+/// Storage layout version written by this code.
+/// Bump it together with a new step in `DexPool::migrate` when a layout changes.
+pub const CURRENT_STORAGE_VERSION: u32 = 1;
+
 /// Storage keys for the pool contract
 #[contracttype]
 #[derive(Clone)]
@@ -46,6 +65,30 @@ pub enum DataKey {
     TickBitmap(i32),
     /// Position data: PositionKey -> PositionInfo (Persistent storage)
     Position(PositionKey),
+    /// Number of positions holding liquidity, counter for PositionAt (Instance storage)
+    PositionCount,
+    /// Registry index -> PositionKey of a position holding liquidity (Persistent storage)
+    PositionAt(u32),
+    /// PositionKey -> its registry index, for removal (Persistent storage)
+    PositionIndex(PositionKey),
+    /// Position operator approval: (PositionKey, operator) -> true (Persistent storage)
+    PositionOperator(PositionKey, Address),
+    /// Solvency counters: (reserve0, reserve1) tokens accounted to the pool (Instance storage)
+    Reserves,
+    /// Protocol fee denominators: (fee_protocol_0, fee_protocol_1) (Instance storage)
+    FeeProtocol,
+    /// Oracle ring buffer slot: index -> Observation (Persistent storage)
+    Observation(u32),
+    /// Reentrancy guard, present while a state-changing call is running (Instance storage)
+    Locked,
+    /// Per-pool swap pause flag set by the factory (Instance storage)
+    Paused,
+    /// Storage layout version, absent on pools created before versioning (Instance storage)
+    StorageVersion,
+    /// Recent price checkpoints, oldest first (Instance storage)
+    PriceCheckpoints,
+    /// Number of price checkpoints kept, absent for the default (Instance storage)
+    PriceCheckpointCapacity,
 }
 
 // TTL constants
@@ -75,7 +118,7 @@ pub fn get_config(env: &Env) -> PoolConfig {
     env.storage()
         .instance()
         .get(&DataKey::Config)
-        .expect("Pool not initialized")
+        .unwrap_or_else(|| panic_with_error!(env, PoolError::NotInitialized))
 }
 
 pub fn set_config(env: &Env, config: &PoolConfig) {
@@ -90,7 +133,7 @@ pub fn get_state(env: &Env) -> PoolState {
     env.storage()
         .instance()
         .get(&DataKey::State)
-        .expect("Pool not initialized")
+        .unwrap_or_else(|| panic_with_error!(env, PoolError::NotInitialized))
 }
 
 pub fn set_state(env: &Env, state: &PoolState) {
@@ -100,12 +143,51 @@ pub fn set_state(env: &Env, state: &PoolState) {
 
 // === Tick ===
 
+/// Tick layout written before ticks carried oracle outside values.
+/// get_tick reads these entries with the oracle values at 0, the convention
+/// for time before initialization; the next update or crossing rewrites them
+/// in the current layout.
+#[contracttype(export = false)]
+#[derive(Clone, Debug)]
+pub struct LegacyTickInfo {
+    pub liquidity_gross: u128,
+    pub liquidity_net: i128,
+    pub fee_growth_outside_0_x128: u128,
+    pub fee_growth_outside_1_x128: u128,
+    pub initialized: bool,
+}
+
+/// Number of fields in a LegacyTickInfo entry
+const LEGACY_TICK_INFO_FIELDS: u32 = 5;
+
+impl From<LegacyTickInfo> for TickInfo {
+    fn from(legacy: LegacyTickInfo) -> Self {
+        Self {
+            liquidity_gross: legacy.liquidity_gross,
+            liquidity_net: legacy.liquidity_net,
+            fee_growth_outside_0_x128: legacy.fee_growth_outside_0_x128,
+            fee_growth_outside_1_x128: legacy.fee_growth_outside_1_x128,
+            initialized: legacy.initialized,
+            ..Default::default()
+        }
+    }
+}
+
 pub fn get_tick(env: &Env, tick: i32) -> TickInfo {
     let key = DataKey::Tick(tick);
-    env.storage()
-        .persistent()
-        .get(&key)
-        .unwrap_or_default()
+    let stored: Option<Val> = env.storage().persistent().get(&key);
+    match stored {
+        None => TickInfo::default(),
+        Some(raw) => {
+            // Decoding a struct from a map with other keys traps, so check the shape first
+            let fields: Map<Symbol, Val> = Map::from_val(env, &raw);
+            if fields.len() == LEGACY_TICK_INFO_FIELDS {
+                LegacyTickInfo::from_val(env, &raw).into()
+            } else {
+                TickInfo::from_val(env, &raw)
+            }
+        }
+    }
 }
 
 pub fn set_tick(env: &Env, tick: i32, info: &TickInfo) {
@@ -141,6 +223,148 @@ pub fn set_tick_bitmap_word(env: &Env, word_pos: i32, bitmap: u128) {
     }
 }
 
+// === Reentrancy Lock ===
+
+/// Take the reentrancy lock, panicking if a call is already in progress
+pub fn lock(env: &Env) {
+    if env.storage().instance().has(&DataKey::Locked) {
+        panic_with_error!(env, PoolError::Reentrancy);
+    }
+    env.storage().instance().set(&DataKey::Locked, &true);
+}
+
+pub fn unlock(env: &Env) {
+    env.storage().instance().remove(&DataKey::Locked);
+}
+
+// === Oracle Observations ===
+
+pub fn get_observation(env: &Env, index: u32) -> Observation {
+    let key = DataKey::Observation(index);
+    env.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_default()
+}
+
+pub fn set_observation(env: &Env, index: u32, observation: &Observation) {
+    let key = DataKey::Observation(index);
+    env.storage().persistent().set(&key, observation);
+    extend_persistent_ttl(env, &key);
+}
+
+// === Reserves ===
+
+/// Accounted token balances, or None for pools initialized before the
+/// counters existed
+pub fn get_reserves(env: &Env) -> Option<(u128, u128)> {
+    extend_instance_ttl(env);
+    env.storage().instance().get(&DataKey::Reserves)
+}
+
+pub fn set_reserves(env: &Env, reserves: &(u128, u128)) {
+    env.storage().instance().set(&DataKey::Reserves, reserves);
+    extend_instance_ttl(env);
+}
+
+/// Apply signed token deltas to the reserve counters (positive = received)
+/// No-op when the counters are not enabled
+pub fn adjust_reserves(env: &Env, delta0: i128, delta1: i128) {
+    if let Some((reserve0, reserve1)) = get_reserves(env) {
+        let reserve0 = reserve0
+            .checked_add_signed(delta0)
+            .unwrap_or_else(|| panic_with_error!(env, PoolError::ReserveUnderflow));
+        let reserve1 = reserve1
+            .checked_add_signed(delta1)
+            .unwrap_or_else(|| panic_with_error!(env, PoolError::ReserveUnderflow));
+        set_reserves(env, &(reserve0, reserve1));
+    }
+}
+
+// === Protocol Fee ===
+
+/// Protocol fee denominators, (0, 0) when protocol fees are off
+pub fn get_fee_protocol(env: &Env) -> (u32, u32) {
+    extend_instance_ttl(env);
+    env.storage()
+        .instance()
+        .get(&DataKey::FeeProtocol)
+        .unwrap_or((0, 0))
+}
+
+pub fn set_fee_protocol(env: &Env, fee_protocol: &(u32, u32)) {
+    env.storage().instance().set(&DataKey::FeeProtocol, fee_protocol);
+    extend_instance_ttl(env);
+}
+
+// === Pause ===
+
+/// Whether the factory has paused swaps in this pool
+pub fn get_paused(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::Paused, &paused);
+    extend_instance_ttl(env);
+}
+
+// === Storage Version ===
+
+/// Pools created before versioning report version 0
+pub fn get_storage_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::StorageVersion)
+        .unwrap_or(0)
+}
+
+pub fn set_storage_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::StorageVersion, &version);
+    extend_instance_ttl(env);
+}
+
+// === Price Checkpoints ===
+
+/// Price checkpoints kept when the factory has not configured a capacity
+pub const DEFAULT_PRICE_CHECKPOINT_CAPACITY: u32 = 16;
+
+/// Maximum price checkpoints, bounded since instance storage loads on every call
+pub const MAX_PRICE_CHECKPOINT_CAPACITY: u32 = 64;
+
+pub fn get_price_checkpoints(env: &Env) -> Vec<PriceCheckpoint> {
+    env.storage()
+        .instance()
+        .get(&DataKey::PriceCheckpoints)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn set_price_checkpoints(env: &Env, checkpoints: &Vec<PriceCheckpoint>) {
+    env.storage()
+        .instance()
+        .set(&DataKey::PriceCheckpoints, checkpoints);
+    extend_instance_ttl(env);
+}
+
+pub fn get_price_checkpoint_capacity(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PriceCheckpointCapacity)
+        .unwrap_or(DEFAULT_PRICE_CHECKPOINT_CAPACITY)
+}
+
+pub fn set_price_checkpoint_capacity(env: &Env, capacity: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::PriceCheckpointCapacity, &capacity);
+    extend_instance_ttl(env);
+}
+
 // === Position ===
 
 pub fn get_position(env: &Env, key: &PositionKey) -> PositionInfo {
@@ -161,3 +385,115 @@ pub fn set_position(env: &Env, key: &PositionKey, info: &PositionInfo) {
         extend_persistent_ttl(env, &data_key);
     }
 }
+
+// === Position Registry ===
+
+pub fn get_position_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PositionCount)
+        .unwrap_or(0)
+}
+
+/// Append a position that just gained liquidity to the registry
+pub fn register_position(env: &Env, key: &PositionKey) {
+    let index = get_position_count(env);
+    let at_key = DataKey::PositionAt(index);
+    let index_key = DataKey::PositionIndex(key.clone());
+    env.storage().persistent().set(&at_key, key);
+    env.storage().persistent().set(&index_key, &index);
+    extend_persistent_ttl(env, &at_key);
+    extend_persistent_ttl(env, &index_key);
+    env.storage()
+        .instance()
+        .set(&DataKey::PositionCount, &(index + 1));
+}
+
+/// Remove a position that just lost its liquidity, moving the last entry into its slot
+/// Positions opened before the registry existed are not in it and are skipped
+pub fn unregister_position(env: &Env, key: &PositionKey) {
+    let index_key = DataKey::PositionIndex(key.clone());
+    let index: u32 = match env.storage().persistent().get(&index_key) {
+        Some(index) => index,
+        None => return,
+    };
+    env.storage().persistent().remove(&index_key);
+
+    let last = get_position_count(env) - 1;
+    if index != last {
+        let moved: PositionKey = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PositionAt(last))
+            .unwrap();
+        let at_key = DataKey::PositionAt(index);
+        let moved_index_key = DataKey::PositionIndex(moved.clone());
+        env.storage().persistent().set(&at_key, &moved);
+        env.storage().persistent().set(&moved_index_key, &index);
+        extend_persistent_ttl(env, &at_key);
+        extend_persistent_ttl(env, &moved_index_key);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PositionAt(last));
+    env.storage().instance().set(&DataKey::PositionCount, &last);
+}
+
+/// Registered position keys from `start`, at most MAX_POSITIONS_PER_PAGE
+/// Removals move the last key into the freed slot, so order is not stable
+pub fn get_position_keys(env: &Env, start: u32, limit: u32) -> Vec<PositionKey> {
+    let end = get_position_count(env).min(start.saturating_add(limit.min(MAX_POSITIONS_PER_PAGE)));
+    let mut keys = Vec::new(env);
+    for index in start..end {
+        if let Some(key) = env.storage().persistent().get(&DataKey::PositionAt(index)) {
+            keys.push_back(key);
+        }
+    }
+    keys
+}
+
+// === Position Operator ===
+
+pub fn is_position_operator(env: &Env, key: &PositionKey, operator: &Address) -> bool {
+    let data_key = DataKey::PositionOperator(key.clone(), operator.clone());
+    env.storage().persistent().has(&data_key)
+}
+
+pub fn set_position_operator(env: &Env, key: &PositionKey, operator: &Address, approved: bool) {
+    let data_key = DataKey::PositionOperator(key.clone(), operator.clone());
+    if approved {
+        env.storage().persistent().set(&data_key, &true);
+        extend_persistent_ttl(env, &data_key);
+    } else {
+        env.storage().persistent().remove(&data_key);
+    }
+}
+
+/// Extend the TTL of a position, its registry entries and the tick and bitmap entries backing it
+/// Panics if the position does not exist; tick entries already removed are skipped
+pub fn extend_position_ttl(env: &Env, key: &PositionKey, tick_spacing: i32) {
+    let data_key = DataKey::Position(key.clone());
+    if !env.storage().persistent().has(&data_key) {
+        panic_with_error!(env, PoolError::PositionNotFound);
+    }
+    extend_persistent_ttl(env, &data_key);
+
+    let index_key = DataKey::PositionIndex(key.clone());
+    if let Some(index) = env.storage().persistent().get::<_, u32>(&index_key) {
+        extend_persistent_ttl(env, &index_key);
+        extend_persistent_ttl(env, &DataKey::PositionAt(index));
+    }
+
+    for tick in [key.tick_lower, key.tick_upper] {
+        let tick_key = DataKey::Tick(tick);
+        if env.storage().persistent().has(&tick_key) {
+            extend_persistent_ttl(env, &tick_key);
+        }
+
+        let (word_pos, _) = crate::tick::tick_to_bitmap_position(tick, tick_spacing);
+        let word_key = DataKey::TickBitmap(word_pos);
+        if env.storage().persistent().has(&word_key) {
+            extend_persistent_ttl(env, &word_key);
+        }
+    }
+}
@@ -5,46 +5,64 @@
 // This module separates pure computation from side effects:
 //
 // 1. PURE FUNCTIONS (formally verifiable):
-//    - validate_swap_params: Validates input parameters
+//    - check_swap_params: Validates input parameters, returning the error
+//    - clamp_limit_to_min_tick: Keeps a price-lowering limit above MIN_TICK's price
+//    - validate_swap_params: Same, panicking on invalid parameters
 //    - compute_step_target_price: Determines target price for a step
 //    - compute_swap_step_amounts: Computes amounts for a single step
+//    - split_protocol_fee: Splits a swap or flash fee between protocol and LPs
 //    - compute_fee_growth_delta: Computes fee growth change
 //    - compute_next_tick: Determines tick after price change
+//    - can_skip_to_next_word: Whether empty bitmap words can be hopped over
+//    - is_truncated: Whether the swap stopped at the tick crossing cap
 //    - compute_final_amounts: Computes final token amounts
 //
 // 2. SIDE EFFECT FUNCTIONS:
-//    - apply_swap_to_state: Updates pool state in storage
+//    - apply_swap_to_state: Writes the swap onto the already-loaded pool state
 //    - apply_tick_crossing: Updates tick storage when crossing
 //    - transfer_swap_tokens: Handles token transfers
+//    - pull_swap_payment: Pulls swap input, rejecting short deliveries
+//    - has_liquidity_ahead: Whether any liquidity is reachable (reads storage)
 //
 // 3. ORCHESTRATION:
-//    - execute_swap: Main entry point that orchestrates pure + effects
+//    - compute_swap: Swap loop shared by execution and simulation (reads storage)
+//    - try_execute_swap: Main entry point that orchestrates pure + effects
+//    - execute_swap: Same, panicking with the error instead of returning it
+//    - simulate_swap: Quote entry point, runs the loop without writing
 //
 // ============================================================================
 
-use crate::storage::{get_config, get_state, set_state, MAX_TICK_CROSSINGS_PER_SWAP};
-use crate::tick::{cross, next_initialized_tick_within_one_word};
+use crate::storage::{
+    adjust_reserves, get_config, get_fee_protocol, get_paused, get_state, get_tick, set_state,
+    MAX_TICK_CROSSINGS_PER_SWAP,
+};
+use crate::oracle;
+use crate::tick::{cross, next_initialized_tick_within_one_word_cached};
 use dex_math::{add_delta, compute_swap_step, get_sqrt_ratio_at_tick};
 use dex_types::{
-    SwapComputation, SwapParams, SwapState, MAX_SQRT_RATIO, MIN_SQRT_RATIO,
+    PoolConfig, PoolError, PoolState, SwapComputation, SwapParams, SwapResult, SwapSimulation,
+    SwapState, MAX_SQRT_RATIO, MIN_SQRT_RATIO,
 };
-use soroban_sdk::{token, Address, Env};
+use soroban_sdk::{panic_with_error, token, Address, Env, IntoVal, Symbol};
 
 // ============================================================================
 // PURE FUNCTIONS - No storage access, formally verifiable
 // ============================================================================
 
-/// Validate swap parameters (pure)
-/// Returns the effective sqrt price limit
-/// Panics if parameters are invalid
-pub fn validate_swap_params(
+/// Sqrt price at MIN_TICK as get_sqrt_ratio_at_tick computes it
+/// MIN_SQRT_RATIO lies far below it, so the price can never fall to the outermost limit
+const MIN_TICK_SQRT_RATIO: u128 = 18_447_090_764_788_882_727;
+
+/// Check swap parameters (pure)
+/// Returns the effective sqrt price limit, or the error the parameters fail with
+pub fn check_swap_params(
     amount_specified: i128,
     zero_for_one: bool,
     sqrt_price_limit_x96: u128,
     current_sqrt_price_x96: u128,
-) -> u128 {
+) -> Result<u128, PoolError> {
     if amount_specified == 0 {
-        panic!("Amount must be non-zero");
+        return Err(PoolError::ZeroAmount);
     }
 
     // Determine effective price limit
@@ -61,15 +79,46 @@ pub fn validate_swap_params(
     // Validate price limit direction
     if zero_for_one {
         if sqrt_price_limit >= current_sqrt_price_x96 || sqrt_price_limit <= MIN_SQRT_RATIO {
-            panic!("Invalid price limit");
+            return Err(PoolError::InvalidPriceLimit);
         }
     } else {
         if sqrt_price_limit <= current_sqrt_price_x96 || sqrt_price_limit >= MAX_SQRT_RATIO {
-            panic!("Invalid price limit");
+            return Err(PoolError::InvalidPriceLimit);
         }
     }
 
-    sqrt_price_limit
+    Ok(sqrt_price_limit)
+}
+
+/// Raise a price-lowering limit to just above MIN_TICK's price (pure)
+/// The swap loop can't step past MIN_TICK, so a lower limit would never be reached;
+/// stopping one above it keeps the tick at MIN_TICK like any other limit
+pub fn clamp_limit_to_min_tick(sqrt_price_limit: u128, zero_for_one: bool) -> u128 {
+    if zero_for_one {
+        sqrt_price_limit.max(MIN_TICK_SQRT_RATIO + 1)
+    } else {
+        sqrt_price_limit
+    }
+}
+
+/// Validate swap parameters (pure)
+/// Returns the effective sqrt price limit
+/// Panics if parameters are invalid
+#[cfg(test)]
+pub fn validate_swap_params(
+    env: &Env,
+    amount_specified: i128,
+    zero_for_one: bool,
+    sqrt_price_limit_x96: u128,
+    current_sqrt_price_x96: u128,
+) -> u128 {
+    check_swap_params(
+        amount_specified,
+        zero_for_one,
+        sqrt_price_limit_x96,
+        current_sqrt_price_x96,
+    )
+    .unwrap_or_else(|e| panic_with_error!(env, e))
 }
 
 /// Compute the target sqrt price for a swap step (pure)
@@ -117,11 +166,22 @@ pub fn update_swap_amounts(
     }
 }
 
-/// Compute fee growth delta (pure)
-/// Returns the fee growth increment for this step
-pub fn compute_fee_growth_delta(fee_amount: u128, liquidity: u128) -> u128 {
+/// Split a swap step's or flash loan's fee between the protocol and LPs (pure)
+/// The protocol takes fee_amount / fee_protocol (0 = off), LPs keep the remainder
+/// Returns (protocol_fee, lp_fee)
+pub fn split_protocol_fee(fee_amount: u128, fee_protocol: u32) -> (u128, u128) {
+    if fee_protocol == 0 {
+        return (0, fee_amount);
+    }
+    let protocol_fee = fee_amount / fee_protocol as u128;
+    (protocol_fee, fee_amount - protocol_fee)
+}
+
+/// Compute fee growth delta (pure computation with env for U256)
+/// Returns the Q128 fee growth increment for this step: (fee_amount << 128) / liquidity
+pub fn compute_fee_growth_delta(env: &Env, fee_amount: u128, liquidity: u128) -> u128 {
     if liquidity > 0 {
-        (fee_amount as u128) << 128 / liquidity
+        dex_math::div_shl_128(env, fee_amount, liquidity)
     } else {
         0
     }
@@ -151,6 +211,28 @@ pub fn compute_tick_transition(
     }
 }
 
+/// Whether the swap can hop past an uninitialized word boundary without pricing it (pure)
+///
+/// With no liquidity in range nothing trades before the next initialized tick,
+/// so empty bitmap words are skipped until an initialized tick, the tick of the
+/// price limit, or the tick bounds are reached. Hops don't count as crossings.
+pub fn can_skip_to_next_word(
+    initialized: bool,
+    liquidity: u128,
+    tick_next: i32,
+    tick_limit: i32,
+    zero_for_one: bool,
+) -> bool {
+    if initialized || liquidity > 0 {
+        return false;
+    }
+    if zero_for_one {
+        tick_next > tick_limit && tick_next > dex_types::MIN_TICK
+    } else {
+        tick_next < tick_limit && tick_next < dex_types::MAX_TICK
+    }
+}
+
 /// Compute final amounts from swap state (pure)
 pub fn compute_final_amounts(
     amount_specified: i128,
@@ -196,18 +278,38 @@ pub fn should_continue_swap(
         && tick_crossings < MAX_TICK_CROSSINGS_PER_SWAP
 }
 
+/// Check if the swap loop was cut short by the tick crossing cap (pure)
+/// True when the cap was hit with amount still unfilled short of the price limit
+pub fn is_truncated(
+    amount_remaining: i128,
+    sqrt_price_x96: u128,
+    sqrt_price_limit: u128,
+    tick_crossings: u32,
+) -> bool {
+    amount_remaining != 0
+        && sqrt_price_x96 != sqrt_price_limit
+        && tick_crossings >= MAX_TICK_CROSSINGS_PER_SWAP
+}
+
 // ============================================================================
 // SIDE EFFECT FUNCTIONS - Storage and token operations
 // ============================================================================
 
 /// Apply computed swap result to pool state (side effect)
-fn apply_swap_to_state(
-    env: &Env,
-    computation: &SwapComputation,
-    fee_growth_global_0_x128: u128,
-    fee_growth_global_1_x128: u128,
-) {
-    let mut state = get_state(env);
+fn apply_swap_to_state(env: &Env, computation: &SwapComputation, mut state: PoolState) {
+    // Record the tick that held until now before it changes
+    if computation.tick != state.tick {
+        oracle::write(env, &mut state, env.ledger().timestamp());
+    }
+
+    if computation.sqrt_price_x96 != state.sqrt_price_x96 {
+        oracle::write_price_checkpoint(
+            env,
+            env.ledger().timestamp(),
+            computation.sqrt_price_x96,
+            computation.tick,
+        );
+    }
 
     state.sqrt_price_x96 = computation.sqrt_price_x96;
     state.tick = computation.tick;
@@ -215,10 +317,10 @@ fn apply_swap_to_state(
 
     if computation.fee_growth_is_token0 {
         state.fee_growth_global_0_x128 = computation.fee_growth_global_x128;
-        state.fee_growth_global_1_x128 = fee_growth_global_1_x128;
+        state.protocol_fees_0 += computation.protocol_fee as i128;
     } else {
-        state.fee_growth_global_0_x128 = fee_growth_global_0_x128;
         state.fee_growth_global_1_x128 = computation.fee_growth_global_x128;
+        state.protocol_fees_1 += computation.protocol_fee as i128;
     }
 
     set_state(env, &state);
@@ -238,10 +340,11 @@ fn transfer_swap_tokens(
     let token0_client = token::Client::new(env, token0);
     let token1_client = token::Client::new(env, token1);
 
+    // Input is pulled and verified before any output leaves the pool
     if zero_for_one {
         // User pays token0, receives token1
         if amount0 > 0 {
-            token0_client.transfer(recipient, &contract_address, &amount0);
+            pull_swap_payment(env, &token0_client, recipient, amount0);
         }
         if amount1 < 0 {
             token1_client.transfer(&contract_address, recipient, &(-amount1));
@@ -249,7 +352,7 @@ fn transfer_swap_tokens(
     } else {
         // User pays token1, receives token0
         if amount1 > 0 {
-            token1_client.transfer(recipient, &contract_address, &amount1);
+            pull_swap_payment(env, &token1_client, recipient, amount1);
         }
         if amount0 < 0 {
             token0_client.transfer(&contract_address, recipient, &(-amount0));
@@ -257,41 +360,124 @@ fn transfer_swap_tokens(
     }
 }
 
+/// Pull swap input from the payer and verify it all arrived (side effect)
+///
+/// A token that takes a fee on transfer, or reports a transfer it never made,
+/// would otherwise leave the pool paying out and crediting LPs for input it
+/// never received.
+fn pull_swap_payment(env: &Env, token_client: &token::Client, from: &Address, amount: i128) {
+    let contract_address = env.current_contract_address();
+    let balance_before = token_client.balance(&contract_address);
+    token_client.transfer(from, &contract_address, &amount);
+    if token_client.balance(&contract_address) < balance_before + amount {
+        panic_with_error!(env, PoolError::SwapPaymentShort);
+    }
+}
+
+/// Whether an initialized tick lies between the current tick and the price limit
+/// (side effect - reads storage)
+///
+/// Walks the bitmap the same way the swap loop does without liquidity, hopping
+/// empty words until an initialized tick, the limit tick or the tick bounds.
+fn has_liquidity_ahead(
+    env: &Env,
+    tick: i32,
+    tick_spacing: i32,
+    tick_limit: i32,
+    zero_for_one: bool,
+    cached_word: &mut Option<(i32, u128)>,
+) -> bool {
+    let (mut tick_next, mut initialized) = next_initialized_tick_within_one_word_cached(
+        env,
+        tick,
+        tick_spacing,
+        zero_for_one,
+        cached_word,
+    );
+    while can_skip_to_next_word(initialized, 0, tick_next, tick_limit, zero_for_one) {
+        let from = if zero_for_one { tick_next - 1 } else { tick_next };
+        (tick_next, initialized) = next_initialized_tick_within_one_word_cached(
+            env,
+            from,
+            tick_spacing,
+            zero_for_one,
+            cached_word,
+        );
+    }
+
+    if zero_for_one {
+        initialized && tick_next > tick_limit
+    } else {
+        initialized && tick_next <= tick_limit
+    }
+}
+
 // ============================================================================
-// MAIN ENTRY POINT - Orchestrates pure computation and side effects
+// MAIN ENTRY POINTS - Orchestrate pure computation and side effects
 // ============================================================================
 
-/// Execute a swap
-///
-/// This function orchestrates the swap by:
-/// 1. Validating parameters (pure)
-/// 2. Computing swap steps in a loop (mixed - needs tick lookups)
-/// 3. Applying state changes (side effect)
-/// 4. Transferring tokens (side effect)
+/// Outcome of the swap loop, before any state is written
+struct SwapOutcome {
+    computation: SwapComputation,
+    amount_remaining: i128,
+    truncated: bool,
+}
+
+/// Run the swap loop against the current pool state (shared by swap and simulation)
 ///
-/// Note: The swap loop requires storage reads for tick bitmap lookups,
-/// so it cannot be fully pure. However, individual steps use pure functions
-/// that can be formally verified in isolation.
-pub fn execute_swap(
+/// Reads ticks and bitmap words from storage. Crossed ticks are only written
+/// when `commit` is set; pool state, balances and reserves are never touched.
+/// Returns None when no liquidity is reachable and `partial_fill_allowed` is
+/// set, in which case nothing trades and the price stays put. Swaps that can't
+/// go ahead return the error instead; ticks crossed before it are only undone
+/// by the invocation failing.
+fn compute_swap(
     env: &Env,
-    recipient: Address,
-    zero_for_one: bool,
-    amount_specified: i128,
-    sqrt_price_limit_x96: u128,
-) -> (i128, i128) {
-    let config = get_config(env);
-    let state = get_state(env);
+    config: &PoolConfig,
+    state: &PoolState,
+    params: &SwapParams,
+    partial_fill_allowed: bool,
+    commit: bool,
+) -> Result<Option<SwapOutcome>, PoolError> {
+    let zero_for_one = params.zero_for_one;
+    let amount_specified = params.amount_specified;
 
     // === PHASE 1: Validation (pure) ===
-    let sqrt_price_limit = validate_swap_params(
+    let requested_limit = check_swap_params(
         amount_specified,
         zero_for_one,
-        sqrt_price_limit_x96,
+        params.sqrt_price_limit_x96,
         state.sqrt_price_x96,
-    );
+    )?;
+    let sqrt_price_limit = clamp_limit_to_min_tick(requested_limit, zero_for_one);
 
     let exact_input = amount_specified > 0;
 
+    // Tick of the price limit, computed the first time empty words are skipped
+    let mut tick_limit: Option<i32> = None;
+
+    // Last bitmap word read; crossings don't flip bits, so it stays valid all swap
+    let mut cached_word: Option<(i32, u128)> = None;
+
+    // Without liquidity in range, there must be some to reach before the limit
+    if state.liquidity == 0 {
+        let limit = dex_math::get_tick_at_sqrt_ratio(env, sqrt_price_limit);
+        tick_limit = Some(limit);
+        if !has_liquidity_ahead(
+            env,
+            state.tick,
+            config.tick_spacing,
+            limit,
+            zero_for_one,
+            &mut cached_word,
+        ) {
+            if !partial_fill_allowed {
+                return Err(PoolError::NoLiquidity);
+            }
+            return Ok(None);
+        }
+    }
+
     // === PHASE 2: Initialize swap state ===
     let initial_fee_growth = if zero_for_one {
         state.fee_growth_global_0_x128
@@ -309,6 +495,19 @@ pub fn execute_swap(
 
     let mut tick_crossings: u32 = 0;
 
+    // Protocol takes 1/fee_protocol of the input-token fee (0 = off)
+    let (fee_protocol_0, fee_protocol_1) = get_fee_protocol(env);
+    let fee_protocol = if zero_for_one {
+        fee_protocol_0
+    } else {
+        fee_protocol_1
+    };
+    let mut protocol_fee: u128 = 0;
+    let mut fee_amount_total: u128 = 0;
+
+    // Oracle cumulatives as of the swap start, read on the first tick crossing
+    let mut cumulatives: Option<oracle::Cumulatives> = None;
+
     // === PHASE 3: Swap loop ===
     // Note: This loop requires storage reads for tick bitmap, so it's not fully pure.
     // However, each step uses pure helper functions that can be verified.
@@ -319,13 +518,37 @@ pub fn execute_swap(
         tick_crossings,
     ) {
         // Find next initialized tick (requires storage read)
-        let (tick_next, initialized) = next_initialized_tick_within_one_word(
+        let (mut tick_next, mut initialized) = next_initialized_tick_within_one_word_cached(
             env,
             swap_state.tick,
             config.tick_spacing,
             zero_for_one,
+            &mut cached_word,
         );
 
+        // Without liquidity, jump straight across empty words instead of
+        // pricing and stepping through each boundary
+        if !initialized && swap_state.liquidity == 0 {
+            let tick_limit = *tick_limit
+                .get_or_insert_with(|| dex_math::get_tick_at_sqrt_ratio(env, sqrt_price_limit));
+            while can_skip_to_next_word(
+                initialized,
+                swap_state.liquidity,
+                tick_next,
+                tick_limit,
+                zero_for_one,
+            ) {
+                let from = if zero_for_one { tick_next - 1 } else { tick_next };
+                (tick_next, initialized) = next_initialized_tick_within_one_word_cached(
+                    env,
+                    from,
+                    config.tick_spacing,
+                    zero_for_one,
+                    &mut cached_word,
+                );
+            }
+        }
+
         // Clamp to min/max tick (pure)
         let tick_next = tick_next.clamp(dex_types::MIN_TICK, dex_types::MAX_TICK);
 
@@ -358,9 +581,16 @@ pub fn execute_swap(
         swap_state.amount_remaining = new_amount_remaining;
         swap_state.amount_calculated = new_amount_calculated;
 
+        fee_amount_total += step.fee_amount;
+
+        // Carve out the protocol share, the rest accrues to LPs
+        let (step_protocol_fee, lp_fee) = split_protocol_fee(step.fee_amount, fee_protocol);
+        protocol_fee += step_protocol_fee;
+
         // Update fee growth (pure)
-        let fee_growth_delta = compute_fee_growth_delta(step.fee_amount, swap_state.liquidity);
-        swap_state.fee_growth_global_x128 += fee_growth_delta;
+        let fee_growth_delta = compute_fee_growth_delta(env, lp_fee, swap_state.liquidity);
+        swap_state.fee_growth_global_x128 =
+            swap_state.fee_growth_global_x128.wrapping_add(fee_growth_delta);
 
         // Update sqrt price
         swap_state.sqrt_price_x96 = step.sqrt_ratio_next_x96;
@@ -375,13 +605,29 @@ pub fn execute_swap(
         );
 
         if should_cross {
-            // Cross tick (side effect - updates tick storage)
-            let liquidity_net = cross(
-                env,
-                tick_next,
-                state.fee_growth_global_0_x128,
-                state.fee_growth_global_1_x128,
-            );
+            let liquidity_net = if commit {
+                // Outside values flip against fee growth as of this crossing:
+                // the input token's global includes fees from earlier steps
+                let (fee_growth_global_0, fee_growth_global_1) = if zero_for_one {
+                    (swap_state.fee_growth_global_x128, state.fee_growth_global_1_x128)
+                } else {
+                    (state.fee_growth_global_0_x128, swap_state.fee_growth_global_x128)
+                };
+
+                // Cross tick (side effect - updates tick storage)
+                let cumulatives = cumulatives
+                    .get_or_insert_with(|| oracle::current(env, state, env.ledger().timestamp()));
+                cross(
+                    env,
+                    tick_next,
+                    fee_growth_global_0,
+                    fee_growth_global_1,
+                    cumulatives,
+                )
+            } else {
+                // Simulation only needs the liquidity change
+                get_tick(env, tick_next).liquidity_net
+            };
 
             // Apply liquidity change (pure computation)
             let liquidity_delta = if zero_for_one {
@@ -403,6 +649,30 @@ pub fn execute_swap(
         }
     }
 
+    let truncated = is_truncated(
+        swap_state.amount_remaining,
+        swap_state.sqrt_price_x96,
+        sqrt_price_limit,
+        tick_crossings,
+    );
+    if truncated && !partial_fill_allowed {
+        return Err(PoolError::TooManyTicksCrossed);
+    }
+
+    // Exact output must deliver the full amount unless the caller accepts less
+    if !exact_input && swap_state.amount_remaining != 0 && !partial_fill_allowed {
+        // Stopping at a limit the caller chose is a price problem, not a depth one;
+        // the outermost limits are what "no limit" (0) resolves to, so they don't count,
+        // and neither does a limit raised to the tick bound
+        let limit_is_bound = sqrt_price_limit != requested_limit
+            || requested_limit == MIN_SQRT_RATIO + 1
+            || requested_limit == MAX_SQRT_RATIO - 1;
+        if swap_state.sqrt_price_x96 == sqrt_price_limit && !limit_is_bound {
+            return Err(PoolError::PriceLimitReached);
+        }
+        return Err(PoolError::InsufficientLiquidity);
+    }
+
     // === PHASE 4: Compute final amounts (pure) ===
     let (amount0, amount1) = compute_final_amounts(
         amount_specified,
@@ -413,24 +683,106 @@ pub fn execute_swap(
     );
 
     // === PHASE 5: Build computation result ===
-    let computation = SwapComputation {
-        amount0,
-        amount1,
-        sqrt_price_x96: swap_state.sqrt_price_x96,
-        tick: swap_state.tick,
-        liquidity: swap_state.liquidity,
-        fee_growth_global_x128: swap_state.fee_growth_global_x128,
-        fee_growth_is_token0: zero_for_one,
-        ticks_crossed: tick_crossings,
-    };
+    Ok(Some(SwapOutcome {
+        computation: SwapComputation {
+            amount0,
+            amount1,
+            sqrt_price_x96: swap_state.sqrt_price_x96,
+            tick: swap_state.tick,
+            liquidity: swap_state.liquidity,
+            fee_growth_global_x128: swap_state.fee_growth_global_x128,
+            fee_growth_is_token0: zero_for_one,
+            fee_amount_total,
+            protocol_fee,
+            ticks_crossed: tick_crossings,
+        },
+        amount_remaining: swap_state.amount_remaining,
+        truncated,
+    }))
+}
 
-    // === PHASE 6: Apply state changes (side effect) ===
-    apply_swap_to_state(
+/// Execute a swap, returning the error a swap that can't go ahead fails with
+///
+/// This function orchestrates the swap by:
+/// 1. Validating parameters (pure)
+/// 2. Computing swap steps in a loop (mixed - needs tick lookups)
+/// 3. Applying state changes (side effect)
+/// 4. Transferring tokens (side effect)
+///
+/// Note: The swap loop requires storage reads for tick bitmap lookups,
+/// so it cannot be fully pure. However, individual steps use pure functions
+/// that can be formally verified in isolation.
+///
+/// If the tick crossing cap stops the swap before it is filled, it fails
+/// unless `partial_fill_allowed` is set, in which case the partial result is
+/// returned and a `swap_truncated` event is emitted. Likewise an exact output
+/// swap that runs out of liquidity or hits the price limit short of the
+/// requested output only succeeds with `partial_fill_allowed`. A swap with no
+/// liquidity in range and none before the price limit fails with `NoLiquidity`,
+/// or returns zero amounts without moving the price when partial fills are allowed.
+///
+/// Token transfer and payment failures still panic.
+pub fn try_execute_swap(
+    env: &Env,
+    recipient: Address,
+    zero_for_one: bool,
+    amount_specified: i128,
+    sqrt_price_limit_x96: u128,
+    partial_fill_allowed: bool,
+) -> Result<SwapResult, PoolError> {
+    let config = get_config(env);
+    let state = get_state(env);
+
+    if !dex_types::is_valid_recipient(
         env,
-        &computation,
-        state.fee_growth_global_0_x128,
-        state.fee_growth_global_1_x128,
+        &recipient,
+        &[&config.token0, &config.token1, &config.factory],
+    ) {
+        return Err(PoolError::InvalidRecipient);
+    }
+
+    // Factory kill switch halts swaps in every pool
+    let paused: bool = env.invoke_contract(
+        &config.factory,
+        &Symbol::new(env, "get_global_pause"),
+        ().into_val(env),
     );
+    if paused {
+        return Err(PoolError::SwapsPaused);
+    }
+
+    // Pool-level emergency stop, also set through the factory
+    if get_paused(env) {
+        return Err(PoolError::PoolPaused);
+    }
+
+    // === PHASES 1-5: Validate and run the swap loop, crossing ticks ===
+    let params = SwapParams {
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit_x96,
+    };
+    let outcome = match compute_swap(env, &config, &state, &params, partial_fill_allowed, true)? {
+        Some(outcome) => outcome,
+        None => {
+            return Ok(SwapResult {
+                amount0: 0,
+                amount1: 0,
+                sqrt_price_after_x96: state.sqrt_price_x96,
+                tick_after: state.tick,
+                liquidity_after: 0,
+                fee_amount_total: 0,
+                ticks_crossed: 0,
+                truncated: false,
+            })
+        }
+    };
+    let computation = outcome.computation;
+    let truncated = outcome.truncated;
+    let (amount0, amount1) = (computation.amount0, computation.amount1);
+
+    // === PHASE 6: Apply state changes (side effect) ===
+    apply_swap_to_state(env, &computation, state);
 
     // === PHASE 7: Transfer tokens (side effect) ===
     transfer_swap_tokens(
@@ -443,7 +795,119 @@ pub fn execute_swap(
         zero_for_one,
     );
 
-    (amount0, amount1)
+    // Positive amounts were paid in, negative amounts paid out
+    adjust_reserves(env, amount0, amount1);
+
+    // === PHASE 8: Emit events ===
+    if truncated {
+        env.events().publish(
+            (
+                Symbol::new(env, "swap_truncated"),
+                config.token0.clone(),
+                config.token1.clone(),
+            ),
+            (
+                recipient.clone(),
+                computation.ticks_crossed,
+                outcome.amount_remaining,
+            ),
+        );
+    }
+    // Post-swap price, liquidity, tick and fee let indexers skip a state read
+    env.events().publish(
+        (Symbol::new(env, "swap"), config.token0, config.token1),
+        (
+            recipient,
+            amount0,
+            amount1,
+            computation.sqrt_price_x96,
+            computation.liquidity,
+            computation.tick,
+            computation.fee_amount_total,
+        ),
+    );
+
+    Ok(SwapResult {
+        amount0,
+        amount1,
+        sqrt_price_after_x96: computation.sqrt_price_x96,
+        tick_after: computation.tick,
+        liquidity_after: computation.liquidity,
+        fee_amount_total: computation.fee_amount_total,
+        ticks_crossed: computation.ticks_crossed,
+        truncated,
+    })
+}
+
+/// Execute a swap, panicking with the error `try_execute_swap` would return
+pub fn execute_swap(
+    env: &Env,
+    recipient: Address,
+    zero_for_one: bool,
+    amount_specified: i128,
+    sqrt_price_limit_x96: u128,
+    partial_fill_allowed: bool,
+) -> SwapResult {
+    try_execute_swap(
+        env,
+        recipient,
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit_x96,
+        partial_fill_allowed,
+    )
+    .unwrap_or_else(|e| panic_with_error!(env, e))
+}
+
+/// Simulate a swap against current storage without changing anything
+///
+/// Runs the same loop as `execute_swap` but reads crossed ticks instead of
+/// flipping them, and skips `apply_swap_to_state` and `transfer_swap_tokens`.
+/// Behaves like a swap with partial fills allowed, so a capped or short fill is
+/// reported rather than panicking. Pause flags are not checked.
+pub fn simulate_swap(
+    env: &Env,
+    zero_for_one: bool,
+    amount_specified: i128,
+    sqrt_price_limit_x96: u128,
+) -> SwapSimulation {
+    let config = get_config(env);
+    let state = get_state(env);
+
+    let params = SwapParams {
+        zero_for_one,
+        amount_specified,
+        sqrt_price_limit_x96,
+    };
+    let computed = compute_swap(env, &config, &state, &params, true, false)
+        .unwrap_or_else(|e| panic_with_error!(env, e));
+    let outcome = match computed {
+        Some(outcome) => outcome,
+        None => {
+            return SwapSimulation {
+                amount0: 0,
+                amount1: 0,
+                sqrt_price_after_x96: state.sqrt_price_x96,
+                tick_after: state.tick,
+                liquidity_after: 0,
+                fee_amount_total: 0,
+                ticks_crossed: 0,
+                truncated: false,
+            }
+        }
+    };
+    let computation = outcome.computation;
+
+    SwapSimulation {
+        amount0: computation.amount0,
+        amount1: computation.amount1,
+        sqrt_price_after_x96: computation.sqrt_price_x96,
+        tick_after: computation.tick,
+        liquidity_after: computation.liquidity,
+        fee_amount_total: computation.fee_amount_total,
+        ticks_crossed: computation.ticks_crossed,
+        truncated: outcome.truncated,
+    }
 }
 
 // ============================================================================
@@ -459,50 +923,56 @@ mod tests {
     #[test]
     fn test_validate_swap_params_exact_input_zero_for_one() {
         // Use a price in the valid range (between MIN and MAX)
+        let env = Env::default();
         let current_price = dex_types::Q96; // Price = 1
-        let limit = validate_swap_params(100, true, 0, current_price);
+        let limit = validate_swap_params(&env, 100, true, 0, current_price);
         assert_eq!(limit, MIN_SQRT_RATIO + 1);
     }
 
     #[test]
     fn test_validate_swap_params_exact_input_one_for_zero() {
         // Use a price in the valid range
+        let env = Env::default();
         let current_price = dex_types::Q96; // Price = 1
-        let limit = validate_swap_params(100, false, 0, current_price);
+        let limit = validate_swap_params(&env, 100, false, 0, current_price);
         assert_eq!(limit, MAX_SQRT_RATIO - 1);
     }
 
     #[test]
     fn test_validate_swap_params_with_explicit_limit() {
         // Use a price in the valid range, with explicit limit below it
+        let env = Env::default();
         let current_price = dex_types::Q96; // Price = 1
         let explicit_limit = dex_types::Q96 / 2; // Below current price
-        let limit = validate_swap_params(100, true, explicit_limit, current_price);
+        let limit = validate_swap_params(&env, 100, true, explicit_limit, current_price);
         assert_eq!(limit, explicit_limit);
     }
 
     #[test]
-    #[should_panic(expected = "Amount must be non-zero")]
+    #[should_panic(expected = "Error(Contract, #106)")]
     fn test_validate_swap_params_zero_amount() {
-        validate_swap_params(0, true, 0, 1000000);
+        let env = Env::default();
+        validate_swap_params(&env, 0, true, 0, 1000000);
     }
 
     #[test]
-    #[should_panic(expected = "Invalid price limit")]
+    #[should_panic(expected = "Error(Contract, #107)")]
     fn test_validate_swap_params_invalid_limit_zero_for_one() {
         // For zero_for_one, limit must be < current price
+        let env = Env::default();
         let current_price = dex_types::Q96;
         // Limit above current price is invalid for zero_for_one
-        validate_swap_params(100, true, current_price + 1, current_price);
+        validate_swap_params(&env, 100, true, current_price + 1, current_price);
     }
 
     #[test]
-    #[should_panic(expected = "Invalid price limit")]
+    #[should_panic(expected = "Error(Contract, #107)")]
     fn test_validate_swap_params_invalid_limit_one_for_zero() {
         // For one_for_zero, limit must be > current price
+        let env = Env::default();
         let current_price = dex_types::Q96;
         // Limit below current price is invalid for one_for_zero
-        validate_swap_params(100, false, current_price - 1, current_price);
+        validate_swap_params(&env, 100, false, current_price - 1, current_price);
     }
 
     // === compute_step_target_price tests ===
@@ -581,17 +1051,46 @@ mod tests {
 
     #[test]
     fn test_compute_fee_growth_delta_with_liquidity() {
-        let delta = compute_fee_growth_delta(100, 1000);
-        // (100 << 128) / 1000
-        assert!(delta > 0);
+        let env = Env::default();
+        let delta = compute_fee_growth_delta(&env, 100, 1000);
+        // (100 << 128) / 1000 = 2^128 / 10
+        assert_eq!(delta, u128::MAX / 10);
     }
 
     #[test]
     fn test_compute_fee_growth_delta_zero_liquidity() {
-        let delta = compute_fee_growth_delta(100, 0);
+        let env = Env::default();
+        let delta = compute_fee_growth_delta(&env, 100, 0);
         assert_eq!(delta, 0);
     }
 
+    // === split_protocol_fee tests ===
+
+    #[test]
+    fn test_split_protocol_fee_off_leaves_all_to_lps() {
+        assert_eq!(split_protocol_fee(12_345, 0), (0, 12_345));
+    }
+
+    #[test]
+    fn test_split_protocol_fee_takes_one_nth() {
+        assert_eq!(split_protocol_fee(1_000, 4), (250, 750));
+        assert_eq!(split_protocol_fee(1_000, 10), (100, 900));
+        // Rounding favors LPs
+        assert_eq!(split_protocol_fee(9, 4), (2, 7));
+        assert_eq!(split_protocol_fee(3, 4), (0, 3));
+    }
+
+    #[test]
+    fn test_split_protocol_fee_conserves_fee() {
+        for fee_protocol in [0u32, 4, 5, 6, 7, 8, 9, 10] {
+            for fee_amount in [0u128, 1, 3, 9, 10, 999, 1_000_003, u128::MAX] {
+                let (protocol, lp) = split_protocol_fee(fee_amount, fee_protocol);
+                assert_eq!(protocol + lp, fee_amount);
+                assert!(lp >= protocol);
+            }
+        }
+    }
+
     // === compute_tick_transition tests ===
 
     #[test]
@@ -697,6 +1196,74 @@ mod tests {
         assert!(!should_continue_swap(100, 1000, 500, MAX_TICK_CROSSINGS_PER_SWAP));
     }
 
+    // === is_truncated tests ===
+
+    #[test]
+    fn test_is_truncated_at_cap_with_amount_left() {
+        assert!(is_truncated(100, 1000, 500, MAX_TICK_CROSSINGS_PER_SWAP));
+    }
+
+    #[test]
+    fn test_is_truncated_false_below_cap() {
+        assert!(!is_truncated(100, 1000, 500, MAX_TICK_CROSSINGS_PER_SWAP - 1));
+    }
+
+    #[test]
+    fn test_is_truncated_false_when_filled_or_at_limit() {
+        assert!(!is_truncated(0, 1000, 500, MAX_TICK_CROSSINGS_PER_SWAP));
+        assert!(!is_truncated(100, 500, 500, MAX_TICK_CROSSINGS_PER_SWAP));
+    }
+
+    // === clamp_limit_to_min_tick tests ===
+
+    #[test]
+    fn test_min_tick_sqrt_ratio_matches_tick_math() {
+        let env = Env::default();
+        assert_eq!(
+            get_sqrt_ratio_at_tick(&env, dex_types::MIN_TICK),
+            MIN_TICK_SQRT_RATIO
+        );
+    }
+
+    #[test]
+    fn test_clamp_limit_raises_only_price_lowering_limits() {
+        assert_eq!(
+            clamp_limit_to_min_tick(MIN_SQRT_RATIO + 1, true),
+            MIN_TICK_SQRT_RATIO + 1
+        );
+        assert_eq!(clamp_limit_to_min_tick(dex_types::Q96, true), dex_types::Q96);
+        assert_eq!(
+            clamp_limit_to_min_tick(MAX_SQRT_RATIO - 1, false),
+            MAX_SQRT_RATIO - 1
+        );
+    }
+
+    // === can_skip_to_next_word tests ===
+
+    #[test]
+    fn test_can_skip_only_uninitialized_without_liquidity() {
+        assert!(can_skip_to_next_word(false, 0, -7680, -50_000, true));
+        assert!(!can_skip_to_next_word(true, 0, -7680, -50_000, true));
+        assert!(!can_skip_to_next_word(false, 1, -7680, -50_000, true));
+    }
+
+    #[test]
+    fn test_can_skip_stops_at_price_limit_tick() {
+        // zero_for_one: keep hopping only while the boundary is above the limit
+        assert!(can_skip_to_next_word(false, 0, -7680, -7681, true));
+        assert!(!can_skip_to_next_word(false, 0, -7680, -7680, true));
+        // one_for_zero: keep hopping only while the boundary is below the limit
+        assert!(can_skip_to_next_word(false, 0, 7679, 7680, false));
+        assert!(!can_skip_to_next_word(false, 0, 7680, 7680, false));
+    }
+
+    #[test]
+    fn test_can_skip_stops_at_tick_bounds() {
+        use dex_types::{MAX_TICK, MIN_TICK};
+        assert!(!can_skip_to_next_word(false, 0, MIN_TICK, MIN_TICK - 1, true));
+        assert!(!can_skip_to_next_word(false, 0, MAX_TICK, MAX_TICK + 1, false));
+    }
+
     // === init_swap_state tests ===
 
     #[test]
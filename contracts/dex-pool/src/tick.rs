@@ -17,19 +17,33 @@
 // 2. PURE TICK COMPUTATION FUNCTIONS:
 //    - compute_liquidity_after_update: Calculate new liquidity values
 //    - compute_fee_growth_after_cross: Calculate fee growth flip
-//    - compute_fee_growth_inside: Calculate fee growth inside a range
+//    - compute_fee_growth_inside: Calculate fee growth inside a range from its ticks
+//    - compute_cumulatives_after_cross: Flip oracle outside values
+//    - compute_cumulatives_inside: Oracle cumulatives inside a range
 //
 // 3. SIDE EFFECT FUNCTIONS:
 //    - update: Update tick in storage
+//    - update_with_info: Update tick in storage, returning the tick as written
 //    - cross: Cross a tick (updates storage)
+//    - get_cumulatives_inside: Oracle cumulatives inside a range (reads storage)
 //    - flip_tick: Flip tick in bitmap (updates storage)
+//    - flip_range_ticks: Flip a range's ticks, sharing a word read (updates storage)
 //    - next_initialized_tick_within_one_word: Find next tick (reads storage)
+//    - next_initialized_tick_within_one_word_cached: Same, reusing the last word read
+//    - get_populated_ticks_in_word: List initialized ticks in a word (reads storage)
+//    - get_ticks_paginated: List initialized ticks in a tick range (reads storage)
 //
 // ============================================================================
 
-use crate::storage::{get_tick, get_tick_bitmap_word, set_tick, set_tick_bitmap_word};
-use dex_types::TickInfo;
-use soroban_sdk::Env;
+use crate::oracle::Cumulatives;
+use crate::storage::{
+    get_tick, get_tick_bitmap_word, set_tick, set_tick_bitmap_word, MAX_BITMAP_WORDS_PER_PAGE,
+    MAX_POPULATED_TICKS_PER_PAGE,
+};
+use dex_types::{
+    PoolError, PopulatedTick, PopulatedTickPage, PopulatedTickRange, TickInfo, MAX_TICK, MIN_TICK,
+};
+use soroban_sdk::{panic_with_error, Env, Vec};
 
 // ============================================================================
 // PURE BITMAP FUNCTIONS - No storage access, formally verifiable
@@ -38,11 +52,14 @@ use soroban_sdk::Env;
 /// Convert a tick index to bitmap position (pure)
 /// Returns (word_position, bit_position)
 ///
+/// Unaligned ticks compress toward negative infinity, so a tick between two
+/// spacings maps to the one at or below it.
+///
 /// # Properties (for formal verification)
 /// - bit_position is always in [0, 127]
 /// - bitmap_position_to_tick(tick_to_bitmap_position(t, s), s) == t (for aligned ticks)
 pub fn tick_to_bitmap_position(tick: i32, tick_spacing: i32) -> (i32, u8) {
-    let compressed = tick / tick_spacing;
+    let compressed = tick.div_euclid(tick_spacing);
     let word_pos = compressed >> 7; // divide by 128
     let bit_pos = (compressed.rem_euclid(128)) as u8;
     (word_pos, bit_pos)
@@ -174,6 +191,7 @@ pub fn compute_next_tick_gt(
 /// Compute new liquidity values after a tick update (pure)
 /// Returns (liquidity_gross_after, liquidity_net_after, flipped, should_init_fee_growth)
 pub fn compute_liquidity_after_update(
+    env: &Env,
     liquidity_gross_before: u128,
     liquidity_net_before: i128,
     liquidity_delta: i128,
@@ -190,7 +208,7 @@ pub fn compute_liquidity_after_update(
     };
 
     if liquidity_gross_after > max_liquidity {
-        panic!("Liquidity overflow");
+        panic_with_error!(env, PoolError::LiquidityOverflow);
     }
 
     // Check if tick state flipped (initialized <-> uninitialized)
@@ -212,7 +230,10 @@ pub fn compute_liquidity_after_update(
 /// Compute fee growth values after crossing a tick (pure)
 /// Returns (new_fee_growth_outside_0, new_fee_growth_outside_1)
 ///
-/// When crossing a tick, fee_growth_outside is flipped relative to global
+/// When crossing a tick, fee_growth_outside is flipped relative to global.
+/// Fee growth accumulators are Q128.128 values that wrap modulo 2^128, so an
+/// outside value may legitimately exceed global; only differences between
+/// snapshots are meaningful, and all subtractions here wrap.
 pub fn compute_fee_growth_after_cross(
     fee_growth_outside_0: u128,
     fee_growth_outside_1: u128,
@@ -220,12 +241,12 @@ pub fn compute_fee_growth_after_cross(
     fee_growth_global_1: u128,
 ) -> (u128, u128) {
     (
-        fee_growth_global_0 - fee_growth_outside_0,
-        fee_growth_global_1 - fee_growth_outside_1,
+        fee_growth_global_0.wrapping_sub(fee_growth_outside_0),
+        fee_growth_global_1.wrapping_sub(fee_growth_outside_1),
     )
 }
 
-/// Compute fee growth below a tick (pure)
+/// Compute fee growth below a tick (pure, wrapping Q128.128)
 pub fn compute_fee_growth_below(
     tick: i32,
     tick_current: i32,
@@ -238,13 +259,13 @@ pub fn compute_fee_growth_below(
         (fee_growth_outside_0, fee_growth_outside_1)
     } else {
         (
-            fee_growth_global_0 - fee_growth_outside_0,
-            fee_growth_global_1 - fee_growth_outside_1,
+            fee_growth_global_0.wrapping_sub(fee_growth_outside_0),
+            fee_growth_global_1.wrapping_sub(fee_growth_outside_1),
         )
     }
 }
 
-/// Compute fee growth above a tick (pure)
+/// Compute fee growth above a tick (pure, wrapping Q128.128)
 pub fn compute_fee_growth_above(
     tick: i32,
     tick_current: i32,
@@ -257,8 +278,8 @@ pub fn compute_fee_growth_above(
         (fee_growth_outside_0, fee_growth_outside_1)
     } else {
         (
-            fee_growth_global_0 - fee_growth_outside_0,
-            fee_growth_global_1 - fee_growth_outside_1,
+            fee_growth_global_0.wrapping_sub(fee_growth_outside_0),
+            fee_growth_global_1.wrapping_sub(fee_growth_outside_1),
         )
     }
 }
@@ -266,8 +287,9 @@ pub fn compute_fee_growth_above(
 /// Compute fee growth inside a tick range (pure)
 ///
 /// # Properties (for formal verification)
-/// - fee_inside = global - below - above
-/// - Result uses wrapping subtraction for Q128.128 math
+/// - fee_inside = global - below - above (mod 2^128)
+/// - Result uses wrapping subtraction for Q128.128 math; it may exceed global
+///   after a wrap, but `inside_now - inside_last` still yields the true delta
 pub fn compute_fee_growth_inside_pure(
     fee_growth_below_0: u128,
     fee_growth_below_1: u128,
@@ -277,17 +299,123 @@ pub fn compute_fee_growth_inside_pure(
     fee_growth_global_1: u128,
 ) -> (u128, u128) {
     (
-        fee_growth_global_0 - fee_growth_below_0 - fee_growth_above_0,
-        fee_growth_global_1 - fee_growth_below_1 - fee_growth_above_1,
+        fee_growth_global_0
+            .wrapping_sub(fee_growth_below_0)
+            .wrapping_sub(fee_growth_above_0),
+        fee_growth_global_1
+            .wrapping_sub(fee_growth_below_1)
+            .wrapping_sub(fee_growth_above_1),
     )
 }
 
+/// Compute fee growth inside a tick range from its boundary ticks (pure)
+pub fn compute_fee_growth_inside(
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_current: i32,
+    lower: &TickInfo,
+    upper: &TickInfo,
+    fee_growth_global_0_x128: u128,
+    fee_growth_global_1_x128: u128,
+) -> (u128, u128) {
+    let (fee_growth_below_0, fee_growth_below_1) = compute_fee_growth_below(
+        tick_lower,
+        tick_current,
+        lower.fee_growth_outside_0_x128,
+        lower.fee_growth_outside_1_x128,
+        fee_growth_global_0_x128,
+        fee_growth_global_1_x128,
+    );
+
+    let (fee_growth_above_0, fee_growth_above_1) = compute_fee_growth_above(
+        tick_upper,
+        tick_current,
+        upper.fee_growth_outside_0_x128,
+        upper.fee_growth_outside_1_x128,
+        fee_growth_global_0_x128,
+        fee_growth_global_1_x128,
+    );
+
+    compute_fee_growth_inside_pure(
+        fee_growth_below_0,
+        fee_growth_below_1,
+        fee_growth_above_0,
+        fee_growth_above_1,
+        fee_growth_global_0_x128,
+        fee_growth_global_1_x128,
+    )
+}
+
+/// Compute oracle outside values after crossing a tick (pure)
+/// Returns (tick_cumulative_outside, seconds_per_liq_outside_x128, seconds_outside)
+///
+/// Like fee growth, each outside value is flipped relative to the current cumulative
+pub fn compute_cumulatives_after_cross(
+    info: &TickInfo,
+    cumulatives: &Cumulatives,
+) -> (i64, u128, u64) {
+    (
+        cumulatives.tick_cumulative - info.tick_cumulative_outside,
+        cumulatives
+            .seconds_per_liquidity_x128
+            .wrapping_sub(info.seconds_per_liq_outside_x128),
+        cumulatives.time.wrapping_sub(info.seconds_outside),
+    )
+}
+
+/// Compute oracle cumulatives inside a tick range (pure)
+/// Returns (tick_cumulative_inside, seconds_per_liquidity_inside_x128, seconds_inside)
+///
+/// `cumulatives` is only read when the current tick is inside the range.
+/// Values are only meaningful as differences between two snapshots.
+pub fn compute_cumulatives_inside(
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_current: i32,
+    lower: &TickInfo,
+    upper: &TickInfo,
+    cumulatives: &Cumulatives,
+) -> (i64, u128, u64) {
+    if tick_current < tick_lower {
+        (
+            lower.tick_cumulative_outside - upper.tick_cumulative_outside,
+            lower
+                .seconds_per_liq_outside_x128
+                .wrapping_sub(upper.seconds_per_liq_outside_x128),
+            lower.seconds_outside.wrapping_sub(upper.seconds_outside),
+        )
+    } else if tick_current < tick_upper {
+        (
+            cumulatives.tick_cumulative
+                - lower.tick_cumulative_outside
+                - upper.tick_cumulative_outside,
+            cumulatives
+                .seconds_per_liquidity_x128
+                .wrapping_sub(lower.seconds_per_liq_outside_x128)
+                .wrapping_sub(upper.seconds_per_liq_outside_x128),
+            cumulatives
+                .time
+                .wrapping_sub(lower.seconds_outside)
+                .wrapping_sub(upper.seconds_outside),
+        )
+    } else {
+        (
+            upper.tick_cumulative_outside - lower.tick_cumulative_outside,
+            upper
+                .seconds_per_liq_outside_x128
+                .wrapping_sub(lower.seconds_per_liq_outside_x128),
+            upper.seconds_outside.wrapping_sub(lower.seconds_outside),
+        )
+    }
+}
+
 // ============================================================================
 // SIDE EFFECT FUNCTIONS - Storage operations
 // ============================================================================
 
 /// Update a tick with liquidity delta (side effect)
 /// Returns true if the tick was flipped (initialized or uninitialized)
+#[cfg(test)]
 pub fn update(
     env: &Env,
     tick: i32,
@@ -295,14 +423,45 @@ pub fn update(
     liquidity_delta: i128,
     fee_growth_global_0_x128: u128,
     fee_growth_global_1_x128: u128,
+    cumulatives: &Cumulatives,
     upper: bool,
     max_liquidity: u128,
 ) -> bool {
+    update_with_info(
+        env,
+        tick,
+        tick_current,
+        liquidity_delta,
+        fee_growth_global_0_x128,
+        fee_growth_global_1_x128,
+        cumulatives,
+        upper,
+        max_liquidity,
+    )
+    .0
+}
+
+/// Update a tick with liquidity delta (side effect)
+/// Returns (flipped, info) where info is the tick as written, so callers can
+/// compute fee growth inside without reading it back. An emptied tick is
+/// removed from storage but its outside values are still in `info`.
+pub fn update_with_info(
+    env: &Env,
+    tick: i32,
+    tick_current: i32,
+    liquidity_delta: i128,
+    fee_growth_global_0_x128: u128,
+    fee_growth_global_1_x128: u128,
+    cumulatives: &Cumulatives,
+    upper: bool,
+    max_liquidity: u128,
+) -> (bool, TickInfo) {
     let mut info = get_tick(env, tick);
 
     // Pure computation
     let (liquidity_gross_after, liquidity_net_after, flipped, should_init_fee_growth) =
         compute_liquidity_after_update(
+            env,
             info.liquidity_gross,
             info.liquidity_net,
             liquidity_delta,
@@ -313,21 +472,31 @@ pub fn update(
         );
 
     // Apply state changes
+    // By convention, all growth before a tick was initialized happened below it
     if should_init_fee_growth {
         info.fee_growth_outside_0_x128 = fee_growth_global_0_x128;
         info.fee_growth_outside_1_x128 = fee_growth_global_1_x128;
+        info.tick_cumulative_outside = cumulatives.tick_cumulative;
+        info.seconds_per_liq_outside_x128 = cumulatives.seconds_per_liquidity_x128;
+        info.seconds_outside = cumulatives.time;
     }
 
     if info.liquidity_gross == 0 && liquidity_gross_after > 0 {
         info.initialized = true;
     }
 
+    // A tick with no remaining references is uninitialized again; set_tick
+    // then removes the entry so get_tick agrees with the bitmap
+    if liquidity_gross_after == 0 {
+        info.initialized = false;
+    }
+
     info.liquidity_gross = liquidity_gross_after;
     info.liquidity_net = liquidity_net_after;
 
     set_tick(env, tick, &info);
 
-    flipped
+    (flipped, info)
 }
 
 /// Cross a tick during a swap (side effect)
@@ -337,6 +506,7 @@ pub fn cross(
     tick: i32,
     fee_growth_global_0_x128: u128,
     fee_growth_global_1_x128: u128,
+    cumulatives: &Cumulatives,
 ) -> i128 {
     let mut info = get_tick(env, tick);
 
@@ -348,9 +518,15 @@ pub fn cross(
         fee_growth_global_1_x128,
     );
 
+    let (tick_cumulative_outside, seconds_per_liq_outside_x128, seconds_outside) =
+        compute_cumulatives_after_cross(&info, cumulatives);
+
     // Apply state changes
     info.fee_growth_outside_0_x128 = new_fee_0;
     info.fee_growth_outside_1_x128 = new_fee_1;
+    info.tick_cumulative_outside = tick_cumulative_outside;
+    info.seconds_per_liq_outside_x128 = seconds_per_liq_outside_x128;
+    info.seconds_outside = seconds_outside;
 
     set_tick(env, tick, &info);
 
@@ -369,39 +545,46 @@ pub fn get_fee_growth_inside(
     let lower = get_tick(env, tick_lower);
     let upper = get_tick(env, tick_upper);
 
-    // Pure computations
-    let (fee_growth_below_0, fee_growth_below_1) = compute_fee_growth_below(
+    compute_fee_growth_inside(
         tick_lower,
+        tick_upper,
         tick_current,
-        lower.fee_growth_outside_0_x128,
-        lower.fee_growth_outside_1_x128,
+        &lower,
+        &upper,
         fee_growth_global_0_x128,
         fee_growth_global_1_x128,
-    );
+    )
+}
 
-    let (fee_growth_above_0, fee_growth_above_1) = compute_fee_growth_above(
+/// Get oracle cumulatives inside a tick range (side effect - reads storage)
+/// Both ticks must be initialized
+pub fn get_cumulatives_inside(
+    env: &Env,
+    tick_lower: i32,
+    tick_upper: i32,
+    tick_current: i32,
+    cumulatives: &Cumulatives,
+) -> (i64, u128, u64) {
+    let lower = get_tick(env, tick_lower);
+    let upper = get_tick(env, tick_upper);
+    if !lower.initialized || !upper.initialized {
+        panic_with_error!(env, PoolError::TickNotInitialized);
+    }
+
+    compute_cumulatives_inside(
+        tick_lower,
         tick_upper,
         tick_current,
-        upper.fee_growth_outside_0_x128,
-        upper.fee_growth_outside_1_x128,
-        fee_growth_global_0_x128,
-        fee_growth_global_1_x128,
-    );
-
-    compute_fee_growth_inside_pure(
-        fee_growth_below_0,
-        fee_growth_below_1,
-        fee_growth_above_0,
-        fee_growth_above_1,
-        fee_growth_global_0_x128,
-        fee_growth_global_1_x128,
+        &lower,
+        &upper,
+        cumulatives,
     )
 }
 
 /// Flip a tick in the bitmap (side effect)
 pub fn flip_tick(env: &Env, tick: i32, tick_spacing: i32) {
     if tick % tick_spacing != 0 {
-        panic!("Tick not on spacing");
+        panic_with_error!(env, PoolError::TickNotOnSpacing);
     }
 
     let (word_pos, bit_pos) = tick_to_bitmap_position(tick, tick_spacing);
@@ -410,30 +593,206 @@ pub fn flip_tick(env: &Env, tick: i32, tick_spacing: i32) {
     set_tick_bitmap_word(env, word_pos, word ^ mask);
 }
 
+/// Flip a position's boundary ticks in the bitmap (side effect)
+/// Only the ticks marked flipped change; when both share a word it is read
+/// and written once
+pub fn flip_range_ticks(
+    env: &Env,
+    tick_lower: i32,
+    flipped_lower: bool,
+    tick_upper: i32,
+    flipped_upper: bool,
+    tick_spacing: i32,
+) {
+    if !(flipped_lower && flipped_upper) {
+        if flipped_lower {
+            flip_tick(env, tick_lower, tick_spacing);
+        }
+        if flipped_upper {
+            flip_tick(env, tick_upper, tick_spacing);
+        }
+        return;
+    }
+
+    if tick_lower % tick_spacing != 0 || tick_upper % tick_spacing != 0 {
+        panic_with_error!(env, PoolError::TickNotOnSpacing);
+    }
+
+    let (word_lower, bit_lower) = tick_to_bitmap_position(tick_lower, tick_spacing);
+    let (word_upper, bit_upper) = tick_to_bitmap_position(tick_upper, tick_spacing);
+    if word_lower == word_upper {
+        let mask = (1u128 << bit_lower) | (1u128 << bit_upper);
+        let word = get_tick_bitmap_word(env, word_lower);
+        set_tick_bitmap_word(env, word_lower, word ^ mask);
+    } else {
+        flip_tick(env, tick_lower, tick_spacing);
+        flip_tick(env, tick_upper, tick_spacing);
+    }
+}
+
 /// Find the next initialized tick within one word (side effect - reads storage)
 /// Returns (tick, initialized)
+#[cfg(test)]
 pub fn next_initialized_tick_within_one_word(
     env: &Env,
     tick: i32,
     tick_spacing: i32,
     lte: bool, // less than or equal (searching left)
 ) -> (i32, bool) {
-    let compressed = tick / tick_spacing;
+    next_initialized_tick_within_one_word_cached(env, tick, tick_spacing, lte, &mut None)
+}
+
+/// Find the next initialized tick within one word, reusing `cached_word`
+/// (word_pos, word) when the search lands in the word read last (side effect -
+/// reads storage). Returns (tick, initialized)
+///
+/// A swap walks the bitmap in one direction without flipping bits, so it only
+/// needs to keep the most recent word.
+pub fn next_initialized_tick_within_one_word_cached(
+    env: &Env,
+    tick: i32,
+    tick_spacing: i32,
+    lte: bool, // less than or equal (searching left)
+    cached_word: &mut Option<(i32, u128)>,
+) -> (i32, bool) {
+    let (word_pos, bit_pos) = if lte {
+        tick_to_bitmap_position(tick, tick_spacing)
+    } else {
+        // Search right (greater than) starts one spacing up
+        let compressed_plus_one = tick.div_euclid(tick_spacing) + 1;
+        let bit_pos = compressed_plus_one.rem_euclid(128) as u8;
+        (compressed_plus_one >> 7, bit_pos)
+    };
+
+    let word = match *cached_word {
+        Some((cached_pos, word)) if cached_pos == word_pos => word,
+        _ => {
+            let word = get_tick_bitmap_word(env, word_pos);
+            *cached_word = Some((word_pos, word));
+            word
+        }
+    };
 
     if lte {
-        let (word_pos, bit_pos) = tick_to_bitmap_position(tick, tick_spacing);
-        let word = get_tick_bitmap_word(env, word_pos);
         compute_next_tick_lte(word, word_pos, bit_pos, tick_spacing)
     } else {
-        // Search right (greater than)
-        let compressed_plus_one = compressed + 1;
-        let word_pos = compressed_plus_one >> 7;
-        let bit_pos = (compressed_plus_one.rem_euclid(128)) as u8;
-        let word = get_tick_bitmap_word(env, word_pos);
         compute_next_tick_gt(word, word_pos, bit_pos, tick_spacing)
     }
 }
 
+/// List the initialized ticks of one bitmap word (side effect - reads storage)
+///
+/// Reads the word once, then only the ticks whose bits are set at or above
+/// `start_bit`, at most MAX_POPULATED_TICKS_PER_PAGE of them. When more remain,
+/// `next_start_bit` is the bit to resume from.
+pub fn get_populated_ticks_in_word(
+    env: &Env,
+    word_pos: i32,
+    start_bit: u32,
+    tick_spacing: i32,
+) -> PopulatedTickPage {
+    let mut ticks = Vec::new(env);
+    let mut remaining = if start_bit < 128 {
+        get_tick_bitmap_word(env, word_pos) & create_mask_at_or_above(start_bit as u8)
+    } else {
+        0
+    };
+
+    while let Some(bit) = find_least_significant_bit(remaining) {
+        if ticks.len() == MAX_POPULATED_TICKS_PER_PAGE {
+            return PopulatedTickPage {
+                ticks,
+                next_start_bit: Some(bit as u32),
+            };
+        }
+
+        let tick = bitmap_position_to_tick(word_pos, bit as i32, tick_spacing);
+        let info = get_tick(env, tick);
+        ticks.push_back(PopulatedTick {
+            tick,
+            liquidity_gross: info.liquidity_gross,
+            liquidity_net: info.liquidity_net,
+        });
+        remaining &= !(1u128 << bit);
+    }
+
+    PopulatedTickPage {
+        ticks,
+        next_start_bit: None,
+    }
+}
+
+/// List the initialized ticks in [start_tick, end_tick] (side effect - reads storage)
+///
+/// Walks the bitmap words covering the range in ascending order, returning at
+/// most `limit` ticks (raised to 1, capped at MAX_POPULATED_TICKS_PER_PAGE) and
+/// reading at most MAX_BITMAP_WORDS_PER_PAGE words. When either cap stops the
+/// walk, `next_start_tick` is where to resume. A zero limit still returns a
+/// tick so that every page makes progress.
+pub fn get_ticks_paginated(
+    env: &Env,
+    start_tick: i32,
+    end_tick: i32,
+    limit: u32,
+    tick_spacing: i32,
+) -> PopulatedTickRange {
+    let limit = limit.clamp(1, MAX_POPULATED_TICKS_PER_PAGE);
+    let mut ticks = Vec::new(env);
+
+    // First spacing-aligned tick at or above the start; the end compresses downward
+    let first_tick = ((start_tick.max(MIN_TICK) - 1).div_euclid(tick_spacing) + 1) * tick_spacing;
+    let end_tick = end_tick.min(MAX_TICK);
+    if first_tick > end_tick {
+        return PopulatedTickRange {
+            ticks,
+            next_start_tick: None,
+        };
+    }
+
+    let (first_word, first_bit) = tick_to_bitmap_position(first_tick, tick_spacing);
+    let (last_word, last_bit) = tick_to_bitmap_position(end_tick, tick_spacing);
+
+    for (words_read, word_pos) in (first_word..=last_word).enumerate() {
+        if words_read as u32 == MAX_BITMAP_WORDS_PER_PAGE {
+            return PopulatedTickRange {
+                ticks,
+                next_start_tick: Some(bitmap_position_to_tick(word_pos, 0, tick_spacing)),
+            };
+        }
+
+        let mut remaining = get_tick_bitmap_word(env, word_pos);
+        if word_pos == first_word {
+            remaining &= create_mask_at_or_above(first_bit);
+        }
+        if word_pos == last_word {
+            remaining &= create_mask_at_or_below(last_bit);
+        }
+
+        while let Some(bit) = find_least_significant_bit(remaining) {
+            let tick = bitmap_position_to_tick(word_pos, bit as i32, tick_spacing);
+            if ticks.len() == limit {
+                return PopulatedTickRange {
+                    ticks,
+                    next_start_tick: Some(tick),
+                };
+            }
+
+            let info = get_tick(env, tick);
+            ticks.push_back(PopulatedTick {
+                tick,
+                liquidity_gross: info.liquidity_gross,
+                liquidity_net: info.liquidity_net,
+            });
+            remaining &= !(1u128 << bit);
+        }
+    }
+
+    PopulatedTickRange {
+        ticks,
+        next_start_tick: None,
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -574,8 +933,9 @@ mod tests {
 
     #[test]
     fn test_compute_liquidity_after_update_add() {
+        let env = Env::default();
         let (gross, net, flipped, init_fee) =
-            compute_liquidity_after_update(0, 0, 1000, false, u128::MAX, -100, 0);
+            compute_liquidity_after_update(&env, 0, 0, 1000, false, u128::MAX, -100, 0);
         assert_eq!(gross, 1000);
         assert_eq!(net, 1000);
         assert!(flipped); // 0 -> non-zero
@@ -584,8 +944,9 @@ mod tests {
 
     #[test]
     fn test_compute_liquidity_after_update_add_upper() {
+        let env = Env::default();
         let (gross, net, flipped, _) =
-            compute_liquidity_after_update(0, 0, 1000, true, u128::MAX, 100, 0);
+            compute_liquidity_after_update(&env, 0, 0, 1000, true, u128::MAX, 100, 0);
         assert_eq!(gross, 1000);
         assert_eq!(net, -1000); // upper tick subtracts
         assert!(flipped);
@@ -593,8 +954,9 @@ mod tests {
 
     #[test]
     fn test_compute_liquidity_after_update_remove() {
+        let env = Env::default();
         let (gross, net, flipped, _) =
-            compute_liquidity_after_update(1000, 1000, -500, false, u128::MAX, 0, 0);
+            compute_liquidity_after_update(&env, 1000, 1000, -500, false, u128::MAX, 0, 0);
         assert_eq!(gross, 500);
         assert_eq!(net, 500);
         assert!(!flipped); // still has liquidity
@@ -602,16 +964,18 @@ mod tests {
 
     #[test]
     fn test_compute_liquidity_after_update_remove_all() {
+        let env = Env::default();
         let (gross, _, flipped, _) =
-            compute_liquidity_after_update(1000, 1000, -1000, false, u128::MAX, 0, 0);
+            compute_liquidity_after_update(&env, 1000, 1000, -1000, false, u128::MAX, 0, 0);
         assert_eq!(gross, 0);
         assert!(flipped); // non-zero -> 0
     }
 
     #[test]
-    #[should_panic(expected = "Liquidity overflow")]
+    #[should_panic(expected = "Error(Contract, #120)")]
     fn test_compute_liquidity_after_update_overflow() {
-        compute_liquidity_after_update(0, 0, 1000, false, 500, 0, 0);
+        let env = Env::default();
+        compute_liquidity_after_update(&env, 0, 0, 1000, false, 500, 0, 0);
     }
 
     // ============================================================================
@@ -685,6 +1049,97 @@ mod tests {
         assert_eq!(inside_1, 1700); // 2000 - 200 - 100
     }
 
+    #[test]
+    fn test_compute_fee_growth_after_cross_wraps_when_outside_exceeds_global() {
+        // Outside above global is reachable once global has wrapped past 2^128
+        let (new_0, new_1) = compute_fee_growth_after_cross(1000, 300, 100, 200);
+        assert_eq!(new_0, 100u128.wrapping_sub(1000));
+        assert_eq!(new_1, u128::MAX - 99); // 200 - 300 mod 2^128
+
+        // Crossing back after global grows by 50 recovers outside + 50
+        let (back_0, back_1) = compute_fee_growth_after_cross(new_0, new_1, 150, 250);
+        assert_eq!(back_0, 1050);
+        assert_eq!(back_1, 350);
+    }
+
+    #[test]
+    fn test_compute_fee_growth_below_and_above_wrap() {
+        let (below_0, below_1) = compute_fee_growth_below(100, 0, 1000, 2000, 100, 200);
+        assert_eq!(below_0, 100u128.wrapping_sub(1000));
+        assert_eq!(below_1, 200u128.wrapping_sub(2000));
+
+        let (above_0, above_1) = compute_fee_growth_above(-100, 0, 1000, 2000, 100, 200);
+        assert_eq!(above_0, 100u128.wrapping_sub(1000));
+        assert_eq!(above_1, 200u128.wrapping_sub(2000));
+    }
+
+    #[test]
+    fn test_compute_fee_growth_inside_wraps_with_correct_delta() {
+        // below (500, MAX) + above (700, 10) exceeds global (1000, 5): wraps
+        let (inside_0, inside_1) = compute_fee_growth_inside_pure(500, u128::MAX, 700, 10, 1000, 5);
+        assert_eq!(inside_0, 0u128.wrapping_sub(200));
+        assert_eq!(inside_1, 5u128.wrapping_sub(u128::MAX).wrapping_sub(10));
+
+        // Global grows by 300 / 40 while in range: the wrapped snapshots
+        // still give the true fee growth delta a position would be credited
+        let (later_0, later_1) = compute_fee_growth_inside_pure(500, u128::MAX, 700, 10, 1300, 45);
+        assert_eq!(later_0.wrapping_sub(inside_0), 300);
+        assert_eq!(later_1.wrapping_sub(inside_1), 40);
+    }
+
+    #[test]
+    fn test_compute_cumulatives_after_cross_flips_outside() {
+        let info = TickInfo {
+            tick_cumulative_outside: 300,
+            seconds_per_liq_outside_x128: 40,
+            seconds_outside: 1000,
+            ..Default::default()
+        };
+        let cumulatives = Cumulatives {
+            time: 1500,
+            tick_cumulative: 800,
+            seconds_per_liquidity_x128: 100,
+        };
+        assert_eq!(compute_cumulatives_after_cross(&info, &cumulatives), (500, 60, 500));
+    }
+
+    #[test]
+    fn test_compute_cumulatives_inside_by_position() {
+        let lower = TickInfo {
+            tick_cumulative_outside: 100,
+            seconds_per_liq_outside_x128: 10,
+            seconds_outside: 1000,
+            ..Default::default()
+        };
+        let upper = TickInfo {
+            tick_cumulative_outside: 40,
+            seconds_per_liq_outside_x128: 4,
+            seconds_outside: 300,
+            ..Default::default()
+        };
+        let now = Cumulatives {
+            time: 2000,
+            tick_cumulative: 500,
+            seconds_per_liquidity_x128: 50,
+        };
+
+        // Below the range: lower - upper
+        assert_eq!(
+            compute_cumulatives_inside(-60, 60, -120, &lower, &upper, &now),
+            (60, 6, 700)
+        );
+        // Inside the range: now - lower - upper
+        assert_eq!(
+            compute_cumulatives_inside(-60, 60, 0, &lower, &upper, &now),
+            (360, 36, 700)
+        );
+        // Above the range: upper - lower
+        assert_eq!(
+            compute_cumulatives_inside(-60, 60, 120, &lower, &upper, &now),
+            (-60, 4u128.wrapping_sub(10), 300u64.wrapping_sub(1000))
+        );
+    }
+
     // ============================================================================
     // STORAGE-BASED TESTS (existing tests, updated)
     // ============================================================================
@@ -705,6 +1160,7 @@ mod tests {
                 liquidity_delta,
                 0,
                 0,
+                &Cumulatives::default(),
                 false,
                 max_liquidity,
             );
@@ -726,9 +1182,29 @@ mod tests {
             let tick_current = 0;
             let max_liquidity = u128::MAX;
 
-            update(&env, tick, tick_current, 1000, 0, 0, false, max_liquidity);
+            update(
+                &env,
+                tick,
+                tick_current,
+                1000,
+                0,
+                0,
+                &Cumulatives::default(),
+                false,
+                max_liquidity,
+            );
 
-            let flipped = update(&env, tick, tick_current, 500, 0, 0, false, max_liquidity);
+            let flipped = update(
+                &env,
+                tick,
+                tick_current,
+                500,
+                0,
+                0,
+                &Cumulatives::default(),
+                false,
+                max_liquidity,
+            );
             assert!(!flipped);
 
             let info = get_tick(&env, tick);
@@ -745,7 +1221,17 @@ mod tests {
             let tick_current = 0;
             let max_liquidity = u128::MAX;
 
-            update(&env, tick, tick_current, 1000, 0, 0, true, max_liquidity);
+            update(
+                &env,
+                tick,
+                tick_current,
+                1000,
+                0,
+                0,
+                &Cumulatives::default(),
+                true,
+                max_liquidity,
+            );
 
             let info = get_tick(&env, tick);
             assert_eq!(info.liquidity_gross, 1000);
@@ -761,9 +1247,29 @@ mod tests {
             let tick_current = 0;
             let max_liquidity = u128::MAX;
 
-            update(&env, tick, tick_current, 1000, 0, 0, false, max_liquidity);
+            update(
+                &env,
+                tick,
+                tick_current,
+                1000,
+                0,
+                0,
+                &Cumulatives::default(),
+                false,
+                max_liquidity,
+            );
 
-            let flipped = update(&env, tick, tick_current, -400, 0, 0, false, max_liquidity);
+            let flipped = update(
+                &env,
+                tick,
+                tick_current,
+                -400,
+                0,
+                0,
+                &Cumulatives::default(),
+                false,
+                max_liquidity,
+            );
             assert!(!flipped);
 
             let info = get_tick(&env, tick);
@@ -780,9 +1286,29 @@ mod tests {
             let tick_current = 0;
             let max_liquidity = u128::MAX;
 
-            update(&env, tick, tick_current, 1000, 0, 0, false, max_liquidity);
+            update(
+                &env,
+                tick,
+                tick_current,
+                1000,
+                0,
+                0,
+                &Cumulatives::default(),
+                false,
+                max_liquidity,
+            );
 
-            let flipped = update(&env, tick, tick_current, -1000, 0, 0, false, max_liquidity);
+            let flipped = update(
+                &env,
+                tick,
+                tick_current,
+                -1000,
+                0,
+                0,
+                &Cumulatives::default(),
+                false,
+                max_liquidity,
+            );
             assert!(flipped);
 
             let info = get_tick(&env, tick);
@@ -807,6 +1333,7 @@ mod tests {
                 1000,
                 fee_growth_0,
                 fee_growth_1,
+                &Cumulatives::default(),
                 false,
                 max_liquidity,
             );
@@ -834,6 +1361,7 @@ mod tests {
                 1000,
                 fee_growth_0,
                 fee_growth_1,
+                &Cumulatives::default(),
                 false,
                 max_liquidity,
             );
@@ -845,7 +1373,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Liquidity overflow")]
+    #[should_panic(expected = "Error(Contract, #120)")]
     fn test_update_exceeds_max_liquidity() {
         let env = Env::default();
         with_contract(&env, || {
@@ -853,7 +1381,17 @@ mod tests {
             let tick_current = 0;
             let max_liquidity = 1000u128;
 
-            update(&env, tick, tick_current, 2000, 0, 0, false, max_liquidity);
+            update(
+                &env,
+                tick,
+                tick_current,
+                2000,
+                0,
+                0,
+                &Cumulatives::default(),
+                false,
+                max_liquidity,
+            );
         });
     }
 
@@ -868,6 +1406,9 @@ mod tests {
                 liquidity_net: 500,
                 fee_growth_outside_0_x128: 100,
                 fee_growth_outside_1_x128: 200,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             set_tick(&env, tick, &info);
@@ -875,7 +1416,13 @@ mod tests {
             let fee_global_0 = 1000u128;
             let fee_global_1 = 2000u128;
 
-            let liquidity_net = cross(&env, tick, fee_global_0, fee_global_1);
+            let liquidity_net = cross(
+                &env,
+                tick,
+                fee_global_0,
+                fee_global_1,
+                &Cumulatives::default(),
+            );
 
             assert_eq!(liquidity_net, 500);
 
@@ -885,6 +1432,33 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_cross_with_outside_above_global_wraps() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let tick = 0;
+
+            let info = TickInfo {
+                liquidity_gross: 1000,
+                liquidity_net: 500,
+                fee_growth_outside_0_x128: u128::MAX - 10,
+                fee_growth_outside_1_x128: 5000,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
+                initialized: true,
+            };
+            set_tick(&env, tick, &info);
+
+            let liquidity_net = cross(&env, tick, 20, 1000, &Cumulatives::default());
+            assert_eq!(liquidity_net, 500);
+
+            let new_info = get_tick(&env, tick);
+            assert_eq!(new_info.fee_growth_outside_0_x128, 31); // 20 - (2^128 - 11)
+            assert_eq!(new_info.fee_growth_outside_1_x128, 1000u128.wrapping_sub(5000));
+        });
+    }
+
     #[test]
     fn test_cross_returns_liquidity_net() {
         let env = Env::default();
@@ -896,15 +1470,140 @@ mod tests {
                 liquidity_net: -750,
                 fee_growth_outside_0_x128: 0,
                 fee_growth_outside_1_x128: 0,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             set_tick(&env, tick, &info);
 
-            let liquidity_net = cross(&env, tick, 0, 0);
+            let liquidity_net = cross(&env, tick, 0, 0, &Cumulatives::default());
             assert_eq!(liquidity_net, -750);
         });
     }
 
+    #[test]
+    fn test_update_initializes_seconds_outside_below_current() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let cumulatives = Cumulatives {
+                time: 1500,
+                ..Default::default()
+            };
+
+            update(&env, -100, 0, 1000, 0, 0, &cumulatives, false, u128::MAX);
+
+            // All time before initialization counts as spent below the tick
+            assert_eq!(get_tick(&env, -100).seconds_outside, 1500);
+        });
+    }
+
+    #[test]
+    fn test_update_does_not_initialize_seconds_outside_above_current() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let cumulatives = Cumulatives {
+                time: 1500,
+                ..Default::default()
+            };
+
+            update(&env, 100, 0, 1000, 0, 0, &cumulatives, false, u128::MAX);
+
+            assert_eq!(get_tick(&env, 100).seconds_outside, 0);
+        });
+    }
+
+    #[test]
+    fn test_cross_flips_seconds_outside() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let tick = 0;
+
+            let info = TickInfo {
+                liquidity_gross: 1000,
+                liquidity_net: 500,
+                seconds_outside: 400,
+                initialized: true,
+                ..Default::default()
+            };
+            set_tick(&env, tick, &info);
+
+            let cumulatives = Cumulatives {
+                time: 1000,
+                ..Default::default()
+            };
+            cross(&env, tick, 0, 0, &cumulatives);
+            assert_eq!(get_tick(&env, tick).seconds_outside, 600);
+
+            // Crossing back at a later time flips against the new time
+            let cumulatives = Cumulatives {
+                time: 1800,
+                ..Default::default()
+            };
+            cross(&env, tick, 0, 0, &cumulatives);
+            assert_eq!(get_tick(&env, tick).seconds_outside, 1200);
+        });
+    }
+
+    #[test]
+    fn test_cross_with_seconds_outside_above_time_wraps() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let tick = 0;
+
+            let info = TickInfo {
+                liquidity_gross: 1000,
+                liquidity_net: 500,
+                seconds_outside: 5000,
+                initialized: true,
+                ..Default::default()
+            };
+            set_tick(&env, tick, &info);
+
+            let cumulatives = Cumulatives {
+                time: 1000,
+                ..Default::default()
+            };
+            cross(&env, tick, 0, 0, &cumulatives);
+            let seconds_outside = get_tick(&env, tick).seconds_outside;
+            assert_eq!(seconds_outside, 1000u64.wrapping_sub(5000));
+        });
+    }
+
+    #[test]
+    fn test_legacy_tick_reads_with_zero_seconds_outside_until_rewritten() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let tick = 60;
+            let key = crate::storage::DataKey::Tick(tick);
+            let legacy = crate::storage::LegacyTickInfo {
+                liquidity_gross: 1000,
+                liquidity_net: 500,
+                fee_growth_outside_0_x128: 100,
+                fee_growth_outside_1_x128: 200,
+                initialized: true,
+            };
+            env.storage().persistent().set(&key, &legacy);
+
+            let info = get_tick(&env, tick);
+            assert_eq!(info.liquidity_net, 500);
+            assert_eq!(info.fee_growth_outside_1_x128, 200);
+            assert_eq!(info.tick_cumulative_outside, 0);
+            assert_eq!(info.seconds_outside, 0);
+            assert!(info.initialized);
+
+            // Crossing writes the current layout back
+            let cumulatives = Cumulatives {
+                time: 700,
+                ..Default::default()
+            };
+            assert_eq!(cross(&env, tick, 1000, 1000, &cumulatives), 500);
+            let stored: TickInfo = env.storage().persistent().get(&key).unwrap();
+            assert_eq!(stored.seconds_outside, 700);
+            assert_eq!(stored.fee_growth_outside_0_x128, 900);
+        });
+    }
+
     #[test]
     fn test_get_fee_growth_inside_current_in_range() {
         let env = Env::default();
@@ -920,6 +1619,9 @@ mod tests {
                 liquidity_net: 1000,
                 fee_growth_outside_0_x128: 100,
                 fee_growth_outside_1_x128: 200,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             let upper_info = TickInfo {
@@ -927,6 +1629,9 @@ mod tests {
                 liquidity_net: -1000,
                 fee_growth_outside_0_x128: 50,
                 fee_growth_outside_1_x128: 100,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             set_tick(&env, tick_lower, &lower_info);
@@ -963,6 +1668,9 @@ mod tests {
                 liquidity_net: 1000,
                 fee_growth_outside_0_x128: 800,
                 fee_growth_outside_1_x128: 1600,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             let upper_info = TickInfo {
@@ -970,6 +1678,9 @@ mod tests {
                 liquidity_net: -1000,
                 fee_growth_outside_0_x128: 300,
                 fee_growth_outside_1_x128: 600,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             set_tick(&env, tick_lower, &lower_info);
@@ -1010,6 +1721,9 @@ mod tests {
                 liquidity_net: 1000,
                 fee_growth_outside_0_x128: 100,
                 fee_growth_outside_1_x128: 200,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             let upper_info = TickInfo {
@@ -1017,6 +1731,9 @@ mod tests {
                 liquidity_net: -1000,
                 fee_growth_outside_0_x128: 600,
                 fee_growth_outside_1_x128: 1200,
+                tick_cumulative_outside: 0,
+                seconds_per_liq_outside_x128: 0,
+                seconds_outside: 0,
                 initialized: true,
             };
             set_tick(&env, tick_lower, &lower_info);
@@ -1102,7 +1819,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Tick not on spacing")]
+    #[should_panic(expected = "Error(Contract, #119)")]
     fn test_flip_tick_not_on_spacing() {
         let env = Env::default();
         with_contract(&env, || {
@@ -1202,6 +1919,34 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_next_initialized_tick_unaligned_negative_tick() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let tick_spacing = 60;
+
+            // Tick -1 sits in word -1; searching left must not land back on tick 0
+            let (next, initialized) =
+                next_initialized_tick_within_one_word(&env, -1, tick_spacing, true);
+            assert!(!initialized);
+            assert_eq!(next, -128 * 60);
+
+            // Searching right from -1 must still find an initialized tick 0
+            flip_tick(&env, 0, tick_spacing);
+            let (next, initialized) =
+                next_initialized_tick_within_one_word(&env, -1, tick_spacing, false);
+            assert!(initialized);
+            assert_eq!(next, 0);
+        });
+    }
+
+    #[test]
+    fn test_tick_to_bitmap_position_unaligned_negative() {
+        // tick = -1, spacing = 60 -> compressed = -1 (floor) -> word -1, bit 127
+        assert_eq!(tick_to_bitmap_position(-1, 60), (-1, 127));
+        assert_eq!(tick_to_bitmap_position(-61, 60), (-1, 126));
+    }
+
     #[test]
     fn test_position_lifecycle() {
         let env = Env::default();
@@ -1219,6 +1964,7 @@ mod tests {
                 1000,
                 0,
                 0,
+                &Cumulatives::default(),
                 false,
                 max_liquidity,
             );
@@ -1229,6 +1975,7 @@ mod tests {
                 1000,
                 0,
                 0,
+                &Cumulatives::default(),
                 true,
                 max_liquidity,
             );
@@ -1245,6 +1992,7 @@ mod tests {
                 -1000,
                 0,
                 0,
+                &Cumulatives::default(),
                 false,
                 max_liquidity,
             );
@@ -1255,6 +2003,7 @@ mod tests {
                 -1000,
                 0,
                 0,
+                &Cumulatives::default(),
                 true,
                 max_liquidity,
             );
@@ -1267,4 +2016,61 @@ mod tests {
             assert_eq!(upper_info.liquidity_gross, 0);
         });
     }
+
+    #[test]
+    fn test_update_remove_all_liquidity_clears_initialized() {
+        let env = Env::default();
+        with_contract(&env, || {
+            let tick_lower = -60;
+            let tick_upper = 60;
+            let tick_current = 0;
+            let tick_spacing = 60;
+            let max_liquidity = u128::MAX;
+
+            // Mint: both ticks initialized and flipped into the bitmap
+            for (tick, upper) in [(tick_lower, false), (tick_upper, true)] {
+                let flipped = update(
+                    &env,
+                    tick,
+                    tick_current,
+                    1000,
+                    0,
+                    0,
+                    &Cumulatives::default(),
+                    upper,
+                    max_liquidity,
+                );
+                assert!(flipped);
+                flip_tick(&env, tick, tick_spacing);
+            }
+
+            // Burn everything: both ticks flip back out of the bitmap
+            for (tick, upper) in [(tick_lower, false), (tick_upper, true)] {
+                let flipped = update(
+                    &env,
+                    tick,
+                    tick_current,
+                    -1000,
+                    0,
+                    0,
+                    &Cumulatives::default(),
+                    upper,
+                    max_liquidity,
+                );
+                assert!(flipped);
+                flip_tick(&env, tick, tick_spacing);
+            }
+
+            for tick in [tick_lower, tick_upper] {
+                let info = get_tick(&env, tick);
+                assert!(!info.initialized);
+                assert_eq!(info.liquidity_gross, 0);
+                assert!(!crate::storage::has_tick(&env, tick));
+
+                let (word_pos, bit_pos) = tick_to_bitmap_position(tick, tick_spacing);
+                let bit_set = get_tick_bitmap_word(&env, word_pos) & (1u128 << bit_pos) != 0;
+                assert!(crate::invariants::tick_bitmap_consistent(info.initialized, bit_set));
+            }
+        });
+    }
 }
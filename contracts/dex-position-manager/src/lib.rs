@@ -1,7 +1,11 @@
 #![no_std]
 
 use dex_types::PositionData;
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, IntoVal, Symbol, Vec};
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Bytes,
+    Env, Executable, IntoVal, Map, String, Symbol, Vec,
+};
 
 #[contract]
 pub struct DexPositionManager;
@@ -28,6 +32,28 @@ pub enum DataKey {
     Approval(u32),
     /// Owner -> operator -> approved for all
     ApprovalForAll(Address, Address),
+    /// Number of live (minted and not burned) positions, the length of the global list
+    ActiveSupply,
+    /// Global index -> position ID
+    AllPositionAt(u32),
+    /// Position ID -> index in the global list
+    AllPositionIndex(u32),
+    /// Pool -> position count
+    PoolPositionCount(Address),
+    /// Pool -> index -> position ID
+    PoolPositionAt(Address, u32),
+    /// Position ID -> index in its pool's list
+    PoolPositionIndex(u32),
+}
+
+/// Position manager error codes. Codes start at 300, clear of the pool's and
+/// the router's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ManagerError {
+    /// Recipient is the manager, the factory, a pair token or the pool
+    InvalidRecipient = 300,
 }
 
 // ============================================================================
@@ -43,8 +69,26 @@ pub enum DataKey {
 // - Each position ID stored separately (~8 bytes each)
 // - Removal uses swap-and-pop for O(1) operations
 // - Pagination provided for querying positions
+// - Multicall batches are capped at MAX_MULTICALL_CALLS
 // ============================================================================
 
+/// Maximum calls in one multicall batch, each may touch a pool and two tokens
+pub const MAX_MULTICALL_CALLS: u32 = 10;
+
+/// Value a contract recipient's `on_position_received` must return to accept
+/// a `safe_transfer_from`
+pub const POSITION_RECEIVED: &str = "position_received";
+
+/// Furthest ahead a deadline may be set, in seconds (7 days)
+/// Anything later is almost certainly a mistake that would disable the check
+pub const MAX_DEADLINE_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+/// Collect cap meaning "everything the position is owed"
+pub const COLLECT_ALL: u128 = u128::MAX;
+
+/// Basis points in a whole position, the most `decrease_liquidity_bps` removes
+pub const MAX_BPS: u32 = 10_000;
+
 /// Mint parameters
 #[contracttype]
 #[derive(Clone)]
@@ -58,7 +102,32 @@ pub struct MintParams {
     pub amount1_desired: i128,
     pub amount0_min: i128,
     pub amount1_min: i128,
+    /// Account the tokens are pulled from, which must authorize the mint
+    pub payer: Address,
+    /// Owner of the new position, who need not authorize the mint
+    pub recipient: Address,
+    /// Latest ledger timestamp to execute at, 0 for no deadline
+    pub deadline: u64,
+    /// Initial price to create the pool with if it does not exist yet,
+    /// None to require an existing pool
+    pub sqrt_price_x96: Option<u128>,
+}
+
+/// Create pool and mint parameters
+#[contracttype]
+#[derive(Clone)]
+pub struct CreatePoolAndMintParams {
+    pub token0: Address,
+    pub token1: Address,
+    pub fee: u32,
+    /// Initial price, only used when the pool does not exist yet
+    pub sqrt_price_x96: u128,
+    pub amount0_desired: i128,
+    pub amount1_desired: i128,
+    pub amount0_min: i128,
+    pub amount1_min: i128,
     pub recipient: Address,
+    /// Latest ledger timestamp to execute at, 0 for no deadline
     pub deadline: u64,
 }
 
@@ -67,10 +136,13 @@ pub struct MintParams {
 #[derive(Clone)]
 pub struct IncreaseLiquidityParams {
     pub position_id: u32,
+    /// Account the tokens are pulled from, which must authorize the call
+    pub payer: Address,
     pub amount0_desired: i128,
     pub amount1_desired: i128,
     pub amount0_min: i128,
     pub amount1_min: i128,
+    /// Latest ledger timestamp to execute at, 0 for no deadline
     pub deadline: u64,
 }
 
@@ -82,6 +154,7 @@ pub struct DecreaseLiquidityParams {
     pub liquidity: u128,
     pub amount0_min: i128,
     pub amount1_min: i128,
+    /// Latest ledger timestamp to execute at, 0 for no deadline
     pub deadline: u64,
 }
 
@@ -91,8 +164,62 @@ pub struct DecreaseLiquidityParams {
 pub struct CollectParams {
     pub position_id: u32,
     pub recipient: Address,
+    /// Most token0 to collect: COLLECT_ALL for everything owed, 0 for none
     pub amount0_max: u128,
+    /// Most token1 to collect: COLLECT_ALL for everything owed, 0 for none
     pub amount1_max: u128,
+    /// Latest ledger timestamp to execute at, 0 for no deadline
+    pub deadline: u64,
+}
+
+/// Display details of a position for wallets
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionMetadata {
+    /// Short label such as "LP USDC/XLM 0.3% [-600,600]"
+    pub name: String,
+    pub pool: Address,
+    pub token0: Address,
+    pub token1: Address,
+    pub symbol0: String,
+    pub symbol1: String,
+    /// Fee tier in hundredths of bps
+    pub fee: u32,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    /// Whether the pool's current tick is inside [tick_lower, tick_upper)
+    pub in_range: bool,
+    pub liquidity: u128,
+}
+
+/// One operation in a multicall batch
+#[contracttype]
+#[derive(Clone)]
+pub enum ManagerCall {
+    /// Caller and increase parameters
+    IncreaseLiquidity(Address, IncreaseLiquidityParams),
+    /// Caller and decrease parameters
+    DecreaseLiquidity(Address, DecreaseLiquidityParams),
+    /// Caller and collect parameters
+    Collect(Address, CollectParams),
+    /// Position ID to burn
+    Burn(u32),
+    /// Spender, from, to and position ID
+    TransferFrom(Address, Address, Address, u32),
+}
+
+/// Result of one multicall operation, in the same order as the calls
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ManagerResult {
+    /// (liquidity, amount0, amount1)
+    IncreaseLiquidity(u128, i128, i128),
+    /// (amount0, amount1)
+    DecreaseLiquidity(i128, i128),
+    /// (amount0, amount1)
+    Collect(u128, u128),
+    Burn,
+    TransferFrom,
 }
 
 #[contractimpl]
@@ -107,267 +234,468 @@ impl DexPositionManager {
     }
 
     /// Create new position and mint NFT
+    /// The pool pulls the consumed amounts straight from the payer, so the
+    /// unused part of the desired amounts never leaves their account
+    /// The position NFT goes to the recipient, who may differ from the payer
     /// Returns: (position_id, liquidity, amount0, amount1)
     pub fn mint(env: Env, params: MintParams) -> (u32, u128, i128, i128) {
-        params.recipient.require_auth();
+        params.payer.require_auth();
         check_deadline(&env, params.deadline);
 
         let factory = get_factory(&env);
 
-        // Get or create pool
-        let pool = get_pool(&env, &factory, &params.token0, &params.token1, params.fee);
+        // Get the pool, creating it first when an initial price is given
+        let pool = match params.sqrt_price_x96 {
+            Some(sqrt_price_x96) => get_or_create_pool(
+                &env,
+                &factory,
+                &params.payer,
+                &params.token0,
+                &params.token1,
+                params.fee,
+                sqrt_price_x96,
+            ),
+            None => get_pool(&env, &factory, &params.token0, &params.token1, params.fee),
+        };
 
-        // Calculate liquidity from desired amounts
-        let pool_state = get_pool_state(&env, &pool);
-        let sqrt_ratio_lower = dex_math::get_sqrt_ratio_at_tick(&env, params.tick_lower);
-        let sqrt_ratio_upper = dex_math::get_sqrt_ratio_at_tick(&env, params.tick_upper);
+        mint_position(&env, &pool, &params)
+    }
 
-        let liquidity = dex_math::get_liquidity_for_amounts(
-            &env,
-            pool_state.sqrt_price_x96,
-            sqrt_ratio_lower,
-            sqrt_ratio_upper,
-            params.amount0_desired as u128,
-            params.amount1_desired as u128,
+    /// Preview `mint` without executing it: the liquidity the desired amounts buy
+    /// at the pool's current price and the amounts the pool would charge for it
+    /// A missing pool is previewed at `params.sqrt_price_x96` when one is given
+    /// Returns: (liquidity, amount0, amount1)
+    pub fn preview_mint(env: Env, params: MintParams) -> (u128, i128, i128) {
+        let factory = get_factory(&env);
+        let existing: Option<Address> = env.invoke_contract(
+            &factory,
+            &Symbol::new(&env, "get_pool"),
+            (&params.token0, &params.token1, params.fee).into_val(&env),
         );
+        let sqrt_price_x96 = match (existing, params.sqrt_price_x96) {
+            (Some(pool), _) => get_pool_state(&env, &pool).sqrt_price_x96,
+            (None, Some(sqrt_price_x96)) => sqrt_price_x96,
+            (None, None) => panic!("Pool not found"),
+        };
 
-        // Mint liquidity in pool
-        let (amount0, amount1) = invoke_pool_mint(
+        preview_liquidity(
             &env,
-            &pool,
-            &env.current_contract_address(),
+            sqrt_price_x96,
             params.tick_lower,
             params.tick_upper,
-            liquidity,
-        );
+            params.amount0_desired,
+            params.amount1_desired,
+        )
+    }
 
-        // Check minimums
-        if (amount0 as i128) < params.amount0_min || (amount1 as i128) < params.amount1_min {
-            panic!("Slippage check failed");
-        }
+    /// Preview `increase_liquidity` without executing it
+    /// Returns: (liquidity, amount0, amount1)
+    pub fn preview_increase(env: Env, params: IncreaseLiquidityParams) -> (u128, i128, i128) {
+        let position = get_position(&env, params.position_id);
+        let pool_state = get_pool_state(&env, &position.pool);
 
-        // Create NFT position
-        let position_id = get_next_position_id(&env);
+        preview_liquidity(
+            &env,
+            pool_state.sqrt_price_x96,
+            position.tick_lower,
+            position.tick_upper,
+            params.amount0_desired,
+            params.amount1_desired,
+        )
+    }
 
-        let position_data = PositionData {
-            pool: pool.clone(),
-            tick_lower: params.tick_lower,
-            tick_upper: params.tick_upper,
-            liquidity,
-            fee_growth_inside_0_last_x128: 0,
-            fee_growth_inside_1_last_x128: 0,
-            tokens_owed_0: 0,
-            tokens_owed_1: 0,
-        };
+    /// Return the pool for the pair and fee, creating it at `sqrt_price_x96` if missing
+    /// An existing pool is returned as-is and `sqrt_price_x96` is ignored
+    pub fn create_and_init_pool_if_needed(
+        env: Env,
+        token0: Address,
+        token1: Address,
+        fee: u32,
+        sqrt_price_x96: u128,
+    ) -> Address {
+        let factory = get_factory(&env);
+        get_or_create_pool(
+            &env,
+            &factory,
+            &env.current_contract_address(),
+            &token0,
+            &token1,
+            fee,
+            sqrt_price_x96,
+        )
+    }
 
-        // Store position
-        env.storage()
-            .persistent()
-            .set(&DataKey::Position(position_id), &position_data);
-        env.storage()
-            .persistent()
-            .set(&DataKey::PositionOwner(position_id), &params.recipient);
+    /// Create a pool if missing and mint a full-range position in one call
+    /// An existing pool is reused as-is and `sqrt_price_x96` is ignored
+    /// Returns: (pool, position_id, liquidity, amount0, amount1)
+    pub fn create_pool_and_mint(
+        env: Env,
+        params: CreatePoolAndMintParams,
+    ) -> (Address, u32, u128, i128, i128) {
+        params.recipient.require_auth();
+        check_deadline(&env, params.deadline);
+
+        let factory = get_factory(&env);
 
-        // Add to owner's positions
-        add_position_to_owner(&env, &params.recipient, position_id);
+        let pool = get_or_create_pool(
+            &env,
+            &factory,
+            &params.recipient,
+            &params.token0,
+            &params.token1,
+            params.fee,
+            params.sqrt_price_x96,
+        );
 
-        // Emit event
-        env.events().publish(
-            (Symbol::new(&env, "position_minted"),),
-            (position_id, params.recipient.clone(), liquidity),
+        // Widest range aligned to the pool's tick spacing
+        let tick_spacing: i32 = env.invoke_contract(
+            &factory,
+            &Symbol::new(&env, "get_fee_tick_spacing"),
+            (params.fee,).into_val(&env),
         );
+        let tick_lower = (dex_types::MIN_TICK / tick_spacing) * tick_spacing;
+        let tick_upper = (dex_types::MAX_TICK / tick_spacing) * tick_spacing;
+
+        let mint_params = MintParams {
+            token0: params.token0,
+            token1: params.token1,
+            fee: params.fee,
+            tick_lower,
+            tick_upper,
+            amount0_desired: params.amount0_desired,
+            amount1_desired: params.amount1_desired,
+            amount0_min: params.amount0_min,
+            amount1_min: params.amount1_min,
+            payer: params.recipient.clone(),
+            recipient: params.recipient,
+            deadline: params.deadline,
+            sqrt_price_x96: None,
+        };
+
+        let (position_id, liquidity, amount0, amount1) = mint_position(&env, &pool, &mint_params);
 
-        (position_id, liquidity, amount0 as i128, amount1 as i128)
+        (pool, position_id, liquidity, amount0, amount1)
     }
 
     /// Add liquidity to existing position
+    /// `caller` must be the owner, the position's approved address, or an operator of the owner
+    /// Like `mint`, only the consumed amounts are pulled, from `params.payer`
     pub fn increase_liquidity(
         env: Env,
+        caller: Address,
         params: IncreaseLiquidityParams,
     ) -> (u128, i128, i128) {
-        check_deadline(&env, params.deadline);
-
-        let owner = get_position_owner(&env, params.position_id);
-        owner.require_auth();
+        caller.require_auth();
+        if params.payer != caller {
+            params.payer.require_auth();
+        }
+        increase_position(&env, &caller, &params)
+    }
 
-        let mut position = get_position(&env, params.position_id);
+    /// Remove liquidity from position
+    /// `caller` must be the owner, the position's approved address, or an operator of the owner
+    /// The released tokens are credited to the position's tokens owed, never paid to `caller`
+    pub fn decrease_liquidity(
+        env: Env,
+        caller: Address,
+        params: DecreaseLiquidityParams,
+    ) -> (i128, i128) {
+        caller.require_auth();
+        decrease_position(&env, &caller, &params)
+    }
 
-        // Calculate additional liquidity
-        let pool_state = get_pool_state(&env, &position.pool);
-        let sqrt_ratio_lower = dex_math::get_sqrt_ratio_at_tick(&env, position.tick_lower);
-        let sqrt_ratio_upper = dex_math::get_sqrt_ratio_at_tick(&env, position.tick_upper);
+    /// Remove `bps` basis points of a position's liquidity, read at execution time
+    /// `caller` must be the owner, the position's approved address, or an operator of the owner
+    /// Returns: (amount0, amount1) credited to tokens owed
+    pub fn decrease_liquidity_bps(
+        env: Env,
+        caller: Address,
+        position_id: u32,
+        bps: u32,
+        amount0_min: i128,
+        amount1_min: i128,
+        deadline: u64,
+    ) -> (i128, i128) {
+        if bps == 0 || bps > MAX_BPS {
+            panic!("Invalid bps");
+        }
 
-        let liquidity = dex_math::get_liquidity_for_amounts(
-            &env,
-            pool_state.sqrt_price_x96,
-            sqrt_ratio_lower,
-            sqrt_ratio_upper,
-            params.amount0_desired as u128,
-            params.amount1_desired as u128,
-        );
+        let position = get_position(&env, position_id);
+        let liquidity = dex_math::mul_div(&env, position.liquidity, bps as u128, MAX_BPS as u128);
+        if liquidity == 0 {
+            panic!("Zero liquidity");
+        }
 
-        // Mint in pool
-        let (amount0, amount1) = invoke_pool_mint(
-            &env,
-            &position.pool,
-            &env.current_contract_address(),
-            position.tick_lower,
-            position.tick_upper,
-            liquidity,
-        );
+        Self::decrease_liquidity(
+            env,
+            caller,
+            DecreaseLiquidityParams {
+                position_id,
+                liquidity,
+                amount0_min,
+                amount1_min,
+                deadline,
+            },
+        )
+    }
 
-        // Check minimums
-        if (amount0 as i128) < params.amount0_min || (amount1 as i128) < params.amount1_min {
-            panic!("Slippage check failed");
-        }
+    /// Collect fees and tokens from position
+    /// `caller` must be the owner, the position's approved address, or an operator of the owner
+    pub fn collect(env: Env, caller: Address, params: CollectParams) -> (u128, u128) {
+        caller.require_auth();
+        collect_position(&env, &caller, &params)
+    }
 
-        // Update position
-        position.liquidity += liquidity;
+    /// Credit fees earned since the last checkpoint to the position's tokens owed
+    /// Moves no tokens, so anyone may call it
+    /// Returns: (tokens_owed_0, tokens_owed_1)
+    pub fn poke(env: Env, position_id: u32) -> (u128, u128) {
+        let mut position = get_position(&env, position_id);
+        poke_position(&env, &mut position);
         env.storage()
             .persistent()
-            .set(&DataKey::Position(params.position_id), &position);
+            .set(&DataKey::Position(position_id), &position);
 
-        (liquidity, amount0 as i128, amount1 as i128)
+        (position.tokens_owed_0, position.tokens_owed_1)
     }
 
-    /// Remove liquidity from position
-    pub fn decrease_liquidity(env: Env, params: DecreaseLiquidityParams) -> (i128, i128) {
-        check_deadline(&env, params.deadline);
+    /// Close a position in one call: remove all liquidity, send everything owed
+    /// (principal and fees) to `recipient` and burn the NFT
+    /// The minimums apply to the principal released by removing the liquidity
+    /// Returns: (amount0, amount1) paid to `recipient`
+    pub fn exit_position(
+        env: Env,
+        position_id: u32,
+        amount0_min: i128,
+        amount1_min: i128,
+        recipient: Address,
+        deadline: u64,
+    ) -> (i128, i128) {
+        let owner = get_position_owner(&env, position_id);
+        owner.require_auth();
+
+        close_position(
+            &env,
+            &owner,
+            position_id,
+            amount0_min,
+            amount1_min,
+            recipient,
+            deadline,
+        )
+    }
 
-        let owner = get_position_owner(&env, params.position_id);
+    /// Move a position to a new range on the same pool in one call
+    /// Removes all liquidity, collects principal and fees to the owner, mints a new
+    /// position from those tokens and burns the old NFT; whatever the new range does
+    /// not consume stays with the owner
+    /// The minimums apply to the amounts deposited into the new range
+    /// Returns: (new_position_id, liquidity)
+    pub fn rebalance(
+        env: Env,
+        position_id: u32,
+        new_tick_lower: i32,
+        new_tick_upper: i32,
+        amount0_min: i128,
+        amount1_min: i128,
+        deadline: u64,
+    ) -> (u32, u128) {
+        let owner = get_position_owner(&env, position_id);
         owner.require_auth();
+        check_deadline(&env, deadline);
 
-        let mut position = get_position(&env, params.position_id);
+        // Check the new range before the old one is exited
+        let pool = get_position(&env, position_id).pool;
+        let config = get_pool_config(&env, &pool);
+        check_tick_range(new_tick_lower, new_tick_upper, config.tick_spacing);
 
-        if params.liquidity > position.liquidity {
-            panic!("Insufficient liquidity");
-        }
+        let (amount0, amount1) =
+            close_position(&env, &owner, position_id, 0, 0, owner.clone(), deadline);
 
-        // Burn in pool
-        let (amount0, amount1) = invoke_pool_burn(
-            &env,
-            &position.pool,
-            position.tick_lower,
-            position.tick_upper,
-            params.liquidity,
+        let params = MintParams {
+            token0: config.token0,
+            token1: config.token1,
+            fee: config.fee,
+            tick_lower: new_tick_lower,
+            tick_upper: new_tick_upper,
+            amount0_desired: amount0,
+            amount1_desired: amount1,
+            amount0_min,
+            amount1_min,
+            payer: owner.clone(),
+            recipient: owner,
+            deadline,
+            sqrt_price_x96: None,
+        };
+        let (new_position_id, liquidity, _, _) = mint_position(&env, &pool, &params);
+
+        env.events().publish(
+            (Symbol::new(&env, "position_rebalanced"),),
+            (position_id, new_position_id, liquidity),
         );
 
-        // Check minimums
-        if (amount0 as i128) < params.amount0_min || (amount1 as i128) < params.amount1_min {
-            panic!("Slippage check failed");
-        }
+        (new_position_id, liquidity)
+    }
 
-        // Update position
-        position.liquidity -= params.liquidity;
-        position.tokens_owed_0 += amount0;
-        position.tokens_owed_1 += amount1;
-        env.storage()
-            .persistent()
-            .set(&DataKey::Position(params.position_id), &position);
+    /// Reinvest what the position is owed into more liquidity on the same range
+    /// Fees are settled first; the share that matches the current price is
+    /// collected to the manager and minted back, and the rest stays owed
+    /// Owed includes principal from earlier decreases that was not yet collected
+    /// Fails when that share would add less than MIN_LIQUIDITY
+    /// Returns: (liquidity added, amount0, amount1)
+    pub fn compound(
+        env: Env,
+        position_id: u32,
+        amount0_min: i128,
+        amount1_min: i128,
+        deadline: u64,
+    ) -> (u128, i128, i128) {
+        let owner = get_position_owner(&env, position_id);
+        owner.require_auth();
+        check_deadline(&env, deadline);
 
-        (amount0 as i128, amount1 as i128)
-    }
+        let mut position = get_position(&env, position_id);
+        poke_position(&env, &mut position);
 
-    /// Collect fees and tokens from position
-    pub fn collect(env: Env, params: CollectParams) -> (u128, u128) {
-        let owner = get_position_owner(&env, params.position_id);
-        // Allow owner or approved
-        if !is_approved_or_owner(&env, &owner, params.position_id) {
-            panic!("Not authorized");
+        let pool_state = get_pool_state(&env, &position.pool);
+        let (liquidity, amount0, amount1) = preview_liquidity(
+            &env,
+            pool_state.sqrt_price_x96,
+            position.tick_lower,
+            position.tick_upper,
+            position.tokens_owed_0 as i128,
+            position.tokens_owed_1 as i128,
+        );
+        if liquidity == 0 {
+            panic!("Nothing to compound");
+        }
+        // The pool rejects smaller mints, so fail before collecting anything
+        if liquidity < dex_types::MIN_LIQUIDITY {
+            panic!("Liquidity too small to compound");
         }
 
-        let mut position = get_position(&env, params.position_id);
-
-        // Collect from pool
+        // Take the matched amounts out of the pool and pay them straight back in
+        let manager = env.current_contract_address();
         let (collected0, collected1) = invoke_pool_collect(
             &env,
             &position.pool,
-            &params.recipient,
+            &manager,
+            &manager,
             position.tick_lower,
             position.tick_upper,
-            params.amount0_max,
-            params.amount1_max,
+            amount0 as u128,
+            amount1 as u128,
         );
+        authorize_pool_payment(&env, &position.pool, collected0 as i128, collected1 as i128);
+        let (amount0, amount1) = invoke_pool_mint(
+            &env,
+            &position.pool,
+            &manager,
+            &manager,
+            position.tick_lower,
+            position.tick_upper,
+            liquidity,
+        );
+        // The manager keeps nothing, so the mint must spend exactly what was collected
+        if amount0 != collected0 || amount1 != collected1 {
+            panic!("Compound amounts mismatch");
+        }
+        if (amount0 as i128) < amount0_min || (amount1 as i128) < amount1_min {
+            panic!("Slippage check failed");
+        }
 
-        // Update tokens owed
-        position.tokens_owed_0 = position.tokens_owed_0.saturating_sub(collected0);
-        position.tokens_owed_1 = position.tokens_owed_1.saturating_sub(collected1);
+        position.liquidity += liquidity;
+        position.tokens_owed_0 -= collected0;
+        position.tokens_owed_1 -= collected1;
         env.storage()
             .persistent()
-            .set(&DataKey::Position(params.position_id), &position);
+            .set(&DataKey::Position(position_id), &position);
+
+        let (amount0, amount1) = (amount0 as i128, amount1 as i128);
+        env.events().publish(
+            (Symbol::new(&env, "position_compounded"),),
+            (position_id, liquidity, amount0, amount1),
+        );
 
-        (collected0, collected1)
+        (liquidity, amount0, amount1)
     }
 
     /// Burn position NFT (requires zero liquidity)
     pub fn burn(env: Env, position_id: u32) {
         let owner = get_position_owner(&env, position_id);
         owner.require_auth();
+        burn_position(&env, &owner, position_id);
+    }
 
-        let position = get_position(&env, position_id);
+    /// Burn a position NFT after sweeping whatever it is still owed to the owner
+    /// Use when dust left by capped collects would otherwise block `burn` forever;
+    /// anything the pool fails to pay out is forfeited and reported in an
+    /// `owed_forfeited` event (requires zero liquidity)
+    /// Returns: (amount0, amount1) swept to the owner
+    pub fn burn_with_sweep(env: Env, position_id: u32) -> (u128, u128) {
+        let owner = get_position_owner(&env, position_id);
+        owner.require_auth();
 
-        if position.liquidity != 0 {
+        if get_position(&env, position_id).liquidity != 0 {
             panic!("Position has liquidity");
         }
 
-        if position.tokens_owed_0 != 0 || position.tokens_owed_1 != 0 {
-            panic!("Position has uncollected tokens");
-        }
+        let swept = collect_position(
+            &env,
+            &owner,
+            &CollectParams {
+                position_id,
+                recipient: owner.clone(),
+                amount0_max: COLLECT_ALL,
+                amount1_max: COLLECT_ALL,
+                deadline: 0,
+            },
+        );
 
-        // Remove position
-        env.storage()
-            .persistent()
-            .remove(&DataKey::Position(position_id));
-        env.storage()
-            .persistent()
-            .remove(&DataKey::PositionOwner(position_id));
-        env.storage()
-            .persistent()
-            .remove(&DataKey::Approval(position_id));
+        // Forfeit only what the pool failed to pay, and say so
+        let mut position = get_position(&env, position_id);
+        let forfeited = (position.tokens_owed_0, position.tokens_owed_1);
+        if forfeited != (0, 0) {
+            position.tokens_owed_0 = 0;
+            position.tokens_owed_1 = 0;
+            env.storage()
+                .persistent()
+                .set(&DataKey::Position(position_id), &position);
+            env.events().publish(
+                (Symbol::new(&env, "owed_forfeited"),),
+                (position_id, forfeited.0, forfeited.1),
+            );
+        }
 
-        // Remove from owner's list
-        remove_position_from_owner(&env, &owner, position_id);
+        burn_position(&env, &owner, position_id);
 
-        env.events().publish(
-            (Symbol::new(&env, "position_burned"),),
-            (position_id,),
-        );
+        swept
     }
 
     // === NFT-like Ownership Functions ===
 
     /// Transfer position ownership
-    pub fn transfer_from(env: Env, from: Address, to: Address, position_id: u32) {
-        let owner = get_position_owner(&env, position_id);
-
-        if owner != from {
-            panic!("Not owner");
-        }
-
-        if !is_approved_or_owner(&env, &from, position_id) {
-            panic!("Not authorized");
-        }
-
-        from.require_auth();
-
-        // Update owner
-        env.storage()
-            .persistent()
-            .set(&DataKey::PositionOwner(position_id), &to);
-
-        // Clear approval
-        env.storage()
-            .persistent()
-            .remove(&DataKey::Approval(position_id));
-
-        // Update owner lists
-        remove_position_from_owner(&env, &from, position_id);
-        add_position_to_owner(&env, &to, position_id);
+    /// `spender` must be the owner, the position's approved address, or an operator of the owner
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, position_id: u32) {
+        spender.require_auth();
+        transfer_position(&env, &spender, &from, &to, position_id);
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "transfer"),),
-            (from, to, position_id),
-        );
+    /// Transfer position ownership, checking that a contract recipient can hold it
+    /// If `to` is a contract, it must implement
+    /// `on_position_received(operator, from, position_id, data) -> Symbol` and return
+    /// `POSITION_RECEIVED`, otherwise the transfer reverts
+    pub fn safe_transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        position_id: u32,
+        data: Bytes,
+    ) {
+        spender.require_auth();
+        transfer_position(&env, &spender, &from, &to, position_id);
+        check_on_position_received(&env, &spender, &from, &to, position_id, data);
     }
 
     /// Approve address to manage position
@@ -379,18 +707,15 @@ impl DexPositionManager {
             .persistent()
             .set(&DataKey::Approval(position_id), &to);
 
-        env.events().publish(
-            (Symbol::new(&env, "approval"),),
-            (owner, to, position_id),
-        );
+        env.events()
+            .publish((Symbol::new(&env, "approval"), owner), (to, position_id));
     }
 
-    /// Set operator approval for all positions
-    pub fn set_approval_for_all(env: Env, operator: Address, approved: bool) {
-        let caller = env.current_contract_address(); // TODO: get actual caller
-        caller.require_auth();
+    /// Set operator approval for all of owner's positions
+    pub fn set_approval_for_all(env: Env, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
 
-        let key = DataKey::ApprovalForAll(caller.clone(), operator.clone());
+        let key = DataKey::ApprovalForAll(owner.clone(), operator.clone());
         if approved {
             env.storage().persistent().set(&key, &true);
         } else {
@@ -398,11 +723,56 @@ impl DexPositionManager {
         }
 
         env.events().publish(
-            (Symbol::new(&env, "approval_for_all"),),
-            (caller, operator, approved),
+            (Symbol::new(&env, "approval_for_all"), owner),
+            (operator, approved),
         );
     }
 
+    /// Run up to MAX_MULTICALL_CALLS operations in order
+    /// Each distinct caller, payer or burning owner authorizes the batch once,
+    /// and any failure reverts the whole batch
+    pub fn multicall(env: Env, calls: Vec<ManagerCall>) -> Vec<ManagerResult> {
+        if calls.is_empty() || calls.len() > MAX_MULTICALL_CALLS {
+            panic!("Invalid batch size");
+        }
+
+        let mut authorized = Vec::new(&env);
+        let mut results = Vec::new(&env);
+        for call in calls.iter() {
+            let result = match call {
+                ManagerCall::IncreaseLiquidity(caller, params) => {
+                    require_auth_once(&mut authorized, &caller);
+                    require_auth_once(&mut authorized, &params.payer);
+                    let (liquidity, amount0, amount1) = increase_position(&env, &caller, &params);
+                    ManagerResult::IncreaseLiquidity(liquidity, amount0, amount1)
+                }
+                ManagerCall::DecreaseLiquidity(caller, params) => {
+                    require_auth_once(&mut authorized, &caller);
+                    let (amount0, amount1) = decrease_position(&env, &caller, &params);
+                    ManagerResult::DecreaseLiquidity(amount0, amount1)
+                }
+                ManagerCall::Collect(caller, params) => {
+                    require_auth_once(&mut authorized, &caller);
+                    let (amount0, amount1) = collect_position(&env, &caller, &params);
+                    ManagerResult::Collect(amount0, amount1)
+                }
+                ManagerCall::Burn(position_id) => {
+                    let owner = get_position_owner(&env, position_id);
+                    require_auth_once(&mut authorized, &owner);
+                    burn_position(&env, &owner, position_id);
+                    ManagerResult::Burn
+                }
+                ManagerCall::TransferFrom(spender, from, to, position_id) => {
+                    require_auth_once(&mut authorized, &spender);
+                    transfer_position(&env, &spender, &from, &to, position_id);
+                    ManagerResult::TransferFrom
+                }
+            };
+            results.push_back(result);
+        }
+        results
+    }
+
     // === View Functions ===
 
     /// Get position details
@@ -410,27 +780,100 @@ impl DexPositionManager {
         get_position(&env, position_id)
     }
 
-    /// Get position count for owner
-    pub fn balance_of(env: Env, owner: Address) -> u32 {
-        env.storage()
-            .persistent()
-            .get(&DataKey::OwnerPositionCount(owner))
-            .unwrap_or(0)
+    /// Position data, None if it was never minted or has been burned
+    pub fn find_position(env: Env, position_id: u32) -> Option<PositionData> {
+        read_position(&env, position_id)
     }
 
-    /// Get position ID at index for owner
-    pub fn position_of_owner_by_index(env: Env, owner: Address, index: u32) -> Option<u32> {
-        env.storage()
-            .persistent()
-            .get(&DataKey::OwnerPositionAt(owner, index))
+    /// Describe a position for display, reading the pool and both token symbols
+    pub fn token_metadata(env: Env, position_id: u32) -> PositionMetadata {
+        let position = get_position(&env, position_id);
+        let config = get_pool_config(&env, &position.pool);
+        let tick = get_pool_state(&env, &position.pool).tick;
+
+        let symbol0 = token::Client::new(&env, &config.token0).symbol();
+        let symbol1 = token::Client::new(&env, &config.token1).symbol();
+
+        let mut name = NameBuf::new();
+        name.push(b"LP ");
+        name.push_string(&symbol0);
+        name.push(b"/");
+        name.push_string(&symbol1);
+        name.push(b" ");
+        name.push_fee(config.fee);
+        name.push(b" [");
+        name.push_i32(position.tick_lower);
+        name.push(b",");
+        name.push_i32(position.tick_upper);
+        name.push(b"]");
+
+        PositionMetadata {
+            name: name.to_string(&env),
+            pool: position.pool,
+            token0: config.token0,
+            token1: config.token1,
+            symbol0,
+            symbol1,
+            fee: config.fee,
+            tick_lower: position.tick_lower,
+            tick_upper: position.tick_upper,
+            in_range: position.tick_lower <= tick && tick < position.tick_upper,
+            liquidity: position.liquidity,
+        }
     }
 
-    /// Get positions for owner with pagination
-    /// Returns up to `limit` position IDs starting from `start_index`
-    /// Maximum limit is 50 to stay within Soroban's read entry limits
-    pub fn positions_of_paginated(env: Env, owner: Address, start_index: u32, limit: u32) -> Vec<u32> {
-        // Cap limit to prevent exceeding read entry limits
-        let safe_limit = if limit > 50 { 50 } else { limit };
+    /// Preview fees and tokens owed to a position if it were collected now
+    /// Returns (amount0, amount1)
+    pub fn uncollected_fees(env: Env, position_id: u32) -> (u128, u128) {
+        let position = get_position(&env, position_id);
+        let (fee_growth_inside_0, fee_growth_inside_1) = get_pool_fee_growth_inside(
+            &env,
+            &position.pool,
+            position.tick_lower,
+            position.tick_upper,
+        );
+        compute_uncollected_fees(&env, &position, fee_growth_inside_0, fee_growth_inside_1)
+    }
+
+    /// Current value of a position: the tokens its liquidity would release at the
+    /// pool's current price, plus uncollected fees and tokens owed
+    /// Returns (amount0, amount1)
+    pub fn get_position_amounts(env: Env, position_id: u32) -> (i128, i128) {
+        let position = get_position(&env, position_id);
+        let pool_state = get_pool_state(&env, &position.pool);
+
+        let (principal0, principal1) = dex_math::get_amounts_for_liquidity(
+            &env,
+            pool_state.sqrt_price_x96,
+            dex_math::get_sqrt_ratio_at_tick(&env, position.tick_lower),
+            dex_math::get_sqrt_ratio_at_tick(&env, position.tick_upper),
+            position.liquidity,
+        );
+
+        let (fee_growth_inside_0, fee_growth_inside_1) = get_pool_fee_growth_inside(
+            &env,
+            &position.pool,
+            position.tick_lower,
+            position.tick_upper,
+        );
+        let (owed0, owed1) =
+            compute_uncollected_fees(&env, &position, fee_growth_inside_0, fee_growth_inside_1);
+
+        ((principal0 + owed0) as i128, (principal1 + owed1) as i128)
+    }
+
+    /// Aggregate uncollected fees and tokens owed across an owner's positions
+    /// Covers up to `limit` positions starting from `start_index`
+    /// Maximum limit is 20 since each position may need a cross-contract pool read
+    /// Returns (total0, total1, positions_counted)
+    pub fn uncollected_fees_of(
+        env: Env,
+        owner: Address,
+        start_index: u32,
+        limit: u32,
+    ) -> (u128, u128, u32) {
+        // Cap limit to keep pool calls within resource limits
+        let safe_limit = if limit > 20 { 20 } else { limit };
 
         let count: u32 = env
             .storage()
@@ -438,21 +881,132 @@ impl DexPositionManager {
             .get(&DataKey::OwnerPositionCount(owner.clone()))
             .unwrap_or(0);
 
-        let mut positions: Vec<u32> = Vec::new(&env);
+        let end_index = start_index.saturating_add(safe_limit).min(count);
 
-        let end_index = if start_index + safe_limit > count {
-            count
-        } else {
-            start_index + safe_limit
-        };
+        // Positions sharing a pool and range see the same fee growth, so read it once
+        let mut fee_growth_cache: Map<(Address, i32, i32), (u128, u128)> = Map::new(&env);
+
+        let mut total0: u128 = 0;
+        let mut total1: u128 = 0;
+        let mut positions_counted: u32 = 0;
 
         for i in start_index..end_index {
-            if let Some(pos_id) = env
+            let position_id: u32 = match env
                 .storage()
                 .persistent()
                 .get(&DataKey::OwnerPositionAt(owner.clone(), i))
             {
-                positions.push_back(pos_id);
+                Some(id) => id,
+                None => continue,
+            };
+
+            let position = get_position(&env, position_id);
+            let range_key = (position.pool.clone(), position.tick_lower, position.tick_upper);
+
+            let (fee_growth_inside_0, fee_growth_inside_1) =
+                match fee_growth_cache.get(range_key.clone()) {
+                    Some(cached) => cached,
+                    None => {
+                        let fetched = get_pool_fee_growth_inside(
+                            &env,
+                            &position.pool,
+                            position.tick_lower,
+                            position.tick_upper,
+                        );
+                        fee_growth_cache.set(range_key, fetched);
+                        fetched
+                    }
+                };
+
+            let (owed0, owed1) =
+                compute_uncollected_fees(&env, &position, fee_growth_inside_0, fee_growth_inside_1);
+            total0 += owed0;
+            total1 += owed1;
+            positions_counted += 1;
+        }
+
+        (total0, total1, positions_counted)
+    }
+
+    /// Get position count for owner
+    pub fn balance_of(env: Env, owner: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OwnerPositionCount(owner))
+            .unwrap_or(0)
+    }
+
+    /// Get position ID at index for owner
+    pub fn position_of_owner_by_index(env: Env, owner: Address, index: u32) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OwnerPositionAt(owner, index))
+    }
+
+    /// Get positions for owner with pagination
+    /// Returns up to `limit` position IDs starting from `start_index`
+    /// Maximum limit is 50 to stay within Soroban's read entry limits
+    pub fn positions_of_paginated(env: Env, owner: Address, start_index: u32, limit: u32) -> Vec<u32> {
+        // Cap limit to prevent exceeding read entry limits
+        let safe_limit = if limit > 50 { 50 } else { limit };
+
+        PositionList::Owner(owner).page(&env, start_index, safe_limit)
+    }
+
+    /// Get positions minted on a pool with pagination
+    /// Returns up to `limit` position IDs starting from `start_index`
+    /// Maximum limit is 50 to stay within Soroban's read entry limits
+    pub fn positions_of_pool_paginated(
+        env: Env,
+        pool: Address,
+        start_index: u32,
+        limit: u32,
+    ) -> Vec<u32> {
+        let safe_limit = if limit > 50 { 50 } else { limit };
+
+        PositionList::Pool(pool).page(&env, start_index, safe_limit)
+    }
+
+    /// Get the number of live positions on a pool
+    pub fn pool_position_count(env: Env, pool: Address) -> u32 {
+        PositionList::Pool(pool).count(&env)
+    }
+
+    /// Get the live position at `index` in the global list, below `total_supply`
+    /// Burning moves the last position into the freed slot, so order is not stable
+    pub fn token_by_index(env: Env, index: u32) -> Option<u32> {
+        let list = PositionList::All;
+        if index >= list.count(&env) {
+            return None;
+        }
+        env.storage().persistent().get(&list.at_key(index))
+    }
+
+    /// Get every live position with pagination
+    /// Returns up to `limit` position IDs starting from `start_index`
+    /// Maximum limit is 50 to stay within Soroban's read entry limits
+    pub fn all_positions_paginated(env: Env, start_index: u32, limit: u32) -> Vec<u32> {
+        let safe_limit = if limit > 50 { 50 } else { limit };
+
+        PositionList::All.page(&env, start_index, safe_limit)
+    }
+
+    /// Get positions for owner with their data, paginated
+    /// Returns up to `limit` (id, position) pairs starting from `start_index`
+    /// Maximum limit is 25 since each item reads two entries
+    /// IDs whose position entry is missing are skipped
+    pub fn positions_with_data_paginated(
+        env: Env,
+        owner: Address,
+        start_index: u32,
+        limit: u32,
+    ) -> Vec<(u32, PositionData)> {
+        let safe_limit = if limit > 25 { 25 } else { limit };
+
+        let mut positions: Vec<(u32, PositionData)> = Vec::new(&env);
+        for pos_id in PositionList::Owner(owner).page(&env, start_index, safe_limit) {
+            if let Some(position) = read_position(&env, pos_id) {
+                positions.push_back((pos_id, position));
             }
         }
 
@@ -490,6 +1044,11 @@ impl DexPositionManager {
         get_position_owner(&env, position_id)
     }
 
+    /// Owner of a position, None if it was never minted or has been burned
+    pub fn find_owner(env: Env, position_id: u32) -> Option<Address> {
+        read_position_owner(&env, position_id)
+    }
+
     /// Get approved address for position
     pub fn get_approved(env: Env, position_id: u32) -> Option<Address> {
         env.storage()
@@ -505,8 +1064,13 @@ impl DexPositionManager {
             .unwrap_or(false)
     }
 
-    /// Get total positions count
+    /// Get the number of live positions (minted and not yet burned)
     pub fn total_supply(env: Env) -> u32 {
+        PositionList::All.count(&env)
+    }
+
+    /// Get the number of positions ever minted, burned ones included
+    pub fn minted_count(env: Env) -> u32 {
         let next_id: u32 = env
             .storage()
             .instance()
@@ -525,10 +1089,20 @@ fn get_factory(env: &Env) -> Address {
         .expect("Not initialized")
 }
 
+/// Reject expired deadlines and ones more than MAX_DEADLINE_WINDOW ahead
+/// A deadline of 0 means the caller set none
 fn check_deadline(env: &Env, deadline: u64) {
-    if env.ledger().timestamp() > deadline {
+    if deadline == 0 {
+        return;
+    }
+
+    let now = env.ledger().timestamp();
+    if now > deadline {
         panic!("Transaction expired");
     }
+    if deadline - now > MAX_DEADLINE_WINDOW {
+        panic!("Deadline too far");
+    }
 }
 
 fn get_next_position_id(env: &Env) -> u32 {
@@ -543,24 +1117,28 @@ fn get_next_position_id(env: &Env) -> u32 {
     id
 }
 
-fn get_position(env: &Env, position_id: u32) -> PositionData {
+fn read_position(env: &Env, position_id: u32) -> Option<PositionData> {
     env.storage()
         .persistent()
         .get(&DataKey::Position(position_id))
-        .expect("Position not found")
 }
 
-fn get_position_owner(env: &Env, position_id: u32) -> Address {
+fn read_position_owner(env: &Env, position_id: u32) -> Option<Address> {
     env.storage()
         .persistent()
         .get(&DataKey::PositionOwner(position_id))
-        .expect("Position not found")
 }
 
-fn is_approved_or_owner(env: &Env, owner: &Address, position_id: u32) -> bool {
-    let caller = env.current_contract_address(); // TODO: get actual caller
+fn get_position(env: &Env, position_id: u32) -> PositionData {
+    read_position(env, position_id).expect("Position not found")
+}
+
+fn get_position_owner(env: &Env, position_id: u32) -> Address {
+    read_position_owner(env, position_id).expect("Position not found")
+}
 
-    if caller == *owner {
+fn is_approved_or_owner(env: &Env, caller: &Address, owner: &Address, position_id: u32) -> bool {
+    if caller == owner {
         return true;
     }
 
@@ -570,7 +1148,7 @@ fn is_approved_or_owner(env: &Env, owner: &Address, position_id: u32) -> bool {
         .persistent()
         .get::<_, Address>(&DataKey::Approval(position_id))
     {
-        if approved == caller {
+        if approved == *caller {
             return true;
         }
     }
@@ -578,101 +1156,236 @@ fn is_approved_or_owner(env: &Env, owner: &Address, position_id: u32) -> bool {
     // Check approval for all
     env.storage()
         .persistent()
-        .get(&DataKey::ApprovalForAll(owner.clone(), caller))
+        .get(&DataKey::ApprovalForAll(owner.clone(), caller.clone()))
         .unwrap_or(false)
 }
 
-/// Add position to owner's indexed list - O(1) operation
-fn add_position_to_owner(env: &Env, owner: &Address, position_id: u32) {
-    // Get current count
-    let count: u32 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::OwnerPositionCount(owner.clone()))
-        .unwrap_or(0);
+/// Require `address`'s auth unless it already authorized this invocation
+/// A second `require_auth` in the same frame would need its own auth entry
+fn require_auth_once(authorized: &mut Vec<Address>, address: &Address) {
+    if !authorized.contains(address) {
+        address.require_auth();
+        authorized.push_back(address.clone());
+    }
+}
 
-    // Store position at next index
-    env.storage()
-        .persistent()
-        .set(&DataKey::OwnerPositionAt(owner.clone(), count), &position_id);
+/// Move a position from `from` to `to` on behalf of `spender`
+/// Callers are responsible for `spender`'s auth
+fn transfer_position(env: &Env, spender: &Address, from: &Address, to: &Address, position_id: u32) {
+    let owner = get_position_owner(env, position_id);
+
+    if owner != *from {
+        panic!("Not owner");
+    }
+
+    if !is_approved_or_owner(env, spender, from, position_id) {
+        panic!("Not authorized");
+    }
 
-    // Store the index for this position (for O(1) removal)
+    // Update owner
     env.storage()
         .persistent()
-        .set(&DataKey::PositionIndex(position_id), &count);
+        .set(&DataKey::PositionOwner(position_id), to);
 
-    // Increment count
+    // Clear approval
     env.storage()
         .persistent()
-        .set(&DataKey::OwnerPositionCount(owner.clone()), &(count + 1));
+        .remove(&DataKey::Approval(position_id));
+
+    // Update owner lists
+    remove_position_from_owner(env, from, position_id);
+    add_position_to_owner(env, to, position_id);
+
+    publish_transfer(env, Some(from.clone()), Some(to.clone()), position_id);
 }
 
-/// Remove position from owner's indexed list using swap-and-pop - O(1) operation
-fn remove_position_from_owner(env: &Env, owner: &Address, position_id: u32) {
-    // Get current count
-    let count: u32 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::OwnerPositionCount(owner.clone()))
-        .unwrap_or(0);
+/// NFT-style transfer event: topics ("transfer", from, to), data position ID
+/// Mints have no `from` and burns have no `to`
+fn publish_transfer(env: &Env, from: Option<Address>, to: Option<Address>, position_id: u32) {
+    env.events()
+        .publish((Symbol::new(env, "transfer"), from, to), position_id);
+}
 
-    if count == 0 {
-        return;
+/// Require a contract recipient to acknowledge a position it was sent
+/// Accounts, and addresses with nothing deployed, are not asked
+fn check_on_position_received(
+    env: &Env,
+    operator: &Address,
+    from: &Address,
+    to: &Address,
+    position_id: u32,
+    data: Bytes,
+) {
+    match to.executable() {
+        Some(Executable::Wasm(_)) | Some(Executable::StellarAsset) => {}
+        _ => return,
     }
 
-    // Get the index of the position to remove
-    let index_to_remove: u32 = env
-        .storage()
-        .persistent()
-        .get(&DataKey::PositionIndex(position_id))
-        .unwrap_or(0);
+    // A receiver that traps or lacks the hook rejects the position like a wrong answer
+    let result = env.try_invoke_contract::<Symbol, soroban_sdk::Error>(
+        to,
+        &Symbol::new(env, "on_position_received"),
+        (operator.clone(), from.clone(), position_id, data).into_val(env),
+    );
+    match result {
+        Ok(Ok(answer)) if answer == Symbol::new(env, POSITION_RECEIVED) => {}
+        _ => panic!("Receiver rejected position"),
+    }
+}
 
-    let last_index = count - 1;
+/// An indexed list of position IDs: a count plus one entry per slot, with a
+/// reverse index so removal is O(1) swap-and-pop
+enum PositionList {
+    /// Positions held by an owner
+    Owner(Address),
+    /// Positions minted on a pool
+    Pool(Address),
+    /// Every live position
+    All,
+}
 
-    // If not the last element, swap with the last element
-    if index_to_remove != last_index {
-        // Get the last position ID
-        let last_position_id: u32 = env
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerPositionAt(owner.clone(), last_index))
-            .unwrap_or(0);
+impl PositionList {
+    fn count_key(&self) -> DataKey {
+        match self {
+            PositionList::Owner(owner) => DataKey::OwnerPositionCount(owner.clone()),
+            PositionList::Pool(pool) => DataKey::PoolPositionCount(pool.clone()),
+            PositionList::All => DataKey::ActiveSupply,
+        }
+    }
 
-        // Move last position to the removed slot
-        env.storage().persistent().set(
-            &DataKey::OwnerPositionAt(owner.clone(), index_to_remove),
-            &last_position_id,
-        );
+    fn at_key(&self, index: u32) -> DataKey {
+        match self {
+            PositionList::Owner(owner) => DataKey::OwnerPositionAt(owner.clone(), index),
+            PositionList::Pool(pool) => DataKey::PoolPositionAt(pool.clone(), index),
+            PositionList::All => DataKey::AllPositionAt(index),
+        }
+    }
+
+    fn index_key(&self, position_id: u32) -> DataKey {
+        match self {
+            PositionList::Owner(_) => DataKey::PositionIndex(position_id),
+            PositionList::Pool(_) => DataKey::PoolPositionIndex(position_id),
+            PositionList::All => DataKey::AllPositionIndex(position_id),
+        }
+    }
 
-        // Update the index of the moved position
+    fn count(&self, env: &Env) -> u32 {
         env.storage()
             .persistent()
-            .set(&DataKey::PositionIndex(last_position_id), &index_to_remove);
+            .get(&self.count_key())
+            .unwrap_or(0)
     }
 
-    // Remove the last slot
-    env.storage()
-        .persistent()
-        .remove(&DataKey::OwnerPositionAt(owner.clone(), last_index));
+    /// Append a position - O(1) operation
+    fn push(&self, env: &Env, position_id: u32) {
+        let count = self.count(env);
 
-    // Remove the index entry for the removed position
-    env.storage()
-        .persistent()
-        .remove(&DataKey::PositionIndex(position_id));
+        // Store position at next index
+        env.storage()
+            .persistent()
+            .set(&self.at_key(count), &position_id);
 
-    // Decrement count
-    if count > 1 {
+        // Store the index for this position (for O(1) removal)
         env.storage()
             .persistent()
-            .set(&DataKey::OwnerPositionCount(owner.clone()), &(count - 1));
-    } else {
-        // Remove count entry when empty
+            .set(&self.index_key(position_id), &count);
+
+        // Increment count
+        env.storage()
+            .persistent()
+            .set(&self.count_key(), &(count + 1));
+    }
+
+    /// Remove a position using swap-and-pop - O(1) operation
+    fn remove(&self, env: &Env, position_id: u32) {
+        let count = self.count(env);
+
+        if count == 0 {
+            return;
+        }
+
+        // Get the index of the position to remove; guessing it would swap out
+        // another position's entry
+        let index_to_remove: u32 = env
+            .storage()
+            .persistent()
+            .get(&self.index_key(position_id))
+            .expect("Position index missing");
+
+        let stored: Option<u32> = env
+            .storage()
+            .persistent()
+            .get(&self.at_key(index_to_remove));
+        if index_to_remove >= count || stored != Some(position_id) {
+            panic!("Position index mismatch");
+        }
+
+        let last_index = count - 1;
+
+        // If not the last element, swap with the last element
+        if index_to_remove != last_index {
+            // Get the last position ID
+            let last_position_id: u32 = env
+                .storage()
+                .persistent()
+                .get(&self.at_key(last_index))
+                .unwrap_or(0);
+
+            // Move last position to the removed slot
+            env.storage()
+                .persistent()
+                .set(&self.at_key(index_to_remove), &last_position_id);
+
+            // Update the index of the moved position
+            env.storage()
+                .persistent()
+                .set(&self.index_key(last_position_id), &index_to_remove);
+        }
+
+        // Remove the last slot
+        env.storage().persistent().remove(&self.at_key(last_index));
+
+        // Remove the index entry for the removed position
         env.storage()
             .persistent()
-            .remove(&DataKey::OwnerPositionCount(owner.clone()));
+            .remove(&self.index_key(position_id));
+
+        // Decrement count
+        if count > 1 {
+            env.storage()
+                .persistent()
+                .set(&self.count_key(), &(count - 1));
+        } else {
+            // Remove count entry when empty
+            env.storage().persistent().remove(&self.count_key());
+        }
+    }
+
+    /// Up to `limit` position IDs starting from `start_index`
+    fn page(&self, env: &Env, start_index: u32, limit: u32) -> Vec<u32> {
+        let count = self.count(env);
+        let end_index = start_index.saturating_add(limit).min(count);
+
+        let mut positions: Vec<u32> = Vec::new(env);
+        for i in start_index..end_index {
+            if let Some(pos_id) = env.storage().persistent().get(&self.at_key(i)) {
+                positions.push_back(pos_id);
+            }
+        }
+        positions
     }
 }
 
+/// Add position to owner's indexed list - O(1) operation
+fn add_position_to_owner(env: &Env, owner: &Address, position_id: u32) {
+    PositionList::Owner(owner.clone()).push(env, position_id);
+}
+
+/// Remove position from owner's indexed list using swap-and-pop - O(1) operation
+fn remove_position_from_owner(env: &Env, owner: &Address, position_id: u32) {
+    PositionList::Owner(owner.clone()).remove(env, position_id);
+}
+
 fn get_pool(env: &Env, factory: &Address, token0: &Address, token1: &Address, fee: u32) -> Address {
     let pool: Option<Address> = env.invoke_contract(
         factory,
@@ -682,51 +1395,3276 @@ fn get_pool(env: &Env, factory: &Address, token0: &Address, token1: &Address, fe
     pool.expect("Pool not found")
 }
 
+/// Look up the pool, creating it through the factory on behalf of `creator` if missing
+fn get_or_create_pool(
+    env: &Env,
+    factory: &Address,
+    creator: &Address,
+    token0: &Address,
+    token1: &Address,
+    fee: u32,
+    sqrt_price_x96: u128,
+) -> Address {
+    let existing: Option<Address> = env.invoke_contract(
+        factory,
+        &Symbol::new(env, "get_pool"),
+        (token0, token1, fee).into_val(env),
+    );
+    match existing {
+        Some(pool) => pool,
+        None => env.invoke_contract(
+            factory,
+            &Symbol::new(env, "create_pool"),
+            (creator, token0, token1, fee, sqrt_price_x96).into_val(env),
+        ),
+    }
+}
+
+/// Longest position name, in bytes
+const MAX_NAME_LEN: usize = 64;
+
+/// Longest token symbol copied into a name; longer ones show as "?"
+const MAX_SYMBOL_LEN: usize = 16;
+
+/// Fixed-size buffer for building position names without an allocator
+struct NameBuf {
+    bytes: [u8; MAX_NAME_LEN],
+    len: usize,
+}
+
+impl NameBuf {
+    fn new() -> Self {
+        Self {
+            bytes: [0; MAX_NAME_LEN],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        let n = bytes.len().min(MAX_NAME_LEN - self.len);
+        self.bytes[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+    }
+
+    fn push_string(&mut self, s: &String) {
+        let len = s.len() as usize;
+        if len > MAX_SYMBOL_LEN {
+            self.push(b"?");
+            return;
+        }
+        let mut symbol = [0u8; MAX_SYMBOL_LEN];
+        s.copy_into_slice(&mut symbol[..len]);
+        self.push(&symbol[..len]);
+    }
+
+    fn push_u32(&mut self, mut value: u32) {
+        let mut digits = [0u8; 10];
+        let mut i = digits.len();
+        loop {
+            i -= 1;
+            digits[i] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        self.push(&digits[i..]);
+    }
+
+    fn push_i32(&mut self, value: i32) {
+        if value < 0 {
+            self.push(b"-");
+        }
+        self.push_u32(value.unsigned_abs());
+    }
+
+    /// Fee in hundredths of bps as a percentage, e.g. 3000 -> "0.3%"
+    fn push_fee(&mut self, fee: u32) {
+        self.push_u32(fee / 10_000);
+        let mut fraction = fee % 10_000;
+        if fraction != 0 {
+            // Four fractional digits, trailing zeros dropped
+            let mut width = 4;
+            while fraction.is_multiple_of(10) {
+                fraction /= 10;
+                width -= 1;
+            }
+            self.push(b".");
+            let mut scale = 10u32.pow(width - 1);
+            while scale > 0 {
+                self.push(&[b'0' + (fraction / scale % 10) as u8]);
+                scale /= 10;
+            }
+        }
+        self.push(b"%");
+    }
+
+    fn to_string(&self, env: &Env) -> String {
+        String::from_bytes(env, &self.bytes[..self.len])
+    }
+}
+
 fn get_pool_state(env: &Env, pool: &Address) -> dex_types::PoolState {
     env.invoke_contract(pool, &Symbol::new(env, "get_state"), ().into_val(env))
 }
 
-fn invoke_pool_mint(
+fn get_pool_config(env: &Env, pool: &Address) -> dex_types::PoolConfig {
+    env.invoke_contract(pool, &Symbol::new(env, "get_config"), ().into_val(env))
+}
+
+/// Reject a range the pool would refuse, before any liquidity math or pool call
+fn check_tick_range(tick_lower: i32, tick_upper: i32, tick_spacing: i32) {
+    if tick_lower >= tick_upper
+        || tick_lower < dex_types::MIN_TICK
+        || tick_upper > dex_types::MAX_TICK
+    {
+        panic!("Invalid tick range");
+    }
+    if tick_lower % tick_spacing != 0 || tick_upper % tick_spacing != 0 {
+        panic!("Tick not aligned to spacing");
+    }
+}
+
+/// Liquidity the desired amounts buy in a range at `sqrt_price_x96`
+fn liquidity_for_desired(
     env: &Env,
-    pool: &Address,
-    recipient: &Address,
+    sqrt_price_x96: u128,
     tick_lower: i32,
     tick_upper: i32,
-    amount: u128,
-) -> (u128, u128) {
-    env.invoke_contract(
-        pool,
-        &Symbol::new(env, "mint"),
-        (recipient, tick_lower, tick_upper, amount).into_val(env),
+    amount0_desired: i128,
+    amount1_desired: i128,
+) -> u128 {
+    dex_math::get_liquidity_for_amounts(
+        env,
+        sqrt_price_x96,
+        dex_math::get_sqrt_ratio_at_tick(env, tick_lower),
+        dex_math::get_sqrt_ratio_at_tick(env, tick_upper),
+        amount0_desired as u128,
+        amount1_desired as u128,
     )
 }
 
-fn invoke_pool_burn(
+/// What `mint_position` or `increase_liquidity` would add and charge at
+/// `sqrt_price_x96`, with amounts rounded up exactly as the pool's mint does
+/// Returns: (liquidity, amount0, amount1)
+fn preview_liquidity(
     env: &Env,
-    pool: &Address,
+    sqrt_price_x96: u128,
     tick_lower: i32,
     tick_upper: i32,
-    amount: u128,
-) -> (u128, u128) {
-    env.invoke_contract(
+    amount0_desired: i128,
+    amount1_desired: i128,
+) -> (u128, i128, i128) {
+    let liquidity = liquidity_for_desired(
+        env,
+        sqrt_price_x96,
+        tick_lower,
+        tick_upper,
+        amount0_desired,
+        amount1_desired,
+    );
+
+    let sqrt_ratio_lower = dex_math::get_sqrt_ratio_at_tick(env, tick_lower);
+    let sqrt_ratio_upper = dex_math::get_sqrt_ratio_at_tick(env, tick_upper);
+    let (amount0, amount1) = if sqrt_price_x96 <= sqrt_ratio_lower {
+        // Below range - all token0
+        (
+            dex_math::get_amount0_delta(env, sqrt_ratio_lower, sqrt_ratio_upper, liquidity, true),
+            0,
+        )
+    } else if sqrt_price_x96 < sqrt_ratio_upper {
+        // In range - both tokens
+        (
+            dex_math::get_amount0_delta(env, sqrt_price_x96, sqrt_ratio_upper, liquidity, true),
+            dex_math::get_amount1_delta(env, sqrt_ratio_lower, sqrt_price_x96, liquidity, true),
+        )
+    } else {
+        // Above range - all token1
+        (
+            0,
+            dex_math::get_amount1_delta(env, sqrt_ratio_lower, sqrt_ratio_upper, liquidity, true),
+        )
+    };
+
+    (liquidity, amount0 as i128, amount1 as i128)
+}
+
+/// Reject a recipient that can't meaningfully receive a position or its tokens
+fn check_recipient(env: &Env, recipient: &Address, excluded: &[&Address]) {
+    if !dex_types::is_valid_recipient(env, recipient, excluded) {
+        panic_with_error!(env, ManagerError::InvalidRecipient);
+    }
+}
+
+/// Mint liquidity in `pool` and record a new position for `params.recipient`
+/// Callers are responsible for auth and deadline checks
+fn mint_position(env: &Env, pool: &Address, params: &MintParams) -> (u32, u128, i128, i128) {
+    let factory = get_factory(env);
+    check_recipient(
+        env,
+        &params.recipient,
+        &[&params.token0, &params.token1, pool, &factory],
+    );
+
+    let tick_spacing = get_pool_config(env, pool).tick_spacing;
+    check_tick_range(params.tick_lower, params.tick_upper, tick_spacing);
+
+    // Calculate liquidity from desired amounts
+    let pool_state = get_pool_state(env, pool);
+    let liquidity = liquidity_for_desired(
+        env,
+        pool_state.sqrt_price_x96,
+        params.tick_lower,
+        params.tick_upper,
+        params.amount0_desired,
+        params.amount1_desired,
+    );
+
+    // Mint liquidity in pool, paid for by the payer
+    let (amount0, amount1) = invoke_pool_mint(
+        env,
         pool,
-        &Symbol::new(env, "burn"),
-        (tick_lower, tick_upper, amount).into_val(env),
-    )
+        &params.payer,
+        &env.current_contract_address(),
+        params.tick_lower,
+        params.tick_upper,
+        liquidity,
+    );
+
+    // Desired amounts are hard caps on what is spent
+    if (amount0 as i128) > params.amount0_desired || (amount1 as i128) > params.amount1_desired {
+        panic!("Amount exceeds desired");
+    }
+
+    // Check minimums
+    if (amount0 as i128) < params.amount0_min || (amount1 as i128) < params.amount1_min {
+        panic!("Slippage check failed");
+    }
+
+    // Create NFT position, checkpointing fee growth so fees start accruing from now
+    let position_id = get_next_position_id(env);
+
+    let (fee_growth_inside_0, fee_growth_inside_1) = get_pool_fee_growth_inside(
+        env,
+        pool,
+        params.tick_lower,
+        params.tick_upper,
+    );
+
+    let position_data = PositionData {
+        pool: pool.clone(),
+        tick_lower: params.tick_lower,
+        tick_upper: params.tick_upper,
+        liquidity,
+        fee_growth_inside_0_last_x128: fee_growth_inside_0,
+        fee_growth_inside_1_last_x128: fee_growth_inside_1,
+        tokens_owed_0: 0,
+        tokens_owed_1: 0,
+    };
+
+    // Store position
+    env.storage()
+        .persistent()
+        .set(&DataKey::Position(position_id), &position_data);
+    env.storage()
+        .persistent()
+        .set(&DataKey::PositionOwner(position_id), &params.recipient);
+
+    // Add to owner's and pool's positions
+    add_position_to_owner(env, &params.recipient, position_id);
+    PositionList::Pool(pool.clone()).push(env, position_id);
+    PositionList::All.push(env, position_id);
+
+    // Emit events
+    env.events().publish(
+        (Symbol::new(env, "position_minted"),),
+        (position_id, params.recipient.clone(), liquidity),
+    );
+    publish_transfer(env, None, Some(params.recipient.clone()), position_id);
+
+    (position_id, liquidity, amount0 as i128, amount1 as i128)
 }
 
-fn invoke_pool_collect(
+/// Add liquidity to a position on behalf of `caller`, paid for by `params.payer`
+/// Callers are responsible for the auth of `caller` and the payer
+fn increase_position(
     env: &Env,
-    pool: &Address,
-    recipient: &Address,
-    tick_lower: i32,
+    caller: &Address,
+    params: &IncreaseLiquidityParams,
+) -> (u128, i128, i128) {
+    check_deadline(env, params.deadline);
+
+    let owner = get_position_owner(env, params.position_id);
+    if !is_approved_or_owner(env, caller, &owner, params.position_id) {
+        panic!("Not authorized");
+    }
+
+    let mut position = get_position(env, params.position_id);
+
+    // Calculate additional liquidity
+    let pool_state = get_pool_state(env, &position.pool);
+    let liquidity = liquidity_for_desired(
+        env,
+        pool_state.sqrt_price_x96,
+        position.tick_lower,
+        position.tick_upper,
+        params.amount0_desired,
+        params.amount1_desired,
+    );
+
+    // Mint in pool, paid for by the payer
+    let (amount0, amount1) = invoke_pool_mint(
+        env,
+        &position.pool,
+        &params.payer,
+        &env.current_contract_address(),
+        position.tick_lower,
+        position.tick_upper,
+        liquidity,
+    );
+
+    // Desired amounts are hard caps on what is spent
+    if (amount0 as i128) > params.amount0_desired || (amount1 as i128) > params.amount1_desired {
+        panic!("Amount exceeds desired");
+    }
+
+    // Check minimums
+    if (amount0 as i128) < params.amount0_min || (amount1 as i128) < params.amount1_min {
+        panic!("Slippage check failed");
+    }
+
+    // Update position, crediting fees earned on the existing liquidity first
+    accrue_position_fees(env, &mut position);
+    position.liquidity += liquidity;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Position(params.position_id), &position);
+
+    env.events().publish(
+        (Symbol::new(env, "liquidity_increased"),),
+        (
+            params.position_id,
+            liquidity,
+            amount0 as i128,
+            amount1 as i128,
+        ),
+    );
+
+    (liquidity, amount0 as i128, amount1 as i128)
+}
+
+/// Remove liquidity from a position on behalf of `caller`, crediting the
+/// released tokens to its tokens owed
+/// Callers are responsible for `caller`'s auth
+fn decrease_position(
+    env: &Env,
+    caller: &Address,
+    params: &DecreaseLiquidityParams,
+) -> (i128, i128) {
+    check_deadline(env, params.deadline);
+
+    let owner = get_position_owner(env, params.position_id);
+    if !is_approved_or_owner(env, caller, &owner, params.position_id) {
+        panic!("Not authorized");
+    }
+
+    let mut position = get_position(env, params.position_id);
+
+    if params.liquidity > position.liquidity {
+        panic!("Insufficient liquidity");
+    }
+
+    // Credit fees earned on the existing liquidity while the range's ticks
+    // still exist; a burn that empties the range deletes them
+    accrue_position_fees(env, &mut position);
+
+    // Burn in pool
+    let (amount0, amount1) = invoke_pool_burn(
+        env,
+        &position.pool,
+        &env.current_contract_address(),
+        position.tick_lower,
+        position.tick_upper,
+        params.liquidity,
+    );
+
+    // Check minimums
+    if (amount0 as i128) < params.amount0_min || (amount1 as i128) < params.amount1_min {
+        panic!("Slippage check failed");
+    }
+
+    // Update position
+    position.liquidity -= params.liquidity;
+    position.tokens_owed_0 += amount0;
+    position.tokens_owed_1 += amount1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Position(params.position_id), &position);
+
+    env.events().publish(
+        (Symbol::new(env, "liquidity_decreased"),),
+        (
+            params.position_id,
+            params.liquidity,
+            amount0 as i128,
+            amount1 as i128,
+        ),
+    );
+
+    (amount0 as i128, amount1 as i128)
+}
+
+/// Pay a position's fees and tokens owed to `params.recipient` on behalf of `caller`
+/// Callers are responsible for `caller`'s auth
+fn collect_position(env: &Env, caller: &Address, params: &CollectParams) -> (u128, u128) {
+    check_deadline(env, params.deadline);
+
+    let owner = get_position_owner(env, params.position_id);
+    if !is_approved_or_owner(env, caller, &owner, params.position_id) {
+        panic!("Not authorized");
+    }
+
+    let mut position = get_position(env, params.position_id);
+
+    // Tokens are rejected by the pool itself
+    check_recipient(env, &params.recipient, &[&position.pool, &get_factory(env)]);
+
+    // The pool position is shared by every NFT on this range, so pay out
+    // no more than this position's own fees and principal
+    poke_position(env, &mut position);
+
+    // Collect from pool
+    let (collected0, collected1) = invoke_pool_collect(
+        env,
+        &position.pool,
+        &env.current_contract_address(),
+        &params.recipient,
+        position.tick_lower,
+        position.tick_upper,
+        collect_request(params.amount0_max, position.tokens_owed_0),
+        collect_request(params.amount1_max, position.tokens_owed_1),
+    );
+
+    // Update tokens owed
+    position.tokens_owed_0 = position.tokens_owed_0.saturating_sub(collected0);
+    position.tokens_owed_1 = position.tokens_owed_1.saturating_sub(collected1);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Position(params.position_id), &position);
+
+    env.events().publish(
+        (Symbol::new(env, "fees_collected"),),
+        (
+            params.position_id,
+            params.recipient.clone(),
+            collected0,
+            collected1,
+        ),
+    );
+
+    (collected0, collected1)
+}
+
+/// Delete `owner`'s position and its NFT (requires zero liquidity and nothing owed)
+/// Callers are responsible for `owner`'s auth
+fn burn_position(env: &Env, owner: &Address, position_id: u32) {
+    let position = get_position(env, position_id);
+
+    if position.liquidity != 0 {
+        panic!("Position has liquidity");
+    }
+
+    if position.tokens_owed_0 != 0 || position.tokens_owed_1 != 0 {
+        panic!("Position has uncollected tokens");
+    }
+
+    // Remove position
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Position(position_id));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PositionOwner(position_id));
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Approval(position_id));
+
+    // Remove from owner's and pool's lists
+    remove_position_from_owner(env, owner, position_id);
+    PositionList::Pool(position.pool).remove(env, position_id);
+    PositionList::All.remove(env, position_id);
+
+    publish_transfer(env, Some(owner.clone()), None, position_id);
+    env.events()
+        .publish((Symbol::new(env, "position_burned"),), (position_id,));
+}
+
+/// Remove all of `owner`'s liquidity, pay everything owed to `recipient` and
+/// burn the NFT, as `exit_position` does
+/// Callers are responsible for `owner`'s auth
+fn close_position(
+    env: &Env,
+    owner: &Address,
+    position_id: u32,
+    amount0_min: i128,
+    amount1_min: i128,
+    recipient: Address,
+    deadline: u64,
+) -> (i128, i128) {
+    let position = get_position(env, position_id);
+    if position.liquidity > 0 {
+        decrease_position(
+            env,
+            owner,
+            &DecreaseLiquidityParams {
+                position_id,
+                liquidity: position.liquidity,
+                amount0_min,
+                amount1_min,
+                deadline,
+            },
+        );
+    } else {
+        check_deadline(env, deadline);
+    }
+
+    let (amount0, amount1) = collect_position(
+        env,
+        owner,
+        &CollectParams {
+            position_id,
+            recipient,
+            amount0_max: COLLECT_ALL,
+            amount1_max: COLLECT_ALL,
+            deadline: 0,
+        },
+    );
+    burn_position(env, owner, position_id);
+
+    (amount0 as i128, amount1 as i128)
+}
+
+fn get_pool_fee_growth_inside(
+    env: &Env,
+    pool: &Address,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> (u128, u128) {
+    env.invoke_contract(
+        pool,
+        &Symbol::new(env, "get_fee_growth_inside"),
+        (tick_lower, tick_upper).into_val(env),
+    )
+}
+
+/// Fees earned since the position's last checkpoint plus tokens already owed
+fn compute_uncollected_fees(
+    env: &Env,
+    position: &PositionData,
+    fee_growth_inside_0_x128: u128,
+    fee_growth_inside_1_x128: u128,
+) -> (u128, u128) {
+    let fee_delta_0 = fee_growth_inside_0_x128.wrapping_sub(position.fee_growth_inside_0_last_x128);
+    let fee_delta_1 = fee_growth_inside_1_x128.wrapping_sub(position.fee_growth_inside_1_last_x128);
+
+    (
+        position.tokens_owed_0 + dex_math::mul_shr_128(env, fee_delta_0, position.liquidity),
+        position.tokens_owed_1 + dex_math::mul_shr_128(env, fee_delta_1, position.liquidity),
+    )
+}
+
+/// Credit fees earned since the last checkpoint to tokens owed and move the checkpoint
+fn accrue_position_fees(env: &Env, position: &mut PositionData) {
+    let (fee_growth_inside_0, fee_growth_inside_1) = get_pool_fee_growth_inside(
+        env,
+        &position.pool,
+        position.tick_lower,
+        position.tick_upper,
+    );
+
+    let (owed0, owed1) =
+        compute_uncollected_fees(env, position, fee_growth_inside_0, fee_growth_inside_1);
+    position.tokens_owed_0 = owed0;
+    position.tokens_owed_1 = owed1;
+    position.fee_growth_inside_0_last_x128 = fee_growth_inside_0;
+    position.fee_growth_inside_1_last_x128 = fee_growth_inside_1;
+}
+
+/// Settle the pool's fees into the manager's pool position, then accrue this
+/// position's share of them
+fn poke_position(env: &Env, position: &mut PositionData) {
+    // Without liquidity the position earned nothing and the pool rejects a zero burn
+    if position.liquidity > 0 {
+        invoke_pool_burn(
+            env,
+            &position.pool,
+            &env.current_contract_address(),
+            position.tick_lower,
+            position.tick_upper,
+            0,
+        );
+    }
+    accrue_position_fees(env, position);
+}
+
+fn invoke_pool_mint(
+    env: &Env,
+    pool: &Address,
+    payer: &Address,
+    recipient: &Address,
+    tick_lower: i32,
+    tick_upper: i32,
+    amount: u128,
+) -> (u128, u128) {
+    env.invoke_contract(
+        pool,
+        &Symbol::new(env, "mint"),
+        (payer, recipient, tick_lower, tick_upper, amount).into_val(env),
+    )
+}
+
+/// Authorize the token transfers the pool's mint makes from the manager
+/// The pool, not the manager, invokes the tokens, so the manager's own auth
+/// does not reach them without these entries
+fn authorize_pool_payment(env: &Env, pool: &Address, amount0: i128, amount1: i128) {
+    let config = get_pool_config(env, pool);
+    let mut entries = Vec::new(env);
+    for (token, amount) in [(config.token0, amount0), (config.token1, amount1)] {
+        if amount > 0 {
+            entries.push_back(InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token,
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (env.current_contract_address(), pool, amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }));
+        }
+    }
+    env.authorize_as_current_contract(entries);
+}
+
+fn invoke_pool_burn(
+    env: &Env,
+    pool: &Address,
+    owner: &Address,
+    tick_lower: i32,
+    tick_upper: i32,
+    amount: u128,
+) -> (u128, u128) {
+    env.invoke_contract(
+        pool,
+        &Symbol::new(env, "burn"),
+        (owner, tick_lower, tick_upper, amount).into_val(env),
+    )
+}
+
+/// Amount of one token to request from the pool for a collect cap
+/// COLLECT_ALL takes everything the position is owed and 0 takes nothing;
+/// any other cap is clamped to what is owed
+fn collect_request(amount_max: u128, tokens_owed: u128) -> u128 {
+    match amount_max {
+        0 => 0,
+        COLLECT_ALL => tokens_owed,
+        cap => cap.min(tokens_owed),
+    }
+}
+
+fn invoke_pool_collect(
+    env: &Env,
+    pool: &Address,
+    owner: &Address,
+    recipient: &Address,
+    tick_lower: i32,
     tick_upper: i32,
     amount0_max: u128,
     amount1_max: u128,
 ) -> (u128, u128) {
+    // Nothing requested, so leave the pool untouched
+    if amount0_max == 0 && amount1_max == 0 {
+        return (0, 0);
+    }
+
     env.invoke_contract(
         pool,
         &Symbol::new(env, "collect"),
-        (recipient, tick_lower, tick_upper, amount0_max, amount1_max).into_val(env),
+        (owner, recipient, tick_lower, tick_upper, amount0_max, amount1_max).into_val(env),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke};
+    use soroban_sdk::token::StellarAssetClient;
+    use soroban_sdk::{contract, contractimpl, vec, Val};
+
+    // Factory and pool run from their compiled WASM.
+    // Build them first with `cargo build --release --target wasm32-unknown-unknown`.
+    mod dex_factory_contract {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32-unknown-unknown/release/dex_factory.wasm"
+        );
+    }
+
+    mod dex_pool_contract {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32-unknown-unknown/release/dex_pool.wasm"
+        );
+    }
+
+    const Q96: u128 = 1 << 96;
+
+    /// Register a Stellar asset contract to use as a pool token
+    fn create_token(env: &Env) -> Address {
+        let issuer = Address::generate(env);
+        env.register_stellar_asset_contract_v2(issuer).address()
+    }
+
+    /// Deploy a factory with real pool WASM, a position manager and a sorted token pair
+    fn setup<'a>(
+        env: &Env,
+    ) -> (
+        dex_factory_contract::Client<'a>,
+        DexPositionManagerClient<'a>,
+        Address,
+        Address,
+    ) {
+        let admin = Address::generate(env);
+        let factory_id = env.register(dex_factory_contract::WASM, ());
+        let factory = dex_factory_contract::Client::new(env, &factory_id);
+        let pool_wasm_hash = env.deployer().upload_contract_wasm(dex_pool_contract::WASM);
+        factory.initialize(&admin, &pool_wasm_hash);
+
+        let manager_id = env.register(DexPositionManager, ());
+        let manager = DexPositionManagerClient::new(env, &manager_id);
+        manager.initialize(&factory_id);
+
+        let token_a = create_token(env);
+        let token_b = create_token(env);
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        (factory, manager, token0, token1)
+    }
+
+    /// Events the last invocation published from `contract`
+    fn contract_events(env: &Env, contract: &Address) -> Vec<(Address, Vec<Val>, Val)> {
+        let mut events = Vec::new(env);
+        for event in env.events().all().iter() {
+            if event.0 == *contract {
+                events.push_back(event);
+            }
+        }
+        events
+    }
+
+    /// Assert the last call emitted exactly one manager event
+    fn assert_single_event(env: &Env, manager: &DexPositionManagerClient, topic: &str, data: Val) {
+        assert_eq!(
+            contract_events(env, &manager.address),
+            soroban_sdk::vec![
+                env,
+                (
+                    manager.address.clone(),
+                    (Symbol::new(env, topic),).into_val(env),
+                    data,
+                ),
+            ]
+        );
+    }
+
+    fn fund(env: &Env, token: &Address, to: &Address, amount: i128) {
+        StellarAssetClient::new(env, token).mint(to, &amount);
+    }
+
+    fn mint_params(
+        token0: &Address,
+        token1: &Address,
+        fee: u32,
+        recipient: &Address,
+    ) -> MintParams {
+        MintParams {
+            token0: token0.clone(),
+            token1: token1.clone(),
+            fee,
+            tick_lower: -600,
+            tick_upper: 600,
+            amount0_desired: 1_000_000,
+            amount1_desired: 1_000_000,
+            amount0_min: 0,
+            amount1_min: 0,
+            payer: recipient.clone(),
+            recipient: recipient.clone(),
+            deadline: 1000,
+            sqrt_price_x96: None,
+        }
+    }
+
+    fn create_pool_and_mint_params(
+        token0: &Address,
+        token1: &Address,
+        sqrt_price_x96: u128,
+        recipient: &Address,
+    ) -> CreatePoolAndMintParams {
+        CreatePoolAndMintParams {
+            token0: token0.clone(),
+            token1: token1.clone(),
+            fee: 3000,
+            sqrt_price_x96,
+            amount0_desired: 1_000_000,
+            amount1_desired: 1_000_000,
+            amount0_min: 0,
+            amount1_min: 0,
+            recipient: recipient.clone(),
+            deadline: 1000,
+        }
+    }
+
+    // === Create Pool And Mint Tests ===
+
+    #[test]
+    fn test_create_pool_and_mint_fresh_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (pool, position_id, liquidity, amount0, amount1) = manager
+            .create_pool_and_mint(&create_pool_and_mint_params(&token0, &token1, Q96, &owner));
+
+        assert_eq!(factory.get_pool(&token0, &token1, &3000), Some(pool.clone()));
+        assert_eq!(factory.get_pool_count(), 1);
+        assert!(liquidity > 0);
+        assert!(amount0 > 0 && amount1 > 0);
+
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        assert_eq!(pool_client.sqrt_price_x96(), Q96);
+        assert_eq!(pool_client.liquidity(), liquidity);
+
+        // Full range for 60-spacing ticks
+        let position = manager.get_position(&position_id);
+        assert_eq!(position.pool, pool);
+        assert_eq!(position.tick_lower, -443580);
+        assert_eq!(position.tick_upper, 443580);
+        assert_eq!(position.liquidity, liquidity);
+        assert_eq!(manager.owner_of(&position_id), owner);
+    }
+
+    #[test]
+    fn test_create_pool_and_mint_existing_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let creator = Address::generate(&env);
+        let existing = factory.create_pool(&creator, &token0, &token1, &3000, &Q96);
+
+        // A different initial price is ignored for an existing pool
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (pool, position_id, liquidity, _, _) = manager
+            .create_pool_and_mint(&create_pool_and_mint_params(&token0, &token1, Q96 * 2, &owner));
+
+        assert_eq!(pool, existing);
+        assert_eq!(factory.get_pool_count(), 1);
+        assert_eq!(dex_pool_contract::Client::new(&env, &pool).sqrt_price_x96(), Q96);
+        assert_eq!(manager.get_position(&position_id).liquidity, liquidity);
+        assert_eq!(manager.balance_of(&owner), 1);
+    }
+
+    // === Payment Tests ===
+
+    #[test]
+    fn test_mint_spends_exactly_the_consumed_amounts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+
+        // token1 is offered well beyond what the range needs at this price
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.amount1_desired = 3_000_000;
+        let (_, _, amount0, amount1) = manager.mint(&params);
+
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        assert!(amount1 < params.amount1_desired);
+        assert_eq!(token0_client.balance(&owner), 10_000_000 - amount0);
+        assert_eq!(token1_client.balance(&owner), 10_000_000 - amount1);
+        // Nothing is escrowed in the manager
+        assert_eq!(token0_client.balance(&manager.address), 0);
+        assert_eq!(token1_client.balance(&manager.address), 0);
+    }
+
+    #[test]
+    fn test_increase_liquidity_spends_exactly_the_consumed_amounts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        let balance0 = token0_client.balance(&owner);
+        let balance1 = token1_client.balance(&owner);
+
+        let (liquidity, amount0, amount1) = manager.increase_liquidity(
+            &owner,
+            &IncreaseLiquidityParams {
+                position_id: id,
+                payer: owner.clone(),
+                amount0_desired: 3_000_000,
+                amount1_desired: 500_000,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        assert_single_event(
+            &env,
+            &manager,
+            "liquidity_increased",
+            (id, liquidity, amount0, amount1).into_val(&env),
+        );
+
+        assert!(amount0 < 3_000_000);
+        assert_eq!(token0_client.balance(&owner), balance0 - amount0);
+        assert_eq!(token1_client.balance(&owner), balance1 - amount1);
+        assert_eq!(token0_client.balance(&manager.address), 0);
+        assert_eq!(token1_client.balance(&manager.address), 0);
+    }
+
+    #[test]
+    fn test_mint_and_increase_stay_within_desired_at_skewed_price() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let sqrt_price = dex_math::get_sqrt_ratio_at_tick(&env, 4000) + 12_345;
+        let creator = Address::generate(&env);
+        factory.create_pool(&creator, &token0, &token1, &3000, &sqrt_price);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.tick_lower = -6000;
+        params.tick_upper = 6000;
+        params.amount0_desired = 1_000_003;
+        params.amount1_desired = 999_983;
+        let (id, _, amount0, amount1) = manager.mint(&params);
+        assert!(amount0 <= params.amount0_desired && amount1 <= params.amount1_desired);
+
+        let increase = IncreaseLiquidityParams {
+            position_id: id,
+            payer: owner.clone(),
+            amount0_desired: 123_457,
+            amount1_desired: 678_901,
+            amount0_min: 0,
+            amount1_min: 0,
+            deadline: 1000,
+        };
+        let (_, amount0, amount1) = manager.increase_liquidity(&owner, &increase);
+        assert!(amount0 <= increase.amount0_desired && amount1 <= increase.amount1_desired);
+    }
+
+    #[test]
+    fn test_negative_desired_amount_cannot_overdraw() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+
+        // Read as a u128, -1 would leave token0 uncapped and the token1 amount
+        // alone would set how much token0 is pulled
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.amount0_desired = -1;
+        assert!(manager.try_mint(&params).is_err());
+        assert_eq!(token0_client.balance(&owner), 10_000_000);
+
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let before = token0_client.balance(&owner);
+        let increase = IncreaseLiquidityParams {
+            position_id: id,
+            payer: owner.clone(),
+            amount0_desired: -1,
+            amount1_desired: 500_000,
+            amount0_min: 0,
+            amount1_min: 0,
+            deadline: 1000,
+        };
+        assert!(manager.try_increase_liquidity(&owner, &increase).is_err());
+        assert_eq!(token0_client.balance(&owner), before);
+        assert_eq!(manager.get_position(&id).liquidity, liquidity);
+    }
+
+    #[test]
+    fn test_payer_funds_position_owned_by_recipient() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let vault = Address::generate(&env);
+        let user = Address::generate(&env);
+        fund(&env, &token0, &vault, 10_000_000);
+        fund(&env, &token1, &vault, 10_000_000);
+
+        let mut params = mint_params(&token0, &token1, 3000, &user);
+        params.payer = vault.clone();
+        let (id, _, amount0, amount1) = manager.mint(&params);
+
+        // Only the payer signed; the user just receives the NFT
+        let auths = env.auths();
+        assert!(auths.iter().any(|(address, _)| *address == vault));
+        assert!(auths.iter().all(|(address, _)| *address != user));
+        assert_eq!(manager.owner_of(&id), user);
+        assert_eq!(manager.balance_of(&user), 1);
+        assert_eq!(manager.balance_of(&vault), 0);
+
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        assert_eq!(token0_client.balance(&vault), 10_000_000 - amount0);
+        assert_eq!(token1_client.balance(&vault), 10_000_000 - amount1);
+        assert_eq!(token0_client.balance(&user), 0);
+        assert_eq!(token1_client.balance(&user), 0);
+
+        // The vault can keep topping up the user's position
+        let (_, paid0, paid1) = manager.increase_liquidity(
+            &user,
+            &IncreaseLiquidityParams {
+                position_id: id,
+                payer: vault.clone(),
+                amount0_desired: 100_000,
+                amount1_desired: 100_000,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        assert_eq!(token0_client.balance(&vault), 10_000_000 - amount0 - paid0);
+        assert_eq!(token1_client.balance(&vault), 10_000_000 - amount1 - paid1);
+        assert_eq!(token0_client.balance(&user), 0);
+        assert_eq!(token1_client.balance(&user), 0);
+    }
+
+    // === Mint Pool Creation Tests ===
+
+    #[test]
+    fn test_mint_with_price_creates_missing_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+
+        // Without a price a missing pool is still an error
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        assert!(manager.try_mint(&params).is_err());
+
+        // Start inside the [-600, 600] range so the position is active
+        let sqrt_price = dex_math::get_sqrt_ratio_at_tick(&env, 120);
+        params.sqrt_price_x96 = Some(sqrt_price);
+        let (id, liquidity, _, _) = manager.mint(&params);
+
+        let pool = factory.get_pool(&token0, &token1, &3000).unwrap();
+        assert_eq!(factory.get_pool_count(), 1);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        assert_eq!(pool_client.sqrt_price_x96(), sqrt_price);
+        assert_eq!(pool_client.liquidity(), liquidity);
+        assert_eq!(manager.get_position(&id).pool, pool);
+        assert_eq!(manager.owner_of(&id), owner);
+    }
+
+    #[test]
+    fn test_mint_with_price_reuses_existing_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let existing = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.sqrt_price_x96 = Some(Q96 * 2);
+        let (id, _, _, _) = manager.mint(&params);
+
+        assert_eq!(manager.get_position(&id).pool, existing);
+        assert_eq!(factory.get_pool_count(), 1);
+        let pool_client = dex_pool_contract::Client::new(&env, &existing);
+        assert_eq!(pool_client.sqrt_price_x96(), Q96);
+    }
+
+    #[test]
+    fn test_create_and_init_pool_if_needed() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = manager.create_and_init_pool_if_needed(&token0, &token1, &3000, &Q96);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        assert_eq!(factory.get_pool(&token0, &token1, &3000).unwrap(), pool);
+        assert_eq!(pool_client.sqrt_price_x96(), Q96);
+
+        // A second call returns the same pool at its original price
+        let again = manager.create_and_init_pool_if_needed(&token0, &token1, &3000, &(Q96 * 2));
+        assert_eq!(again, pool);
+        assert_eq!(factory.get_pool_count(), 1);
+        assert_eq!(pool_client.sqrt_price_x96(), Q96);
+    }
+
+    // === Recipient Validation Tests ===
+
+    #[test]
+    fn test_degenerate_recipients_rejected_by_mint_entrypoints() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let creator = Address::generate(&env);
+        let pool = factory.create_pool(&creator, &token0, &token1, &3000, &Q96);
+
+        let degenerate = [
+            manager.address.clone(),
+            factory.address.clone(),
+            token0.clone(),
+            token1.clone(),
+            pool,
+        ];
+
+        let invalid_recipient = Ok(soroban_sdk::Error::from(ManagerError::InvalidRecipient));
+        for recipient in degenerate.iter() {
+            let result = manager.try_mint(&mint_params(&token0, &token1, 3000, recipient));
+            assert_eq!(result.unwrap_err(), invalid_recipient);
+            let params = create_pool_and_mint_params(&token0, &token1, Q96, recipient);
+            assert_eq!(
+                manager.try_create_pool_and_mint(&params).unwrap_err(),
+                invalid_recipient
+            );
+        }
+
+        // Nothing was minted, and an ordinary recipient still can
+        assert_eq!(manager.total_supply(), 0);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        assert_eq!(manager.balance_of(&owner), 1);
+    }
+
+    #[test]
+    fn test_total_supply_counts_only_live_positions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        assert_eq!(manager.total_supply(), 3);
+        assert_eq!(manager.minted_count(), 3);
+
+        // Burning drops the live count but not the minted count
+        manager.exit_position(&id, &0, &0, &owner, &1000);
+        assert_eq!(manager.total_supply(), 2);
+        assert_eq!(manager.minted_count(), 3);
+
+        manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        assert_eq!(manager.total_supply(), 3);
+        assert_eq!(manager.minted_count(), 4);
+    }
+
+    // === NFT Event Tests ===
+
+    #[test]
+    fn test_mint_transfer_burn_event_stream() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let none: Option<Address> = None;
+        let transfer = Symbol::new(&env, "transfer");
+
+        // Mint: custom event, then a transfer from nobody
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        assert_eq!(
+            contract_events(&env, &manager.address),
+            vec![
+                &env,
+                (
+                    manager.address.clone(),
+                    (Symbol::new(&env, "position_minted"),).into_val(&env),
+                    (id, owner.clone(), liquidity).into_val(&env),
+                ),
+                (
+                    manager.address.clone(),
+                    (transfer.clone(), none.clone(), Some(owner.clone())).into_val(&env),
+                    id.into_val(&env),
+                ),
+            ]
+        );
+
+        // Approvals carry the owner as a topic
+        let operator = Address::generate(&env);
+        manager.approve(&operator, &id);
+        assert_eq!(
+            contract_events(&env, &manager.address),
+            vec![
+                &env,
+                (
+                    manager.address.clone(),
+                    (Symbol::new(&env, "approval"), owner.clone()).into_val(&env),
+                    (operator.clone(), id).into_val(&env),
+                ),
+            ]
+        );
+        manager.set_approval_for_all(&owner, &operator, &true);
+        assert_eq!(
+            contract_events(&env, &manager.address),
+            vec![
+                &env,
+                (
+                    manager.address.clone(),
+                    (Symbol::new(&env, "approval_for_all"), owner.clone()).into_val(&env),
+                    (operator.clone(), true).into_val(&env),
+                ),
+            ]
+        );
+
+        // Transfer between accounts
+        let receiver = Address::generate(&env);
+        manager.transfer_from(&operator, &owner, &receiver, &id);
+        let (from, to) = (Some(owner.clone()), Some(receiver.clone()));
+        assert_eq!(
+            contract_events(&env, &manager.address),
+            vec![
+                &env,
+                (
+                    manager.address.clone(),
+                    (transfer.clone(), from, to).into_val(&env),
+                    id.into_val(&env),
+                ),
+            ]
+        );
+
+        // Burn: a transfer to nobody, then the custom event
+        manager.decrease_liquidity(
+            &receiver,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        manager.collect(
+            &receiver,
+            &CollectParams {
+                position_id: id,
+                recipient: receiver.clone(),
+                amount0_max: COLLECT_ALL,
+                amount1_max: COLLECT_ALL,
+                deadline: 1000,
+            },
+        );
+        manager.burn(&id);
+        assert_eq!(
+            contract_events(&env, &manager.address),
+            vec![
+                &env,
+                (
+                    manager.address.clone(),
+                    (transfer, Some(receiver), none).into_val(&env),
+                    id.into_val(&env),
+                ),
+                (
+                    manager.address.clone(),
+                    (Symbol::new(&env, "position_burned"),).into_val(&env),
+                    (id,).into_val(&env),
+                ),
+            ]
+        );
+    }
+
+    // === Operator Approval Tests ===
+
+    #[test]
+    fn test_owner_approves_and_revokes_operator() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let manager = DexPositionManagerClient::new(&env, &env.register(DexPositionManager, ()));
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+        assert!(!manager.is_approved_for_all(&owner, &operator));
+
+        manager.set_approval_for_all(&owner, &operator, &true);
+        assert!(manager.is_approved_for_all(&owner, &operator));
+        // Approval is per owner, not shared with the manager or other owners
+        assert!(!manager.is_approved_for_all(&manager.address, &operator));
+        assert!(!manager.is_approved_for_all(&Address::generate(&env), &operator));
+
+        manager.set_approval_for_all(&owner, &operator, &false);
+        assert!(!manager.is_approved_for_all(&owner, &operator));
+    }
+
+    #[test]
+    fn test_set_approval_for_all_requires_owner_auth() {
+        let env = Env::default();
+
+        let manager = DexPositionManagerClient::new(&env, &env.register(DexPositionManager, ()));
+        let owner = Address::generate(&env);
+        let operator = Address::generate(&env);
+
+        // The operator cannot approve itself on the owner's behalf
+        let result = manager
+            .mock_auths(&[MockAuth {
+                address: &operator,
+                invoke: &MockAuthInvoke {
+                    contract: &manager.address,
+                    fn_name: "set_approval_for_all",
+                    args: (owner.clone(), operator.clone(), true).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_set_approval_for_all(&owner, &operator, &true);
+        assert!(result.is_err());
+        assert!(!manager.is_approved_for_all(&owner, &operator));
+    }
+
+    #[test]
+    fn test_approved_address_collects_for_owner() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+
+        let approved = Address::generate(&env);
+        manager.approve(&approved, &id);
+        let (collected0, collected1) = manager.collect(
+            &approved,
+            &CollectParams {
+                position_id: id,
+                recipient: approved.clone(),
+                amount0_max: u128::MAX,
+                amount1_max: u128::MAX,
+                deadline: 1000,
+            },
+        );
+        assert_single_event(
+            &env,
+            &manager,
+            "fees_collected",
+            (id, approved.clone(), collected0, collected1).into_val(&env),
+        );
+
+        assert!(collected0 > 0 && collected1 > 0);
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        assert_eq!(token0_client.balance(&approved), collected0 as i128);
+        let position = manager.get_position(&id);
+        assert_eq!((position.tokens_owed_0, position.tokens_owed_1), (0, 0));
+    }
+
+    #[test]
+    fn test_operator_transfers_owner_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let operator = Address::generate(&env);
+        let receiver = Address::generate(&env);
+        manager.set_approval_for_all(&owner, &operator, &true);
+        manager.transfer_from(&operator, &owner, &receiver, &id);
+
+        assert_eq!(manager.owner_of(&id), receiver);
+        assert_eq!(manager.balance_of(&owner), 0);
+        assert_eq!(manager.balance_of(&receiver), 1);
+        // The operator was approved by the old owner, not the new one
+        assert!(manager
+            .try_transfer_from(&operator, &receiver, &owner, &id)
+            .is_err());
+    }
+
+    #[test]
+    fn test_unauthorized_caller_cannot_collect_or_transfer() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let stranger = Address::generate(&env);
+        let params = CollectParams {
+            position_id: id,
+            recipient: stranger.clone(),
+            amount0_max: u128::MAX,
+            amount1_max: u128::MAX,
+            deadline: 1000,
+        };
+        assert!(manager.try_collect(&stranger, &params).is_err());
+        assert!(manager
+            .try_transfer_from(&stranger, &owner, &stranger, &id)
+            .is_err());
+
+        // Being an operator for a different owner grants nothing here
+        let other = Address::generate(&env);
+        manager.set_approval_for_all(&other, &stranger, &true);
+        assert!(manager.try_collect(&stranger, &params).is_err());
+        assert_eq!(manager.owner_of(&id), owner);
+    }
+
+    #[test]
+    fn test_operator_rebalances_liquidity_without_taking_principal() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (from_id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let (to_id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let vault = Address::generate(&env);
+        fund(&env, &token0, &vault, 10_000_000);
+        fund(&env, &token1, &vault, 10_000_000);
+        manager.set_approval_for_all(&owner, &vault, &true);
+
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        let owner_before = (token0_client.balance(&owner), token1_client.balance(&owner));
+
+        // Pulling liquidity out credits the position, not the operator
+        let (amount0, amount1) = manager.decrease_liquidity(
+            &vault,
+            &DecreaseLiquidityParams {
+                position_id: from_id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        let position = manager.get_position(&from_id);
+        assert_eq!(position.liquidity, 0);
+        assert_eq!(position.tokens_owed_0, amount0 as u128);
+        assert_eq!(position.tokens_owed_1, amount1 as u128);
+        assert_eq!(token0_client.balance(&vault), 10_000_000);
+        assert_eq!(token1_client.balance(&vault), 10_000_000);
+
+        // Adding liquidity is paid by the operator
+        let to_liquidity = manager.get_position(&to_id).liquidity;
+        let (added, paid0, paid1) = manager.increase_liquidity(
+            &vault,
+            &IncreaseLiquidityParams {
+                position_id: to_id,
+                payer: vault.clone(),
+                amount0_desired: amount0,
+                amount1_desired: amount1,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        assert_eq!(manager.get_position(&to_id).liquidity, to_liquidity + added);
+        assert_eq!(token0_client.balance(&vault), 10_000_000 - paid0);
+        assert_eq!(token1_client.balance(&vault), 10_000_000 - paid1);
+
+        assert_eq!(
+            (token0_client.balance(&owner), token1_client.balance(&owner)),
+            owner_before
+        );
+        assert_eq!(manager.owner_of(&from_id), owner);
+        assert_eq!(manager.owner_of(&to_id), owner);
+    }
+
+    #[test]
+    fn test_revoked_operator_cannot_change_liquidity() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let operator = Address::generate(&env);
+        fund(&env, &token0, &operator, 10_000_000);
+        fund(&env, &token1, &operator, 10_000_000);
+        let decrease = DecreaseLiquidityParams {
+            position_id: id,
+            liquidity: 10_000,
+            amount0_min: 0,
+            amount1_min: 0,
+            deadline: 1000,
+        };
+        let increase = IncreaseLiquidityParams {
+            position_id: id,
+            payer: operator.clone(),
+            amount0_desired: 100_000,
+            amount1_desired: 100_000,
+            amount0_min: 0,
+            amount1_min: 0,
+            deadline: 1000,
+        };
+        manager.set_approval_for_all(&owner, &operator, &true);
+        manager.decrease_liquidity(&operator, &decrease);
+
+        manager.set_approval_for_all(&owner, &operator, &false);
+        assert!(manager
+            .try_decrease_liquidity(&operator, &decrease)
+            .is_err());
+        assert!(manager
+            .try_increase_liquidity(&operator, &increase)
+            .is_err());
+        assert_eq!(manager.get_position(&id).liquidity, liquidity - 10_000);
+
+        // A per-position approval works the same way
+        manager.approve(&operator, &id);
+        manager.increase_liquidity(&operator, &increase);
+        assert!(manager.get_position(&id).liquidity > liquidity - 10_000);
+    }
+
+    // === Safe Transfer Tests ===
+
+    /// Contract recipient that accepts or rejects positions on request
+    #[contract]
+    struct PositionReceiver;
+
+    #[contractimpl]
+    impl PositionReceiver {
+        pub fn set_accept(env: Env, accept: bool) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "accept"), &accept);
+        }
+
+        pub fn on_position_received(
+            env: Env,
+            operator: Address,
+            from: Address,
+            position_id: u32,
+            data: Bytes,
+        ) -> Symbol {
+            env.storage().instance().set(
+                &Symbol::new(&env, "received"),
+                &(operator, from, position_id, data),
+            );
+            let accept: bool = env
+                .storage()
+                .instance()
+                .get(&Symbol::new(&env, "accept"))
+                .unwrap_or(false);
+            if accept {
+                Symbol::new(&env, POSITION_RECEIVED)
+            } else {
+                Symbol::new(&env, "no_thanks")
+            }
+        }
+
+        pub fn received(env: Env) -> (Address, Address, u32, Bytes) {
+            env.storage()
+                .instance()
+                .get(&Symbol::new(&env, "received"))
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn test_safe_transfer_to_accepting_contract() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let operator = Address::generate(&env);
+        manager.set_approval_for_all(&owner, &operator, &true);
+        let receiver = PositionReceiverClient::new(&env, &env.register(PositionReceiver, ()));
+        receiver.set_accept(&true);
+        let data = Bytes::from_slice(&env, b"vault-7");
+        manager.safe_transfer_from(&operator, &owner, &receiver.address, &id, &data);
+
+        assert_eq!(manager.owner_of(&id), receiver.address);
+        assert_eq!(manager.balance_of(&receiver.address), 1);
+        // The hook saw who moved the position, whose it was, and the payload
+        assert_eq!(receiver.received(), (operator, owner, id, data));
+    }
+
+    #[test]
+    fn test_safe_transfer_to_rejecting_or_unaware_contract_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let data = Bytes::new(&env);
+
+        // Implements the hook but answers with the wrong value
+        let receiver = PositionReceiverClient::new(&env, &env.register(PositionReceiver, ()));
+        receiver.set_accept(&false);
+        assert!(manager
+            .try_safe_transfer_from(&owner, &owner, &receiver.address, &id, &data)
+            .is_err());
+
+        // Has no hook at all
+        let unaware = create_named_token(&env, "NOPE");
+        assert!(manager
+            .try_safe_transfer_from(&owner, &owner, &unaware.address, &id, &data)
+            .is_err());
+
+        assert_eq!(manager.owner_of(&id), owner);
+        assert_eq!(manager.balance_of(&owner), 1);
+
+        // Plain transfer_from still moves it without asking
+        manager.transfer_from(&owner, &owner, &unaware.address, &id);
+        assert_eq!(manager.owner_of(&id), unaware.address);
+    }
+
+    #[test]
+    fn test_safe_transfer_to_account_needs_no_hook() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let receiver = Address::generate(&env);
+        manager.safe_transfer_from(&owner, &owner, &receiver, &id, &Bytes::new(&env));
+        assert_eq!(manager.owner_of(&id), receiver);
+    }
+
+    // === Fee Accrual Tests ===
+
+    #[test]
+    fn test_poke_credits_swap_fees_and_collect_pays_owner() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token1, &trader, 1_000_000);
+        dex_pool_contract::Client::new(&env, &pool).swap(&trader, &false, &100_000, &0, &false);
+
+        let (expected0, expected1) = manager.uncollected_fees(&id);
+        assert!(expected1 > 0);
+        assert_eq!(manager.poke(&id), (expected0, expected1));
+        let position = manager.get_position(&id);
+        assert_eq!(
+            (position.tokens_owed_0, position.tokens_owed_1),
+            (expected0, expected1)
+        );
+        // Poking again finds nothing new
+        assert_eq!(manager.poke(&id), (expected0, expected1));
+
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        let balance1 = token1_client.balance(&owner);
+        let collected = manager.collect(
+            &owner,
+            &CollectParams {
+                position_id: id,
+                recipient: owner.clone(),
+                amount0_max: u128::MAX,
+                amount1_max: u128::MAX,
+                deadline: 1000,
+            },
+        );
+        assert_eq!(collected, (expected0, expected1));
+        assert_eq!(token1_client.balance(&owner), balance1 + expected1 as i128);
+    }
+
+    #[test]
+    fn test_collect_pays_only_the_positions_share_of_a_shared_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        for user in [&alice, &bob] {
+            fund(&env, &token0, user, 10_000_000);
+            fund(&env, &token1, user, 10_000_000);
+        }
+        let (alice_id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &alice));
+        let (bob_id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &bob));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token1, &trader, 1_000_000);
+        dex_pool_contract::Client::new(&env, &pool).swap(&trader, &false, &100_000, &0, &false);
+
+        // Collect without a prior poke still accrues first, and leaves bob's share alone
+        let params = |id: u32, recipient: &Address| CollectParams {
+            position_id: id,
+            recipient: recipient.clone(),
+            amount0_max: u128::MAX,
+            amount1_max: u128::MAX,
+            deadline: 1000,
+        };
+        let bob_fees = manager.uncollected_fees(&bob_id);
+        let (_, alice_fees1) = manager.collect(&alice, &params(alice_id, &alice));
+        assert!(alice_fees1 > 0);
+        assert_eq!(manager.uncollected_fees(&bob_id), bob_fees);
+        assert_eq!(manager.collect(&bob, &params(bob_id, &bob)), bob_fees);
+    }
+
+    #[test]
+    fn test_decrease_liquidity_credits_fees_with_principal() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token1, &trader, 1_000_000);
+        dex_pool_contract::Client::new(&env, &pool).swap(&trader, &false, &100_000, &0, &false);
+
+        let (fees0, fees1) = manager.uncollected_fees(&id);
+        let (amount0, amount1) = manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity: liquidity / 2,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        assert_single_event(
+            &env,
+            &manager,
+            "liquidity_decreased",
+            (id, liquidity / 2, amount0, amount1).into_val(&env),
+        );
+
+        let position = manager.get_position(&id);
+        assert_eq!(position.tokens_owed_0, fees0 + amount0 as u128);
+        assert_eq!(position.tokens_owed_1, fees1 + amount1 as u128);
+        // The checkpoint moved, so the same fees are not counted twice
+        assert_eq!(
+            manager.uncollected_fees(&id),
+            (position.tokens_owed_0, position.tokens_owed_1)
+        );
+    }
+
+    #[test]
+    fn test_full_decrease_credits_fees_of_range_minted_after_fee_growth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token0, &trader, 1_000_000);
+        fund(&env, &token1, &trader, 1_000_000);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        pool_client.swap(&trader, &false, &100_000, &0, &false);
+        pool_client.swap(&trader, &true, &100_000, &0, &false);
+
+        // Its ticks are initialized with non-zero fee growth outside
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.tick_lower = -1200;
+        params.tick_upper = 1200;
+        let (id, liquidity, _, _) = manager.mint(&params);
+
+        pool_client.swap(&trader, &false, &100_000, &0, &false);
+        pool_client.swap(&trader, &true, &100_000, &0, &false);
+        let (fees0, fees1) = manager.uncollected_fees(&id);
+        assert!(fees0 > 0 && fees1 > 0);
+
+        // Removing all of it deletes the range's ticks in the pool
+        let (amount0, amount1) = manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+
+        let position = manager.get_position(&id);
+        assert_eq!(position.liquidity, 0);
+        assert_eq!(position.tokens_owed_0, fees0 + amount0 as u128);
+        assert_eq!(position.tokens_owed_1, fees1 + amount1 as u128);
+    }
+
+    #[test]
+    fn test_collect_caps_partial_all_and_none() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token0, &trader, 1_000_000);
+        fund(&env, &token1, &trader, 1_000_000);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        pool_client.swap(&trader, &true, &100_000, &0, &false);
+        pool_client.swap(&trader, &false, &100_000, &0, &false);
+        manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity: liquidity / 2,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        let owed = manager.get_position(&id);
+        let (owed0, owed1) = (owed.tokens_owed_0, owed.tokens_owed_1);
+        assert!(owed0 > 1 && owed1 > 1);
+
+        let recipient = Address::generate(&env);
+        let collect = |amount0_max: u128, amount1_max: u128| {
+            manager.collect(
+                &owner,
+                &CollectParams {
+                    position_id: id,
+                    recipient: recipient.clone(),
+                    amount0_max,
+                    amount1_max,
+                    deadline: 1000,
+                },
+            )
+        };
+        let tokens_owed = || {
+            let position = manager.get_position(&id);
+            (position.tokens_owed_0, position.tokens_owed_1)
+        };
+
+        // Partial caps take exactly the cap
+        assert_eq!(collect(owed0 / 2, 1), (owed0 / 2, 1));
+        assert_eq!(tokens_owed(), (owed0 - owed0 / 2, owed1 - 1));
+
+        // COLLECT_ALL takes the rest of token0, 0 leaves token1 alone
+        assert_eq!(collect(COLLECT_ALL, 0), (owed0 - owed0 / 2, 0));
+        assert_eq!(tokens_owed(), (0, owed1 - 1));
+
+        // Both at COLLECT_ALL drains what is left
+        assert_eq!(collect(COLLECT_ALL, COLLECT_ALL), (0, owed1 - 1));
+        assert_eq!(tokens_owed(), (0, 0));
+
+        // Nothing owed and nothing requested are both no-ops
+        assert_eq!(collect(COLLECT_ALL, COLLECT_ALL), (0, 0));
+        assert_eq!(collect(0, 0), (0, 0));
+
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        assert_eq!(token0_client.balance(&recipient), owed0 as i128);
+        assert_eq!(token1_client.balance(&recipient), owed1 as i128);
+    }
+
+    // === Token Metadata Tests ===
+
+    /// Minimal token with a settable symbol, enough for the factory, pool and manager
+    #[contract]
+    struct NamedToken;
+
+    #[contractimpl]
+    impl NamedToken {
+        pub fn set_symbol(env: Env, symbol: String) {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "symbol"), &symbol);
+        }
+
+        pub fn symbol(env: Env) -> String {
+            env.storage()
+                .instance()
+                .get(&Symbol::new(&env, "symbol"))
+                .unwrap()
+        }
+
+        pub fn decimals(_env: Env) -> u32 {
+            7
+        }
+
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), from.clone());
+            env.storage().persistent().set(&from, &(balance - amount));
+            Self::mint(env, to, amount);
+        }
+    }
+
+    fn create_named_token<'a>(env: &Env, symbol: &str) -> NamedTokenClient<'a> {
+        let token = NamedTokenClient::new(env, &env.register(NamedToken, ()));
+        token.set_symbol(&String::from_str(env, symbol));
+        token
+    }
+
+    #[test]
+    fn test_token_metadata_describes_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, _, _) = setup(&env);
+        let usdc = create_named_token(&env, "USDC");
+        let xlm = create_named_token(&env, "XLM");
+        let (token0, token1) = if usdc.address < xlm.address {
+            (usdc, xlm)
+        } else {
+            (xlm, usdc)
+        };
+        let pool = factory.create_pool(
+            &Address::generate(&env),
+            &token0.address,
+            &token1.address,
+            &3000,
+            &Q96,
+        );
+
+        let owner = Address::generate(&env);
+        token0.mint(&owner, &10_000_000);
+        token1.mint(&owner, &10_000_000);
+        let params = mint_params(&token0.address, &token1.address, 3000, &owner);
+        let (id, liquidity, _, _) = manager.mint(&params);
+
+        let metadata = manager.token_metadata(&id);
+        let name = if token0.symbol() == String::from_str(&env, "USDC") {
+            "LP USDC/XLM 0.3% [-600,600]"
+        } else {
+            "LP XLM/USDC 0.3% [-600,600]"
+        };
+        assert_eq!(metadata.name, String::from_str(&env, name));
+        assert_eq!(metadata.pool, pool);
+        assert_eq!(metadata.token0, token0.address);
+        assert_eq!(metadata.token1, token1.address);
+        assert_eq!(metadata.symbol0, token0.symbol());
+        assert_eq!(metadata.symbol1, token1.symbol());
+        assert_eq!(metadata.fee, 3000);
+        assert_eq!((metadata.tick_lower, metadata.tick_upper), (-600, 600));
+        assert!(metadata.in_range);
+        assert_eq!(metadata.liquidity, liquidity);
+    }
+
+    #[test]
+    fn test_token_metadata_formats_fee_and_out_of_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, _, _) = setup(&env);
+        let token_a = create_named_token(&env, "AAA");
+        let token_b = create_named_token(&env, "AAA");
+        factory.create_pool(
+            &Address::generate(&env),
+            &token_a.address,
+            &token_b.address,
+            &500,
+            &Q96,
+        );
+        let (token0, token1) = if token_a.address < token_b.address {
+            (token_a.address, token_b.address)
+        } else {
+            (token_b.address, token_a.address)
+        };
+
+        let owner = Address::generate(&env);
+        NamedTokenClient::new(&env, &token0).mint(&owner, &10_000_000);
+        NamedTokenClient::new(&env, &token1).mint(&owner, &10_000_000);
+        let mut params = mint_params(&token0, &token1, 500, &owner);
+        params.tick_lower = 100;
+        params.tick_upper = 1_000;
+        let (id, _, _, _) = manager.mint(&params);
+
+        let metadata = manager.token_metadata(&id);
+        let name = String::from_str(&env, "LP AAA/AAA 0.05% [100,1000]");
+        assert_eq!(metadata.name, name);
+        assert!(!metadata.in_range);
+    }
+
+    #[test]
+    fn test_token_metadata_of_burned_position_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        manager.collect(
+            &owner,
+            &CollectParams {
+                position_id: id,
+                recipient: owner.clone(),
+                amount0_max: u128::MAX,
+                amount1_max: u128::MAX,
+                deadline: 1000,
+            },
+        );
+        manager.burn(&id);
+
+        assert!(manager.try_token_metadata(&id).is_err());
+        assert!(manager.try_token_metadata(&(id + 1)).is_err());
+    }
+
+    // === Exit Position Tests ===
+
+    #[test]
+    fn test_exit_position_pays_principal_and_fees() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        // An identical position shows what removing the liquidity alone returns
+        let (twin, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token1, &trader, 1_000_000);
+        dex_pool_contract::Client::new(&env, &pool).swap(&trader, &false, &100_000, &0, &false);
+
+        // Fees accrued before the exit are paid out with the principal
+        let (fees0, fees1) = manager.poke(&id);
+        assert!(fees1 > 0);
+        let (principal0, principal1) = manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: twin,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+
+        let recipient = Address::generate(&env);
+        let (amount0, amount1) = manager.exit_position(&id, &0, &0, &recipient, &1000);
+
+        assert_eq!(amount0, principal0 + fees0 as i128);
+        assert_eq!(amount1, principal1 + fees1 as i128);
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        assert_eq!(token0_client.balance(&recipient), amount0);
+        assert_eq!(token1_client.balance(&recipient), amount1);
+        assert!(manager.try_get_position(&id).is_err());
+        assert!(manager.try_owner_of(&id).is_err());
+        assert_eq!(manager.balance_of(&owner), 1);
+    }
+
+    #[test]
+    fn test_exit_position_slippage_keeps_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, amount0, _) =
+            manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        // Removing liquidity can't return more than was deposited
+        assert!(manager
+            .try_exit_position(&id, &(amount0 + 1), &0, &owner, &1000)
+            .is_err());
+        assert_eq!(manager.get_position(&id).liquidity, liquidity);
+        assert_eq!(manager.owner_of(&id), owner);
+    }
+
+    #[test]
+    fn test_exit_position_needs_only_one_owner_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token1, &trader, 1_000_000);
+        dex_pool_contract::Client::new(&env, &pool).swap(&trader, &false, &100_000, &0, &false);
+
+        // One entry covers the decrease, collect and burn done inside
+        let recipient = Address::generate(&env);
+        let (amount0, amount1) = manager
+            .mock_auths(&[MockAuth {
+                address: &owner,
+                invoke: &MockAuthInvoke {
+                    contract: &manager.address,
+                    fn_name: "exit_position",
+                    args: (id, 0_i128, 0_i128, recipient.clone(), 1000_u64).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .exit_position(&id, &0, &0, &recipient, &1000);
+
+        assert!(amount0 > 0 && amount1 > 0);
+        let paid0 = token::Client::new(&env, &token0).balance(&recipient);
+        let paid1 = token::Client::new(&env, &token1).balance(&recipient);
+        assert_eq!((paid0, paid1), (amount0, amount1));
+        assert!(manager.find_position(&id).is_none());
+    }
+
+    #[test]
+    fn test_burn_with_sweep_clears_dust_that_blocks_burn() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token0, &trader, 1_000_000);
+        dex_pool_contract::Client::new(&env, &pool).swap(&trader, &true, &100_000, &0, &false);
+
+        // Remove the liquidity and collect all but one unit of token0
+        manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        let owed0 = manager.get_position(&id).tokens_owed_0;
+        manager.collect(
+            &owner,
+            &CollectParams {
+                position_id: id,
+                recipient: owner.clone(),
+                amount0_max: owed0 - 1,
+                amount1_max: u128::MAX,
+                deadline: 1000,
+            },
+        );
+        let position = manager.get_position(&id);
+        assert_eq!((position.tokens_owed_0, position.tokens_owed_1), (1, 0));
+
+        // The strict path refuses the dust
+        assert!(manager.try_burn(&id).is_err());
+        assert_eq!(manager.owner_of(&id), owner);
+
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let before = token0_client.balance(&owner);
+        let swept = manager
+            .mock_auths(&[MockAuth {
+                address: &owner,
+                invoke: &MockAuthInvoke {
+                    contract: &manager.address,
+                    fn_name: "burn_with_sweep",
+                    args: (id,).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .burn_with_sweep(&id);
+        assert_eq!(swept, (1, 0));
+        // The pool paid everything, so nothing was forfeited
+        let forfeit_topic: Vec<Val> = (Symbol::new(&env, "owed_forfeited"),).into_val(&env);
+        let events = contract_events(&env, &manager.address);
+        assert!(events.iter().all(|(_, topics, _)| topics != forfeit_topic));
+        assert_eq!(token0_client.balance(&owner), before + 1);
+        assert!(manager.try_get_position(&id).is_err());
+        assert_eq!(manager.balance_of(&owner), 0);
+    }
+
+    #[test]
+    fn test_burn_with_sweep_rejects_position_with_liquidity() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        assert!(manager.try_burn_with_sweep(&id).is_err());
+        assert_eq!(manager.get_position(&id).liquidity, liquidity);
+    }
+
+    // === Deadline Tests ===
+
+    const NOW: u64 = 10_000;
+
+    /// (deadline, accepted) around a ledger time of NOW
+    fn deadline_cases() -> [(u64, bool); 5] {
+        [
+            (0, true),
+            (NOW - 1, false),
+            (NOW, true),
+            (NOW + MAX_DEADLINE_WINDOW, true),
+            (NOW + MAX_DEADLINE_WINDOW + 1, false),
+        ]
+    }
+
+    #[test]
+    fn test_mint_deadlines() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(NOW);
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+
+        for (deadline, accepted) in deadline_cases() {
+            let mut params = mint_params(&token0, &token1, 3000, &owner);
+            params.deadline = deadline;
+            assert_eq!(manager.try_mint(&params).is_ok(), accepted, "{}", deadline);
+        }
+        assert_eq!(manager.balance_of(&owner), 3);
+    }
+
+    #[test]
+    fn test_increase_liquidity_deadlines() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(NOW);
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.deadline = 0;
+        let (id, _, _, _) = manager.mint(&params);
+
+        for (deadline, accepted) in deadline_cases() {
+            let result = manager.try_increase_liquidity(
+                &owner,
+                &IncreaseLiquidityParams {
+                    position_id: id,
+                    payer: owner.clone(),
+                    amount0_desired: 100_000,
+                    amount1_desired: 100_000,
+                    amount0_min: 0,
+                    amount1_min: 0,
+                    deadline,
+                },
+            );
+            assert_eq!(result.is_ok(), accepted, "{}", deadline);
+        }
+    }
+
+    #[test]
+    fn test_decrease_liquidity_deadlines() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(NOW);
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.deadline = 0;
+        let (id, liquidity, _, _) = manager.mint(&params);
+
+        for (deadline, accepted) in deadline_cases() {
+            let result = manager.try_decrease_liquidity(
+                &owner,
+                &DecreaseLiquidityParams {
+                    position_id: id,
+                    liquidity: 10_000,
+                    amount0_min: 0,
+                    amount1_min: 0,
+                    deadline,
+                },
+            );
+            assert_eq!(result.is_ok(), accepted, "{}", deadline);
+        }
+        assert_eq!(manager.get_position(&id).liquidity, liquidity - 30_000);
+    }
+
+    #[test]
+    fn test_collect_deadlines() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(NOW);
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.deadline = 0;
+        let (id, liquidity, _, _) = manager.mint(&params);
+        manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 0,
+            },
+        );
+
+        for (deadline, accepted) in deadline_cases() {
+            let result = manager.try_collect(
+                &owner,
+                &CollectParams {
+                    position_id: id,
+                    recipient: owner.clone(),
+                    amount0_max: 1,
+                    amount1_max: 1,
+                    deadline,
+                },
+            );
+            assert_eq!(result.is_ok(), accepted, "{}", deadline);
+        }
+    }
+
+    // === Rebalance Tests ===
+
+    #[test]
+    fn test_rebalance_moves_everything_into_new_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token1, &trader, 1_000_000);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        pool_client.swap(&trader, &false, &100_000, &0, &false);
+
+        let token0_client = soroban_sdk::token::Client::new(&env, &token0);
+        let token1_client = soroban_sdk::token::Client::new(&env, &token1);
+        let before0 = token0_client.balance(&owner);
+        let before1 = token1_client.balance(&owner);
+        // Principal plus fees released by the old range
+        let (value0, value1) = manager.get_position_amounts(&id);
+
+        let (new_id, liquidity) = manager.rebalance(&id, &-1200, &1200, &0, &0, &1000);
+
+        // The new range holds the liquidity those tokens buy at the current price
+        let sqrt_price = pool_client.get_state().sqrt_price_x96;
+        let expected = dex_math::get_liquidity_for_amounts(
+            &env,
+            sqrt_price,
+            dex_math::get_sqrt_ratio_at_tick(&env, -1200),
+            dex_math::get_sqrt_ratio_at_tick(&env, 1200),
+            value0 as u128,
+            value1 as u128,
+        );
+        assert_eq!(liquidity, expected);
+        let position = manager.get_position(&new_id);
+        assert_eq!((position.tick_lower, position.tick_upper), (-1200, 1200));
+        assert_eq!(position.liquidity, liquidity);
+        assert_eq!(position.pool, pool);
+        assert_eq!(manager.owner_of(&new_id), owner);
+        assert!(manager.try_get_position(&id).is_err());
+        assert_eq!(manager.balance_of(&owner), 1);
+
+        // What the new range did not take went back to the owner; deposits
+        // round up and the valuation rounds down, so at most a unit is lost
+        let (new0, new1) = manager.get_position_amounts(&new_id);
+        let returned0 = token0_client.balance(&owner) - before0;
+        let returned1 = token1_client.balance(&owner) - before1;
+        assert!(returned0 >= 0 && returned1 >= 0);
+        assert!(value0 - 1 <= returned0 + new0 && returned0 + new0 <= value0);
+        assert!(value1 - 1 <= returned1 + new1 && returned1 + new1 <= value1);
+        assert_eq!(token0_client.balance(&manager.address), 0);
+        assert_eq!(token1_client.balance(&manager.address), 0);
+    }
+
+    #[test]
+    fn test_rebalance_slippage_keeps_old_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, amount0, _) =
+            manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        // The new range can't take more than the old one released
+        assert!(manager
+            .try_rebalance(&id, &-1200, &1200, &(amount0 + 1), &0, &1000)
+            .is_err());
+        assert_eq!(manager.get_position(&id).liquidity, liquidity);
+        assert_eq!(manager.owner_of(&id), owner);
+    }
+
+    // === Multicall Tests ===
+
+    #[test]
+    fn test_multicall_decrease_collect_burn() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let calls = Vec::from_array(
+            &env,
+            [
+                ManagerCall::DecreaseLiquidity(
+                    owner.clone(),
+                    DecreaseLiquidityParams {
+                        position_id: id,
+                        liquidity,
+                        amount0_min: 0,
+                        amount1_min: 0,
+                        deadline: 1000,
+                    },
+                ),
+                ManagerCall::Collect(
+                    owner.clone(),
+                    CollectParams {
+                        position_id: id,
+                        recipient: owner.clone(),
+                        amount0_max: u128::MAX,
+                        amount1_max: u128::MAX,
+                        deadline: 1000,
+                    },
+                ),
+                ManagerCall::Burn(id),
+            ],
+        );
+        // The owner authorizes the batch once, not once per call
+        let results = manager
+            .mock_auths(&[MockAuth {
+                address: &owner,
+                invoke: &MockAuthInvoke {
+                    contract: &manager.address,
+                    fn_name: "multicall",
+                    args: (calls.clone(),).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .multicall(&calls);
+
+        assert_eq!(results.len(), 3);
+        let (amount0, amount1) = match results.get(0).unwrap() {
+            ManagerResult::DecreaseLiquidity(amount0, amount1) => (amount0, amount1),
+            other => panic!("unexpected result {:?}", other),
+        };
+        assert_eq!(
+            results.get(1).unwrap(),
+            ManagerResult::Collect(amount0 as u128, amount1 as u128)
+        );
+        assert_eq!(results.get(2).unwrap(), ManagerResult::Burn);
+        assert!(manager.try_get_position(&id).is_err());
+        assert_eq!(manager.balance_of(&owner), 0);
+    }
+
+    #[test]
+    fn test_multicall_failure_reverts_whole_batch() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let receiver = Address::generate(&env);
+
+        // The burn fails because half the liquidity is still in the position
+        let calls = Vec::from_array(
+            &env,
+            [
+                ManagerCall::DecreaseLiquidity(
+                    owner.clone(),
+                    DecreaseLiquidityParams {
+                        position_id: id,
+                        liquidity: liquidity / 2,
+                        amount0_min: 0,
+                        amount1_min: 0,
+                        deadline: 1000,
+                    },
+                ),
+                ManagerCall::Burn(id),
+                ManagerCall::TransferFrom(owner.clone(), owner.clone(), receiver, id),
+            ],
+        );
+        assert!(manager.try_multicall(&calls).is_err());
+
+        let position = manager.get_position(&id);
+        assert_eq!(position.liquidity, liquidity);
+        assert_eq!((position.tokens_owed_0, position.tokens_owed_1), (0, 0));
+        assert_eq!(manager.owner_of(&id), owner);
+    }
+
+    #[test]
+    fn test_multicall_rejects_empty_and_oversized_batches() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let manager = DexPositionManagerClient::new(&env, &env.register(DexPositionManager, ()));
+        assert!(manager.try_multicall(&Vec::new(&env)).is_err());
+
+        let mut calls = Vec::new(&env);
+        for id in 0..=MAX_MULTICALL_CALLS {
+            calls.push_back(ManagerCall::Burn(id));
+        }
+        assert!(manager.try_multicall(&calls).is_err());
+    }
+
+    // === Owner Index Integrity Tests ===
+
+    #[test]
+    fn test_missing_owner_index_refuses_removal() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id1, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let (id2, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let (id3, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        env.as_contract(&manager.address, || {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::PositionIndex(id2));
+        });
+
+        // Treating the missing index as slot 0 would have dropped id1 instead
+        let other = Address::generate(&env);
+        assert!(manager
+            .try_transfer_from(&owner, &owner, &other, &id2)
+            .is_err());
+        assert_eq!(
+            manager.positions_of_paginated(&owner, &0, &50),
+            vec![&env, id1, id2, id3]
+        );
+        assert_eq!(manager.owner_of(&id2), owner);
+
+        // Positions with intact indexes still move
+        manager.transfer_from(&owner, &owner, &other, &id1);
+        assert_eq!(
+            manager.positions_of_paginated(&owner, &0, &50),
+            vec![&env, id3, id2]
+        );
+    }
+
+    #[test]
+    fn test_mismatched_owner_index_refuses_removal() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id1, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let (id2, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        // Point id2 at id1's slot, and at a slot past the end
+        for bad_index in [0u32, 7] {
+            env.as_contract(&manager.address, || {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::PositionIndex(id2), &bad_index);
+            });
+            assert!(manager
+                .try_exit_position(&id2, &0, &0, &owner, &1000)
+                .is_err());
+            assert!(manager
+                .try_transfer_from(&owner, &owner, &Address::generate(&env), &id2)
+                .is_err());
+            assert_eq!(
+                manager.positions_of_paginated(&owner, &0, &50),
+                vec![&env, id1, id2]
+            );
+            assert_eq!(manager.owner_of(&id1), owner);
+        }
+    }
+
+    // === Owner Query Tests ===
+
+    #[test]
+    fn test_positions_with_data_paginated() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let mut ids = Vec::new(&env);
+        for _ in 0..3 {
+            let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+            ids.push_back(id);
+        }
+
+        let page = manager.positions_with_data_paginated(&owner, &0, &2);
+        assert_eq!(page.len(), 2);
+        for (index, (id, position)) in page.iter().enumerate() {
+            assert_eq!(id, ids.get(index as u32).unwrap());
+            assert_eq!(position, manager.get_position(&id));
+            assert_eq!(position.pool, pool);
+        }
+        let rest = manager.positions_with_data_paginated(&owner, &2, &25);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest.get(0).unwrap().0, ids.get(2).unwrap());
+
+        // Burning the first position drops it from the pages
+        let first = ids.get(0).unwrap();
+        manager.exit_position(&first, &0, &0, &owner, &1000);
+        let page = manager.positions_with_data_paginated(&owner, &0, &25);
+        assert_eq!(page.len(), 2);
+        assert!(page.iter().all(|(id, _)| id != first));
+        let stranger = Address::generate(&env);
+        let none = manager.positions_with_data_paginated(&stranger, &0, &25);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_positions_of_pool_paginated() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let creator = Address::generate(&env);
+        let pool_3000 = factory.create_pool(&creator, &token0, &token1, &3000, &Q96);
+        let pool_500 = factory.create_pool(&creator, &token0, &token1, &500, &Q96);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        for owner in [&alice, &bob] {
+            fund(&env, &token0, owner, 10_000_000);
+            fund(&env, &token1, owner, 10_000_000);
+        }
+        let (id1, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &alice));
+        let (id2, _, _, _) = manager.mint(&mint_params(&token0, &token1, 500, &alice));
+        let (id3, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &bob));
+        let (id4, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &alice));
+
+        // Each pool lists its own positions, whoever owns them
+        assert_eq!(manager.pool_position_count(&pool_3000), 3);
+        assert_eq!(manager.pool_position_count(&pool_500), 1);
+        assert_eq!(
+            manager.positions_of_pool_paginated(&pool_3000, &0, &50),
+            vec![&env, id1, id3, id4]
+        );
+        assert_eq!(
+            manager.positions_of_pool_paginated(&pool_500, &0, &50),
+            vec![&env, id2]
+        );
+        assert_eq!(
+            manager.positions_of_pool_paginated(&pool_3000, &1, &1),
+            vec![&env, id3]
+        );
+
+        // Burning the first position moves the last one into its slot
+        manager.exit_position(&id1, &0, &0, &alice, &1000);
+        assert_eq!(manager.pool_position_count(&pool_3000), 2);
+        assert_eq!(
+            manager.positions_of_pool_paginated(&pool_3000, &0, &50),
+            vec![&env, id4, id3]
+        );
+        assert_eq!(
+            manager.positions_of_pool_paginated(&pool_500, &0, &50),
+            vec![&env, id2]
+        );
+
+        // Emptying a pool's list leaves nothing behind
+        manager.exit_position(&id2, &0, &0, &alice, &1000);
+        assert_eq!(manager.pool_position_count(&pool_500), 0);
+        assert!(manager
+            .positions_of_pool_paginated(&pool_500, &0, &50)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_all_positions_paginated_and_token_by_index() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let carol = Address::generate(&env);
+        for owner in [&alice, &bob, &carol] {
+            fund(&env, &token0, owner, 10_000_000);
+            fund(&env, &token1, owner, 10_000_000);
+        }
+        let mut ids = Vec::new(&env);
+        for owner in [&alice, &bob, &carol, &alice, &bob] {
+            let (id, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, owner));
+            ids.push_back(id);
+        }
+        assert_eq!(manager.all_positions_paginated(&0, &50), ids);
+
+        // Burn bob's first position; the last one fills its slot
+        let burned = ids.get(1).unwrap();
+        manager.exit_position(&burned, &0, &0, &bob, &1000);
+        assert_eq!(manager.total_supply(), 4);
+
+        let all = manager.all_positions_paginated(&0, &50);
+        assert_eq!(all.len(), 4);
+        assert!(!all.contains(burned));
+        for id in ids.iter().filter(|id| *id != burned) {
+            assert!(all.contains(id));
+        }
+
+        // Pages and single lookups agree, with no gaps
+        let mut paged = manager.all_positions_paginated(&0, &3);
+        paged.append(&manager.all_positions_paginated(&3, &3));
+        assert_eq!(paged, all);
+        for index in 0..4 {
+            assert_eq!(manager.token_by_index(&index), all.get(index));
+        }
+        assert_eq!(manager.token_by_index(&4), None);
+        assert!(manager.all_positions_paginated(&4, &50).is_empty());
+    }
+
+    // === Uncollected Fees Tests ===
+
+    #[test]
+    fn test_uncollected_fees_of_matches_individual_previews() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let creator = Address::generate(&env);
+        let pool_3000 = factory.create_pool(&creator, &token0, &token1, &3000, &Q96);
+        let pool_500 = factory.create_pool(&creator, &token0, &token1, &500, &Q96);
+
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id1, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let (id2, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let (id3, _, _, _) = manager.mint(&mint_params(&token0, &token1, 500, &owner));
+
+        // Fresh positions have nothing to collect
+        assert_eq!(manager.uncollected_fees_of(&owner, &0, &20), (0, 0, 3));
+
+        // Accrue fees in both tokens and both pools
+        let trader = Address::generate(&env);
+        fund(&env, &token0, &trader, 1_000_000);
+        fund(&env, &token1, &trader, 1_000_000);
+        for pool in [&pool_3000, &pool_500] {
+            let pool_client = dex_pool_contract::Client::new(&env, pool);
+            pool_client.swap(&trader, &false, &10_000, &0, &false);
+            pool_client.swap(&trader, &true, &5_000, &0, &false);
+        }
+
+        let (fees1_0, fees1_1) = manager.uncollected_fees(&id1);
+        let (fees2_0, fees2_1) = manager.uncollected_fees(&id2);
+        let (fees3_0, fees3_1) = manager.uncollected_fees(&id3);
+
+        assert!(fees1_0 > 0 && fees1_1 > 0);
+        assert!(fees3_0 > 0 && fees3_1 > 0);
+        // Identical positions in the same pool earn identical fees
+        assert_eq!((fees1_0, fees1_1), (fees2_0, fees2_1));
+
+        let (total0, total1, counted) = manager.uncollected_fees_of(&owner, &0, &20);
+        assert_eq!(counted, 3);
+        assert_eq!(total0, fees1_0 + fees2_0 + fees3_0);
+        assert_eq!(total1, fees1_1 + fees2_1 + fees3_1);
+    }
+
+    #[test]
+    fn test_uncollected_fees_of_pagination() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let creator = Address::generate(&env);
+        let pool = factory.create_pool(&creator, &token0, &token1, &3000, &Q96);
+
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id1, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        let (id2, _, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        let trader = Address::generate(&env);
+        fund(&env, &token1, &trader, 1_000_000);
+        dex_pool_contract::Client::new(&env, &pool).swap(&trader, &false, &10_000, &0, &false);
+
+        let (fees1_0, fees1_1) = manager.uncollected_fees(&id1);
+        let (fees2_0, fees2_1) = manager.uncollected_fees(&id2);
+
+        assert_eq!(manager.uncollected_fees_of(&owner, &0, &1), (fees1_0, fees1_1, 1));
+        assert_eq!(manager.uncollected_fees_of(&owner, &1, &1), (fees2_0, fees2_1, 1));
+        // Past the end and unknown owners count nothing
+        assert_eq!(manager.uncollected_fees_of(&owner, &2, &20), (0, 0, 0));
+        assert_eq!(manager.uncollected_fees_of(&owner, &u32::MAX, &20), (0, 0, 0));
+        assert_eq!(
+            manager.uncollected_fees_of(&Address::generate(&env), &0, &20),
+            (0, 0, 0)
+        );
+    }
+
+    // === Position Valuation Tests ===
+
+    #[test]
+    fn test_position_amounts_match_full_decrease() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        // Move the price and earn fees in both tokens
+        let trader = Address::generate(&env);
+        fund(&env, &token0, &trader, 1_000_000);
+        fund(&env, &token1, &trader, 1_000_000);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        pool_client.swap(&trader, &false, &100_000, &0, &false);
+        pool_client.swap(&trader, &true, &30_000, &0, &false);
+
+        let (value0, value1) = manager.get_position_amounts(&id);
+        let (fees0, fees1) = manager.uncollected_fees(&id);
+        assert!(fees0 > 0 && fees1 > 0);
+
+        let (amount0, amount1) = manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        assert_eq!(value0, amount0 + fees0 as i128);
+        assert_eq!(value1, amount1 + fees1 as i128);
+        let position = manager.get_position(&id);
+        assert_eq!(value0, position.tokens_owed_0 as i128);
+        assert_eq!(value1, position.tokens_owed_1 as i128);
+
+        // With no liquidity left the value is just what is owed
+        assert_eq!(manager.get_position_amounts(&id), (value0, value1));
+    }
+
+    #[test]
+    fn test_position_amounts_out_of_range_is_one_token() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.tick_lower = 600;
+        params.tick_upper = 1200;
+        let (id, liquidity, _, _) = manager.mint(&params);
+
+        // Above the price the range holds only token0
+        let (value0, value1) = manager.get_position_amounts(&id);
+        assert_eq!(value1, 0);
+        let (amount0, amount1) = manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        assert_eq!((value0, value1), (amount0, amount1));
+
+        manager.burn_with_sweep(&id);
+        assert!(manager.try_get_position_amounts(&id).is_err());
+    }
+
+    #[test]
+    fn test_preview_mint_and_increase_match_actual() {
+        let ranges = [(-600, 600), (-6000, -1200), (1200, 6000), (-60, 60)];
+
+        for tick in [-3000, -300, 0, 450, 3000] {
+            let env = Env::default();
+            env.mock_all_auths();
+
+            let (factory, manager, token0, token1) = setup(&env);
+            let sqrt_price = dex_math::get_sqrt_ratio_at_tick(&env, tick) + 12_345;
+            let creator = Address::generate(&env);
+            factory.create_pool(&creator, &token0, &token1, &3000, &sqrt_price);
+            let owner = Address::generate(&env);
+            fund(&env, &token0, &owner, 100_000_000);
+            fund(&env, &token1, &owner, 100_000_000);
+
+            for (tick_lower, tick_upper) in ranges {
+                let mut params = mint_params(&token0, &token1, 3000, &owner);
+                params.tick_lower = tick_lower;
+                params.tick_upper = tick_upper;
+                params.amount1_desired = 700_001;
+
+                let preview = manager.preview_mint(&params);
+                let (id, liquidity, amount0, amount1) = manager.mint(&params);
+                assert_eq!(preview, (liquidity, amount0, amount1));
+
+                let increase = IncreaseLiquidityParams {
+                    position_id: id,
+                    payer: owner.clone(),
+                    amount0_desired: 333_333,
+                    amount1_desired: 250_000,
+                    amount0_min: 0,
+                    amount1_min: 0,
+                    deadline: 1000,
+                };
+                let preview = manager.preview_increase(&increase);
+                assert_eq!(preview, manager.increase_liquidity(&owner, &increase));
+            }
+        }
+    }
+
+    #[test]
+    fn test_preview_mint_missing_pool_uses_initial_price() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (_, manager, token0, token1) = setup(&env);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        assert!(manager.try_preview_mint(&params).is_err());
+
+        params.sqrt_price_x96 = Some(dex_math::get_sqrt_ratio_at_tick(&env, 250));
+        let preview = manager.preview_mint(&params);
+        let (_, liquidity, amount0, amount1) = manager.mint(&params);
+        assert_eq!(preview, (liquidity, amount0, amount1));
+    }
+
+    #[test]
+    fn test_find_position_and_owner_for_missing_ids() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+
+        // Never minted
+        assert_eq!(manager.find_position(&1), None);
+        assert_eq!(manager.find_owner(&1), None);
+        assert!(manager.try_get_position(&1).is_err());
+        assert!(manager.try_owner_of(&1).is_err());
+
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+        assert_eq!(manager.find_position(&id), Some(manager.get_position(&id)));
+        assert_eq!(manager.find_owner(&id), Some(owner.clone()));
+
+        // Burned
+        manager.decrease_liquidity(
+            &owner,
+            &DecreaseLiquidityParams {
+                position_id: id,
+                liquidity,
+                amount0_min: 0,
+                amount1_min: 0,
+                deadline: 1000,
+            },
+        );
+        manager.burn_with_sweep(&id);
+        assert_eq!(manager.find_position(&id), None);
+        assert_eq!(manager.find_owner(&id), None);
+        assert!(manager.try_get_position(&id).is_err());
+        assert!(manager.try_owner_of(&id).is_err());
+    }
+
+    #[test]
+    fn test_decrease_liquidity_bps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        // Out of range bps are rejected
+        assert!(manager
+            .try_decrease_liquidity_bps(&owner, &id, &0, &0, &0, &1000)
+            .is_err());
+        assert!(manager
+            .try_decrease_liquidity_bps(&owner, &id, &10_001, &0, &0, &1000)
+            .is_err());
+        assert_eq!(manager.get_position(&id).liquidity, liquidity);
+
+        // A quarter, rounded down
+        let (amount0, amount1) = manager.decrease_liquidity_bps(&owner, &id, &2_500, &0, &0, &1000);
+        assert!(amount0 > 0 && amount1 > 0);
+        let remaining = manager.get_position(&id).liquidity;
+        assert_eq!(remaining, liquidity - liquidity / 4);
+
+        // The slippage floor still applies
+        assert!(manager
+            .try_decrease_liquidity_bps(&owner, &id, &MAX_BPS, &i128::MAX, &0, &1000)
+            .is_err());
+
+        // The whole position, whatever is left of it
+        manager.decrease_liquidity_bps(&owner, &id, &MAX_BPS, &0, &0, &1000);
+        assert_eq!(manager.get_position(&id).liquidity, 0);
+        assert!(manager
+            .try_decrease_liquidity_bps(&owner, &id, &MAX_BPS, &0, &0, &1000)
+            .is_err());
+        manager.burn_with_sweep(&id);
+    }
+
+    #[test]
+    fn test_mint_rejects_bad_tick_ranges() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+
+        // Spacing is 60 for the 0.3% tier
+        let bad_ranges = [
+            (-601, 600),
+            (-600, 601),
+            (600, 600),
+            (600, -600),
+            (-443_640, 600),
+            (-600, 443_640),
+        ];
+        for (tick_lower, tick_upper) in bad_ranges {
+            let mut params = mint_params(&token0, &token1, 3000, &owner);
+            params.tick_lower = tick_lower;
+            params.tick_upper = tick_upper;
+            assert!(manager.try_mint(&params).is_err());
+        }
+        assert_eq!(manager.total_supply(), 0);
+
+        let mut params = mint_params(&token0, &token1, 3000, &owner);
+        params.tick_lower = -443_580;
+        params.tick_upper = 443_580;
+        let (id, liquidity, _, _) = manager.mint(&params);
+        assert!(liquidity > 0);
+
+        // Rebalancing into a misaligned range leaves the position untouched
+        assert!(manager
+            .try_rebalance(&id, &-610, &610, &0, &0, &1000)
+            .is_err());
+        assert_eq!(manager.get_position(&id).liquidity, liquidity);
+    }
+
+    #[test]
+    fn test_compound_reinvests_fees() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (factory, manager, token0, token1) = setup(&env);
+        let pool = factory.create_pool(&Address::generate(&env), &token0, &token1, &3000, &Q96);
+        let owner = Address::generate(&env);
+        fund(&env, &token0, &owner, 10_000_000);
+        fund(&env, &token1, &owner, 10_000_000);
+        let (id, liquidity, _, _) = manager.mint(&mint_params(&token0, &token1, 3000, &owner));
+
+        // Nothing owed yet
+        assert!(manager.try_compound(&id, &0, &0, &1000).is_err());
+
+        // Fees too small for a MIN_LIQUIDITY mint are left owed
+        let trader = Address::generate(&env);
+        fund(&env, &token0, &trader, 1_000_000);
+        fund(&env, &token1, &trader, 1_000_000);
+        let pool_client = dex_pool_contract::Client::new(&env, &pool);
+        pool_client.swap(&trader, &false, &2_000, &0, &false);
+        pool_client.swap(&trader, &true, &2_000, &0, &false);
+        let (small0, small1) = manager.uncollected_fees(&id);
+        assert!(small0 > 0 && small1 > 0);
+        assert!(manager.try_compound(&id, &0, &0, &1000).is_err());
+        assert_eq!(manager.uncollected_fees(&id), (small0, small1));
+
+        // Earn fees in both tokens
+        pool_client.swap(&trader, &false, &200_000, &0, &false);
+        pool_client.swap(&trader, &true, &200_000, &0, &false);
+        let (fees0, fees1) = manager.uncollected_fees(&id);
+        assert!(fees0 > 0 && fees1 > 0);
+
+        assert!(manager
+            .try_compound(&id, &(fees0 as i128 + 1), &0, &1000)
+            .is_err());
+
+        // Only the owner's auth is given; the manager authorizes its own payment
+        let (added, amount0, amount1) = manager
+            .mock_auths(&[MockAuth {
+                address: &owner,
+                invoke: &MockAuthInvoke {
+                    contract: &manager.address,
+                    fn_name: "compound",
+                    args: (id, 0_i128, 0_i128, 1000_u64).into_val(&env),
+                    sub_invokes: &[],
+                },
+            }])
+            .compound(&id, &0, &0, &1000);
+        assert!(added > 0);
+        assert!(amount0 <= fees0 as i128 && amount1 <= fees1 as i128);
+
+        let position = manager.get_position(&id);
+        assert_eq!(position.liquidity, liquidity + added);
+        let remaining0 = position.tokens_owed_0;
+        let remaining1 = position.tokens_owed_1;
+        assert_eq!(remaining0, fees0 - amount0 as u128);
+        assert_eq!(remaining1, fees1 - amount1 as u128);
+        // The scarcer token is used up to rounding; the rest stays owed
+        assert!(remaining0 * 100 < fees0 || remaining1 * 100 < fees1);
+
+        // The manager holds nothing between calls
+        let held0 = token::Client::new(&env, &token0).balance(&manager.address);
+        let held1 = token::Client::new(&env, &token1).balance(&manager.address);
+        assert_eq!((held0, held1), (0, 0));
+    }
+}
@@ -1,8 +1,10 @@
 #![no_std]
 
-use dex_types::{MAX_SQRT_RATIO, MIN_SQRT_RATIO};
+use dex_types::{PoolError, SwapResult, SwapSimulation, MAX_SQRT_RATIO, MIN_SQRT_RATIO};
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Env, IntoVal, Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Env,
+    Error, IntoVal, InvokeError, Symbol, Vec,
 };
 
 #[contract]
@@ -13,8 +15,35 @@ pub struct DexRouter;
 #[derive(Clone)]
 pub enum DataKey {
     Factory,
+    Quoter,
 }
 
+/// Swap failures the router reports in its own terms, mapped from pool errors
+/// so UIs can tell a price limit from a lack of liquidity. Codes start at 200,
+/// clear of the pool's.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RouterError {
+    /// Price limit is on the wrong side of the pool price or out of bounds
+    InvalidPriceLimit = 200,
+    /// Pool reached the price limit short of the requested output
+    PriceLimitReached = 201,
+    /// Pool has no liquidity, or not enough for the requested output
+    InsufficientLiquidity = 202,
+    /// Pool stopped at its tick crossing cap and partial fills were not allowed
+    TooManyTicksCrossed = 203,
+    /// Swaps are paused on this pool or across the factory
+    SwapsPaused = 204,
+    /// Recipient is the router, the factory, a path token or a hop's pool
+    InvalidRecipient = 205,
+}
+
+/// Extra input added to each hop's quoted requirement when executing
+/// multi-hop exact output swaps, covering rounding differences between the
+/// exact-output quote and the exact-input execution. Unused input is refunded.
+const HOP_INPUT_EPSILON: i128 = 10;
+
 /// Parameters for exact input single swap
 #[contracttype]
 #[derive(Clone)]
@@ -27,6 +56,8 @@ pub struct ExactInputSingleParams {
     pub amount_in: i128,
     pub amount_out_minimum: i128,
     pub sqrt_price_limit_x96: u128,
+    /// Accept a partial fill if the pool hits its tick crossing cap
+    pub partial_fill_allowed: bool,
 }
 
 /// Parameters for exact output single swap
@@ -41,6 +72,8 @@ pub struct ExactOutputSingleParams {
     pub amount_out: i128,
     pub amount_in_maximum: i128,
     pub sqrt_price_limit_x96: u128,
+    /// Accept a partial fill if the pool hits its tick crossing cap
+    pub partial_fill_allowed: bool,
 }
 
 /// Path element for multi-hop swaps
@@ -51,14 +84,43 @@ pub struct PathElement {
     pub fee: u32,
 }
 
+/// Parameters for multi-hop exact output swap
+/// `path` is ordered from the input token to the output token, as for exact_input
+#[contracttype]
+#[derive(Clone)]
+pub struct ExactOutputParams {
+    pub path: Vec<PathElement>,
+    pub recipient: Address,
+    pub deadline: u64,
+    pub amount_out: i128,
+    pub amount_in_maximum: i128,
+}
+
+/// Result of a multi-hop exact output swap
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ExactOutputResult {
+    /// Total input taken from the recipient after refunds
+    pub amount_in: i128,
+    /// Total output delivered to the recipient
+    pub amount_out: i128,
+    /// Quoted input required by each hop, in path order
+    pub hop_amounts_quoted: Vec<i128>,
+    /// Input actually spent by each hop, in path order
+    pub hop_amounts_in: Vec<i128>,
+    /// Output produced by each hop, in path order
+    pub hop_amounts_out: Vec<i128>,
+}
+
 #[contractimpl]
 impl DexRouter {
-    /// Initialize router with factory address
-    pub fn initialize(env: Env, factory: Address) {
+    /// Initialize router with factory and quoter addresses
+    pub fn initialize(env: Env, factory: Address, quoter: Address) {
         if env.storage().instance().has(&DataKey::Factory) {
             panic!("Already initialized");
         }
         env.storage().instance().set(&DataKey::Factory, &factory);
+        env.storage().instance().set(&DataKey::Quoter, &quoter);
     }
 
     /// Swap exact input amount for maximum output (single pool)
@@ -68,6 +130,11 @@ impl DexRouter {
 
         let factory = get_factory(&env);
         let pool = get_pool(&env, &factory, &params.token_in, &params.token_out, params.fee);
+        check_recipient(
+            &env,
+            &params.recipient,
+            &[&params.token_in, &params.token_out, &factory, &pool],
+        );
 
         let zero_for_one = params.token_in < params.token_out;
         let sqrt_price_limit = if params.sqrt_price_limit_x96 == 0 {
@@ -81,13 +148,14 @@ impl DexRouter {
         };
 
         // Execute swap
-        let (amount0, amount1) = invoke_swap(
+        let SwapResult { amount0, amount1, .. } = invoke_swap(
             &env,
             &pool,
             &params.recipient,
             zero_for_one,
             params.amount_in,
             sqrt_price_limit,
+            params.partial_fill_allowed,
         );
 
         let amount_out = if zero_for_one { -amount1 } else { -amount0 };
@@ -106,6 +174,11 @@ impl DexRouter {
 
         let factory = get_factory(&env);
         let pool = get_pool(&env, &factory, &params.token_in, &params.token_out, params.fee);
+        check_recipient(
+            &env,
+            &params.recipient,
+            &[&params.token_in, &params.token_out, &factory, &pool],
+        );
 
         let zero_for_one = params.token_in < params.token_out;
         let sqrt_price_limit = if params.sqrt_price_limit_x96 == 0 {
@@ -119,13 +192,14 @@ impl DexRouter {
         };
 
         // Execute swap with negative amount (exact output)
-        let (amount0, amount1) = invoke_swap(
+        let SwapResult { amount0, amount1, .. } = invoke_swap(
             &env,
             &pool,
             &params.recipient,
             zero_for_one,
             -params.amount_out, // Negative for exact output
             sqrt_price_limit,
+            params.partial_fill_allowed,
         );
 
         let amount_in = if zero_for_one { amount0 } else { amount1 };
@@ -154,6 +228,8 @@ impl DexRouter {
         }
 
         let factory = get_factory(&env);
+        validate_path_recipient(&env, &path, &recipient, &factory);
+
         let mut current_amount = amount_in;
 
         // Execute swaps along path
@@ -178,13 +254,15 @@ impl DexRouter {
                 MAX_SQRT_RATIO - 1
             };
 
-            let (amount0, amount1) = invoke_swap(
+            // A truncated hop would leave input stranded in the router
+            let SwapResult { amount0, amount1, .. } = invoke_swap(
                 &env,
                 &pool,
                 &swap_recipient,
                 zero_for_one,
                 current_amount,
                 sqrt_price_limit,
+                false,
             );
 
             current_amount = if zero_for_one { -amount1 } else { -amount0 };
@@ -197,10 +275,128 @@ impl DexRouter {
         current_amount
     }
 
+    /// Multi-hop exact output swap
+    ///
+    /// Quotes each hop backwards from the desired output with the pool's own
+    /// `simulate_swap` to find the minimum input, then executes forwards with the router holding intermediate
+    /// tokens. Each hop spends its quoted input plus HOP_INPUT_EPSILON and any
+    /// unspent input or intermediate dust is refunded to the recipient.
+    pub fn exact_output(env: Env, params: ExactOutputParams) -> ExactOutputResult {
+        params.recipient.require_auth();
+        check_deadline(&env, params.deadline);
+
+        let path = params.path;
+        if path.len() < 2 {
+            panic!("Invalid path length");
+        }
+
+        let factory = get_factory(&env);
+        let hops = path.len() - 1;
+        validate_path_recipient(&env, &path, &params.recipient, &factory);
+
+        // Backward pass: required input of each hop, last hop first
+        let mut hop_amounts_quoted: Vec<i128> = Vec::new(&env);
+        let mut required = params.amount_out;
+        for i in (0..hops).rev() {
+            let token_in = path.get(i).unwrap().token;
+            let fee = path.get(i).unwrap().fee;
+            let token_out = path.get(i + 1).unwrap().token;
+
+            let pool = get_pool(&env, &factory, &token_in, &token_out, fee);
+            required = quote_exact_output_single(&env, &pool, token_in < token_out, required);
+            hop_amounts_quoted.push_front(required);
+        }
+
+        let amount_in_pulled = hop_amounts_quoted.get(0).unwrap() + HOP_INPUT_EPSILON;
+        if amount_in_pulled > params.amount_in_maximum {
+            panic!("Excessive input amount");
+        }
+
+        // Take custody of the input
+        let router = env.current_contract_address();
+        let first_token = path.get(0).unwrap().token;
+        token::Client::new(&env, &first_token).transfer(
+            &params.recipient,
+            &router,
+            &amount_in_pulled,
+        );
+
+        // Forward pass: exact input per hop, refunding whatever a hop leaves unspent
+        let mut hop_amounts_in: Vec<i128> = Vec::new(&env);
+        let mut hop_amounts_out: Vec<i128> = Vec::new(&env);
+        let mut available = amount_in_pulled;
+        let mut amount_in = 0;
+
+        for i in 0..hops {
+            let token_in = path.get(i).unwrap().token;
+            let fee = path.get(i).unwrap().fee;
+            let token_out = path.get(i + 1).unwrap().token;
+
+            let quoted = hop_amounts_quoted.get(i).unwrap();
+            let spend = available.min(quoted + HOP_INPUT_EPSILON);
+
+            let pool = get_pool(&env, &factory, &token_in, &token_out, fee);
+            let zero_for_one = token_in < token_out;
+            let sqrt_price_limit = if zero_for_one {
+                MIN_SQRT_RATIO + 1
+            } else {
+                MAX_SQRT_RATIO - 1
+            };
+
+            authorize_swap_payment(&env, &pool, &token_in, spend);
+            let SwapResult { amount0, amount1, .. } =
+                invoke_swap(&env, &pool, &router, zero_for_one, spend, sqrt_price_limit, false);
+
+            let (spent, received) = if zero_for_one {
+                (amount0, -amount1)
+            } else {
+                (amount1, -amount0)
+            };
+
+            // Sweep leftover input of this hop back to the recipient
+            let dust = available - spent;
+            if dust > 0 {
+                token::Client::new(&env, &token_in).transfer(
+                    &router,
+                    &params.recipient,
+                    &dust,
+                );
+            }
+
+            if i == 0 {
+                amount_in = spent;
+            }
+            hop_amounts_in.push_back(spent);
+            hop_amounts_out.push_back(received);
+            available = received;
+        }
+
+        if available < params.amount_out {
+            panic!("Insufficient output amount");
+        }
+
+        // Deliver the full final output, including any epsilon surplus
+        let last_token = path.get(hops).unwrap().token;
+        token::Client::new(&env, &last_token).transfer(&router, &params.recipient, &available);
+
+        ExactOutputResult {
+            amount_in,
+            amount_out: available,
+            hop_amounts_quoted,
+            hop_amounts_in,
+            hop_amounts_out,
+        }
+    }
+
     /// Get factory address
     pub fn get_factory(env: Env) -> Address {
         get_factory(&env)
     }
+
+    /// Get quoter address
+    pub fn get_quoter(env: Env) -> Address {
+        get_quoter(&env)
+    }
 }
 
 fn get_factory(env: &Env) -> Address {
@@ -210,6 +406,41 @@ fn get_factory(env: &Env) -> Address {
         .expect("Not initialized")
 }
 
+fn get_quoter(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Quoter)
+        .expect("Not initialized")
+}
+
+/// Reject a multi-hop recipient that is the router, the factory, a path token or a hop's pool
+fn validate_path_recipient(
+    env: &Env,
+    path: &Vec<PathElement>,
+    recipient: &Address,
+    factory: &Address,
+) {
+    check_recipient(env, recipient, &[factory]);
+
+    for i in 0..path.len() {
+        let element = path.get(i).unwrap();
+        check_recipient(env, recipient, &[&element.token]);
+
+        if i + 1 < path.len() {
+            let token_out = path.get(i + 1).unwrap().token;
+            let pool = get_pool(env, factory, &element.token, &token_out, element.fee);
+            check_recipient(env, recipient, &[&pool]);
+        }
+    }
+}
+
+/// Reject a recipient that can't meaningfully receive the swap output
+fn check_recipient(env: &Env, recipient: &Address, excluded: &[&Address]) {
+    if !dex_types::is_valid_recipient(env, recipient, excluded) {
+        panic_with_error!(env, RouterError::InvalidRecipient);
+    }
+}
+
 fn check_deadline(env: &Env, deadline: u64) {
     let current_time = env.ledger().timestamp();
     if current_time > deadline {
@@ -233,10 +464,552 @@ fn invoke_swap(
     zero_for_one: bool,
     amount_specified: i128,
     sqrt_price_limit_x96: u128,
-) -> (i128, i128) {
-    env.invoke_contract(
+    partial_fill_allowed: bool,
+) -> SwapResult {
+    let result = env.try_invoke_contract::<SwapResult, PoolError>(
         pool,
         &Symbol::new(env, "swap"),
-        (recipient, zero_for_one, amount_specified, sqrt_price_limit_x96).into_val(env),
-    )
+        (
+            recipient,
+            zero_for_one,
+            amount_specified,
+            sqrt_price_limit_x96,
+            partial_fill_allowed,
+        )
+            .into_val(env),
+    );
+    match result {
+        Ok(Ok(swap_result)) => swap_result,
+        Ok(Err(_)) => panic!("Invalid swap result"),
+        Err(Ok(pool_error)) => panic_with_error!(env, router_error(pool_error)),
+        // Not a pool error (e.g. a failed token transfer): pass it through unchanged
+        Err(Err(InvokeError::Contract(code))) => {
+            panic_with_error!(env, Error::from_contract_error(code))
+        }
+        Err(Err(InvokeError::Abort)) => panic!("Swap aborted"),
+    }
+}
+
+/// Authorize the pool to pull `amount` of `token` from the router during a swap
+/// The pool, not the router, invokes the token, so the router's own auth
+/// does not reach the transfer without this entry
+fn authorize_swap_payment(env: &Env, pool: &Address, token: &Address, amount: i128) {
+    let mut entries = Vec::new(env);
+    entries.push_back(InvokerContractAuthEntry::Contract(SubContractInvocation {
+        context: ContractContext {
+            contract: token.clone(),
+            fn_name: Symbol::new(env, "transfer"),
+            args: (env.current_contract_address(), pool, amount).into_val(env),
+        },
+        sub_invocations: Vec::new(env),
+    }));
+    env.authorize_as_current_contract(entries);
+}
+
+/// Map a pool swap failure to the router's error, keeping pool errors
+/// the router has no counterpart for
+fn router_error(pool_error: PoolError) -> soroban_sdk::Error {
+    match pool_error {
+        PoolError::InvalidPriceLimit => RouterError::InvalidPriceLimit.into(),
+        PoolError::PriceLimitReached => RouterError::PriceLimitReached.into(),
+        PoolError::NoLiquidity | PoolError::InsufficientLiquidity => {
+            RouterError::InsufficientLiquidity.into()
+        }
+        PoolError::TooManyTicksCrossed => RouterError::TooManyTicksCrossed.into(),
+        PoolError::SwapsPaused | PoolError::PoolPaused => RouterError::SwapsPaused.into(),
+        other => other.into(),
+    }
+}
+
+/// Input `pool` requires to pay out `amount_out`, from the pool's own swap simulation
+/// The simulation walks and crosses ticks exactly as the swap will
+fn quote_exact_output_single(
+    env: &Env,
+    pool: &Address,
+    zero_for_one: bool,
+    amount_out: i128,
+) -> i128 {
+    let sqrt_price_limit = if zero_for_one {
+        MIN_SQRT_RATIO + 1
+    } else {
+        MAX_SQRT_RATIO - 1
+    };
+    let quote: SwapSimulation = env.invoke_contract(
+        pool,
+        &Symbol::new(env, "simulate_swap"),
+        (zero_for_one, -amount_out, sqrt_price_limit).into_val(env),
+    );
+
+    let (amount_in, received) = if zero_for_one {
+        (quote.amount0, -quote.amount1)
+    } else {
+        (quote.amount1, -quote.amount0)
+    };
+    // A short or capped fill here would leave the forward pass short as well
+    if quote.truncated || received < amount_out {
+        panic_with_error!(env, RouterError::InsufficientLiquidity);
+    }
+    amount_in
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, MockAuth, MockAuthInvoke};
+    use soroban_sdk::token::{StellarAssetClient, TokenClient};
+    use soroban_sdk::vec;
+
+    // Factory, pool and quoter run from their compiled WASM.
+    // Build them first with `cargo build --release --target wasm32-unknown-unknown`.
+    mod dex_factory_contract {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32-unknown-unknown/release/dex_factory.wasm"
+        );
+    }
+
+    mod dex_pool_contract {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32-unknown-unknown/release/dex_pool.wasm"
+        );
+    }
+
+    mod dex_quoter_contract {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32-unknown-unknown/release/dex_quoter.wasm"
+        );
+    }
+
+    const Q96: u128 = 1 << 96;
+
+    struct Setup<'a> {
+        factory: dex_factory_contract::Client<'a>,
+        router: DexRouterClient<'a>,
+        /// Tokens sorted ascending
+        tokens: Vec<Address>,
+    }
+
+    /// Deploy factory, quoter and router with a chain of funded 0.3% pools
+    /// between consecutive tokens: tokens[0]/tokens[1], tokens[1]/tokens[2], ...
+    fn setup<'a>(env: &Env, token_count: u32) -> Setup<'a> {
+        let admin = Address::generate(env);
+        let factory_id = env.register(dex_factory_contract::WASM, ());
+        let factory = dex_factory_contract::Client::new(env, &factory_id);
+        let pool_wasm_hash = env.deployer().upload_contract_wasm(dex_pool_contract::WASM);
+        factory.initialize(&admin, &pool_wasm_hash);
+
+        let quoter_id = env.register(dex_quoter_contract::WASM, ());
+        dex_quoter_contract::Client::new(env, &quoter_id).initialize(&factory_id);
+
+        let router_id = env.register(DexRouter, ());
+        let router = DexRouterClient::new(env, &router_id);
+        router.initialize(&factory_id, &quoter_id);
+
+        let mut sorted: Vec<Address> = Vec::new(env);
+        for _ in 0..token_count {
+            let issuer = Address::generate(env);
+            let token = env.register_stellar_asset_contract_v2(issuer).address();
+            let mut index = 0;
+            while index < sorted.len() && sorted.get(index).unwrap() < token {
+                index += 1;
+            }
+            sorted.insert(index, token);
+        }
+
+        let lp = Address::generate(env);
+        for token in sorted.iter() {
+            StellarAssetClient::new(env, &token).mint(&lp, &10_000_000_000);
+        }
+        for i in 0..(token_count - 1) {
+            let pool = factory.create_pool(
+                &lp,
+                &sorted.get(i).unwrap(),
+                &sorted.get(i + 1).unwrap(),
+                &3000,
+                &Q96,
+            );
+            dex_pool_contract::Client::new(env, &pool)
+                .mint(&lp, &lp, &-6000, &6000, &1_000_000_000);
+        }
+
+        Setup {
+            factory,
+            router,
+            tokens: sorted,
+        }
+    }
+
+    fn hop(token: Address) -> PathElement {
+        PathElement { token, fee: 3000 }
+    }
+
+    /// Input the 0.3% pool's own simulation requires to pay out `amount_out`,
+    /// and the initialized ticks it crosses
+    fn simulate_exact_output(
+        env: &Env,
+        factory: &dex_factory_contract::Client,
+        token_in: &Address,
+        token_out: &Address,
+        amount_out: i128,
+    ) -> (i128, u32) {
+        let pool = factory.get_pool(token_in, token_out, &3000).unwrap();
+        let zero_for_one = token_in < token_out;
+        let sqrt_price_limit = if zero_for_one {
+            MIN_SQRT_RATIO + 1
+        } else {
+            MAX_SQRT_RATIO - 1
+        };
+        let quote = dex_pool_contract::Client::new(env, &pool).simulate_swap(
+            &zero_for_one,
+            &-amount_out,
+            &sqrt_price_limit,
+        );
+        let amount_in = if zero_for_one {
+            quote.amount0
+        } else {
+            quote.amount1
+        };
+        (amount_in, quote.ticks_crossed)
+    }
+
+    // === Exact Output Multi-hop Tests ===
+
+    #[test]
+    fn test_exact_output_three_hops_minimizes_input_and_refunds_dust() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let Setup {
+            factory,
+            router,
+            tokens,
+        } = setup(&env, 4);
+
+        let token = |i: u32| tokens.get(i).unwrap();
+
+        // A narrow position on the middle pool puts initialized ticks in the way
+        let lp = Address::generate(&env);
+        StellarAssetClient::new(&env, &token(1)).mint(&lp, &1_000_000_000);
+        StellarAssetClient::new(&env, &token(2)).mint(&lp, &1_000_000_000);
+        let middle = factory.get_pool(&token(1), &token(2), &3000).unwrap();
+        dex_pool_contract::Client::new(&env, &middle).mint(&lp, &lp, &-60, &60, &500_000_000);
+
+        // Route from the highest token down so every hop swaps one for zero
+        let path = vec![&env, hop(token(3)), hop(token(2)), hop(token(1)), hop(token(0))];
+
+        // Theoretical minimum input from chained exact-output simulations
+        let amount_out: i128 = 5_000_000;
+        let quote = |token_in: u32, token_out: u32, amount: i128| {
+            simulate_exact_output(&env, &factory, &token(token_in), &token(token_out), amount)
+        };
+        let (q2, _) = quote(1, 0, amount_out);
+        let (q1, crossed) = quote(2, 1, q2);
+        let (q0, _) = quote(3, 2, q1);
+        assert!(crossed > 0);
+
+        let user = Address::generate(&env);
+        let starting_balance: i128 = 20_000_000;
+        StellarAssetClient::new(&env, &token(3)).mint(&user, &starting_balance);
+
+        let result = router.exact_output(&ExactOutputParams {
+            path,
+            recipient: user.clone(),
+            deadline: 1000,
+            amount_out,
+            amount_in_maximum: starting_balance,
+        });
+
+        assert_eq!(result.hop_amounts_quoted, vec![&env, q0, q1, q2]);
+        assert!(result.amount_in >= q0);
+        assert!(result.amount_in <= q0 + HOP_INPUT_EPSILON);
+        assert!(result.amount_out >= amount_out);
+        // Surplus output is bounded by the last hop's epsilon plus rounding
+        assert!(result.amount_out <= amount_out + 2 * HOP_INPUT_EPSILON);
+
+        let balance = |i: u32, who: &Address| TokenClient::new(&env, &token(i)).balance(who);
+
+        // User paid exactly amount_in and received the full output
+        assert_eq!(balance(3, &user), starting_balance - result.amount_in);
+        assert_eq!(balance(0, &user), result.amount_out);
+
+        // Intermediate dust went back to the user
+        assert_eq!(
+            balance(2, &user),
+            result.hop_amounts_out.get(0).unwrap() - result.hop_amounts_in.get(1).unwrap()
+        );
+        assert_eq!(
+            balance(1, &user),
+            result.hop_amounts_out.get(1).unwrap() - result.hop_amounts_in.get(2).unwrap()
+        );
+
+        // Router keeps nothing
+        for i in 0..4 {
+            assert_eq!(balance(i, &router.address), 0);
+        }
+    }
+
+    #[test]
+    fn test_exact_output_multi_hop_with_only_recipient_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let Setup {
+            factory,
+            router,
+            tokens,
+        } = setup(&env, 3);
+
+        let token = |i: u32| tokens.get(i).unwrap();
+        let amount_out: i128 = 100_000;
+        let (q1, _) = simulate_exact_output(&env, &factory, &token(1), &token(0), amount_out);
+        let (q0, _) = simulate_exact_output(&env, &factory, &token(2), &token(1), q1);
+        let amount_in_pulled = q0 + HOP_INPUT_EPSILON;
+
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &token(2)).mint(&user, &10_000_000);
+
+        let params = ExactOutputParams {
+            path: vec![&env, hop(token(2)), hop(token(1)), hop(token(0))],
+            recipient: user.clone(),
+            deadline: 1000,
+            amount_out,
+            amount_in_maximum: 10_000_000,
+        };
+
+        // The user authorizes the call and the input transfer; the router
+        // authorizes the pools' pulls of the tokens it holds itself
+        let result = router
+            .mock_auths(&[MockAuth {
+                address: &user,
+                invoke: &MockAuthInvoke {
+                    contract: &router.address,
+                    fn_name: "exact_output",
+                    args: (params.clone(),).into_val(&env),
+                    sub_invokes: &[MockAuthInvoke {
+                        contract: &token(2),
+                        fn_name: "transfer",
+                        args: (user.clone(), router.address.clone(), amount_in_pulled)
+                            .into_val(&env),
+                        sub_invokes: &[],
+                    }],
+                },
+            }])
+            .exact_output(&params);
+
+        let balance = |i: u32, who: &Address| TokenClient::new(&env, &token(i)).balance(who);
+        assert!(result.amount_out >= amount_out);
+        assert_eq!(balance(0, &user), result.amount_out);
+        for i in 0..3 {
+            assert_eq!(balance(i, &router.address), 0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Excessive input amount")]
+    fn test_exact_output_respects_input_maximum() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let Setup { router, tokens, .. } = setup(&env, 3);
+
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &tokens.get(2).unwrap()).mint(&user, &10_000_000);
+
+        router.exact_output(&ExactOutputParams {
+            path: vec![
+                &env,
+                hop(tokens.get(2).unwrap()),
+                hop(tokens.get(1).unwrap()),
+                hop(tokens.get(0).unwrap()),
+            ],
+            recipient: user,
+            deadline: 1000,
+            amount_out: 100_000,
+            // Two 0.3% hops at price 1 need more than the output amount
+            amount_in_maximum: 100_000,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid path length")]
+    fn test_exact_output_rejects_single_token_path() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let Setup { router, tokens, .. } = setup(&env, 2);
+
+        router.exact_output(&ExactOutputParams {
+            path: vec![&env, hop(tokens.get(0).unwrap())],
+            recipient: Address::generate(&env),
+            deadline: 1000,
+            amount_out: 100,
+            amount_in_maximum: 1_000,
+        });
+    }
+
+    // === Recipient Validation Tests ===
+
+    #[test]
+    fn test_degenerate_recipients_rejected_by_every_entrypoint() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let Setup {
+            factory,
+            router,
+            tokens,
+            ..
+        } = setup(&env, 3);
+        let token = |i: u32| tokens.get(i).unwrap();
+        let pool01 = factory.get_pool(&token(0), &token(1), &3000).unwrap();
+        let pool12 = factory.get_pool(&token(1), &token(2), &3000).unwrap();
+
+        // Single-hop entrypoints swap token2 for token1 through pool12,
+        // paths go token2 -> token1 -> token0 through both pools
+        let single_hop_degenerate = [
+            router.address.clone(),
+            factory.address.clone(),
+            token(1),
+            token(2),
+            pool12.clone(),
+        ];
+        let path_degenerate = [
+            router.address.clone(),
+            factory.address.clone(),
+            token(0),
+            token(1),
+            token(2),
+            pool01,
+            pool12,
+        ];
+
+        let invalid_recipient = Ok(soroban_sdk::Error::from(RouterError::InvalidRecipient));
+        for recipient in single_hop_degenerate.iter() {
+            let result = router.try_exact_input_single(&ExactInputSingleParams {
+                token_in: token(2),
+                token_out: token(1),
+                fee: 3000,
+                recipient: recipient.clone(),
+                deadline: 1000,
+                amount_in: 1_000,
+                amount_out_minimum: 0,
+                sqrt_price_limit_x96: 0,
+                partial_fill_allowed: false,
+            });
+            assert_eq!(result.unwrap_err(), invalid_recipient);
+            let result = router.try_exact_output_single(&ExactOutputSingleParams {
+                token_in: token(2),
+                token_out: token(1),
+                fee: 3000,
+                recipient: recipient.clone(),
+                deadline: 1000,
+                amount_out: 1_000,
+                amount_in_maximum: i128::MAX,
+                sqrt_price_limit_x96: 0,
+                partial_fill_allowed: false,
+            });
+            assert_eq!(result.unwrap_err(), invalid_recipient);
+        }
+        for recipient in path_degenerate.iter() {
+            let path = vec![&env, hop(token(2)), hop(token(1)), hop(token(0))];
+            let result = router.try_exact_input(&path, recipient, &1000, &1_000, &0);
+            assert_eq!(result.unwrap_err(), invalid_recipient);
+            let result = router.try_exact_output(&ExactOutputParams {
+                path,
+                recipient: recipient.clone(),
+                deadline: 1000,
+                amount_out: 1_000,
+                amount_in_maximum: i128::MAX,
+            });
+            assert_eq!(result.unwrap_err(), invalid_recipient);
+        }
+
+        // An ordinary recipient goes through
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &token(2)).mint(&user, &10_000);
+        let amount_out = router.exact_input_single(&ExactInputSingleParams {
+            token_in: token(2),
+            token_out: token(1),
+            fee: 3000,
+            recipient: user,
+            deadline: 1000,
+            amount_in: 1_000,
+            amount_out_minimum: 0,
+            sqrt_price_limit_x96: 0,
+            partial_fill_allowed: false,
+        });
+        assert!(amount_out > 0);
+    }
+
+    #[test]
+    fn test_exact_input_single_to_router_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let Setup { router, tokens, .. } = setup(&env, 2);
+
+        let result = router.try_exact_input_single(&ExactInputSingleParams {
+            token_in: tokens.get(1).unwrap(),
+            token_out: tokens.get(0).unwrap(),
+            fee: 3000,
+            recipient: router.address.clone(),
+            deadline: 1000,
+            amount_in: 1_000,
+            amount_out_minimum: 0,
+            sqrt_price_limit_x96: 0,
+            partial_fill_allowed: false,
+        });
+        assert_eq!(
+            result,
+            Err(Ok(soroban_sdk::Error::from(RouterError::InvalidRecipient)))
+        );
+    }
+
+    // === Pool Error Mapping Tests ===
+
+    #[test]
+    fn test_exact_output_single_reports_price_limit_and_liquidity_distinctly() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let Setup { router, tokens, .. } = setup(&env, 2);
+
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &tokens.get(0).unwrap()).mint(&user, &10_000_000_000);
+        let params = |amount_out: i128, sqrt_price_limit_x96: u128| ExactOutputSingleParams {
+            token_in: tokens.get(0).unwrap(),
+            token_out: tokens.get(1).unwrap(),
+            fee: 3000,
+            recipient: user.clone(),
+            deadline: 1000,
+            amount_out,
+            amount_in_maximum: i128::MAX,
+            sqrt_price_limit_x96,
+            partial_fill_allowed: false,
+        };
+
+        // A 0.1% price move covers about 1_000_000 of the 1_000_000_000 liquidity
+        let limit = Q96 - Q96 / 1000;
+        assert_eq!(
+            router.try_exact_output_single(&params(10_000_000, limit)),
+            Err(Ok(soroban_sdk::Error::from(RouterError::PriceLimitReached)))
+        );
+
+        // The [-6000, 6000] range holds well under 1_000_000_000 token1
+        assert_eq!(
+            router.try_exact_output_single(&params(1_000_000_000, 0)),
+            Err(Ok(soroban_sdk::Error::from(RouterError::InsufficientLiquidity)))
+        );
+
+        // A limit above the price for a price-lowering swap
+        assert_eq!(
+            router.try_exact_output_single(&params(1_000, Q96 + 1)),
+            Err(Ok(soroban_sdk::Error::from(RouterError::InvalidPriceLimit)))
+        );
+
+        // Errors the router has no counterpart for come through as the pool's
+        assert_eq!(
+            router.try_exact_output_single(&params(0, 0)),
+            Err(Ok(soroban_sdk::Error::from(PoolError::ZeroAmount)))
+        );
+    }
 }
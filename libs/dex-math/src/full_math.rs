@@ -40,6 +40,35 @@ pub fn mul_div_rounding_up(env: &Env, a: u128, b: u128, denominator: u128) -> u1
     }
 }
 
+/// Multiply with 256-bit intermediate precision and shift right by 128 (rounds down)
+/// Returns (a * b) >> 128, converting a Q128 growth value into a token amount
+pub fn mul_shr_128(env: &Env, a: u128, b: u128) -> u128 {
+    let a_256 = U256::from_u128(env, a);
+    let b_256 = U256::from_u128(env, b);
+
+    let result = a_256.mul(&b_256).shr(128);
+
+    u128_from_u256(env, &result)
+}
+
+/// Shift left by 128 and divide with 256-bit intermediate precision (rounds down)
+/// Returns ((a << 128) / denominator) mod 2^128, converting a token amount into
+/// Q128 growth per unit of liquidity. Growth accumulators wrap by design, so the
+/// bits above 128 are discarded rather than treated as overflow.
+pub fn div_shl_128(env: &Env, a: u128, denominator: u128) -> u128 {
+    if denominator == 0 {
+        panic!("Division by zero");
+    }
+
+    let a_256 = U256::from_u128(env, a);
+    let denom_256 = U256::from_u128(env, denominator);
+    let two_pow_128 = U256::from_u32(env, 1).shl(128);
+
+    let result = a_256.shl(128).div(&denom_256).rem_euclid(&two_pow_128);
+
+    u128_from_u256(env, &result)
+}
+
 /// Convert U256 to u128, panics if overflow
 fn u128_from_u256(env: &Env, value: &U256) -> u128 {
     let max_u128 = U256::from_u128(env, u128::MAX);
@@ -65,6 +94,52 @@ mod tests {
     use super::*;
     use soroban_sdk::Env;
 
+    // === mul_shr_128 / div_shl_128 tests ===
+
+    #[test]
+    fn test_mul_shr_128_basic() {
+        let env = Env::default();
+        // Half of 2^128 times 10 shifted down by 128 = 5
+        assert_eq!(mul_shr_128(&env, 1u128 << 127, 10), 5);
+        // Products below 2^128 round down to zero
+        assert_eq!(mul_shr_128(&env, 1000, 1000), 0);
+    }
+
+    #[test]
+    fn test_div_shl_128_basic() {
+        let env = Env::default();
+        // (1 << 128) / 2 = 2^127
+        assert_eq!(div_shl_128(&env, 1, 2), 1u128 << 127);
+        // (3 << 128) / 4 = 3 * 2^126
+        assert_eq!(div_shl_128(&env, 3, 4), 3u128 << 126);
+    }
+
+    #[test]
+    fn test_div_shl_128_roundtrip() {
+        let env = Env::default();
+        let liquidity = 1_000_000u128;
+        let fee = 3_000u128;
+        let growth = div_shl_128(&env, fee, liquidity);
+        // Converting growth back to an amount loses at most one unit
+        let recovered = mul_shr_128(&env, growth, liquidity);
+        assert!(recovered == fee || recovered == fee - 1);
+    }
+
+    #[test]
+    fn test_div_shl_128_wraps() {
+        let env = Env::default();
+        // a / denominator >= 1 wraps modulo 2^128
+        assert_eq!(div_shl_128(&env, 2, 1), 0);
+        assert_eq!(div_shl_128(&env, 3, 2), 1u128 << 127);
+    }
+
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_div_shl_128_zero_denominator() {
+        let env = Env::default();
+        div_shl_128(&env, 1, 0);
+    }
+
     // === mul_div tests ===
 
     #[test]
@@ -0,0 +1,89 @@
+use soroban_sdk::contracterror;
+
+/// Pool error codes, shared so periphery contracts can decode pool failures
+///
+/// Codes start at 100 so they cannot be mistaken for a token contract error
+/// surfacing through the same call.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PoolError {
+    /// initialize called twice
+    AlreadyInitialized = 100,
+    /// Pool used before initialize
+    NotInitialized = 101,
+    /// token0 is not strictly less than token1
+    InvalidTokenOrder = 102,
+    /// Initial sqrt price outside (MIN_SQRT_RATIO, MAX_SQRT_RATIO)
+    InvalidSqrtPrice = 103,
+    /// Fee at or above 100%
+    InvalidFee = 104,
+    /// Tick spacing is not positive
+    InvalidTickSpacing = 105,
+    /// Swap, mint or burn amount is zero where it must not be, or a mint owes no tokens
+    ZeroAmount = 106,
+    /// Swap price limit is on the wrong side of the price or out of bounds
+    InvalidPriceLimit = 107,
+    /// No liquidity in range or reachable before the price limit
+    NoLiquidity = 108,
+    /// Swap stopped at the tick crossing cap without partial fills allowed
+    TooManyTicksCrossed = 109,
+    /// Exact output swap ran out of liquidity short of the requested output
+    InsufficientLiquidity = 110,
+    /// Exact output swap reached the caller's price limit short of the requested output
+    PriceLimitReached = 111,
+    /// Factory kill switch is on
+    SwapsPaused = 112,
+    /// This pool's swaps are paused
+    PoolPaused = 113,
+    /// Payer's transfer delivered less than the mint amounts owed
+    MintPaymentShort = 114,
+    /// Position has no liquidity to poke
+    PositionHasNoLiquidity = 115,
+    /// tick_lower is not strictly less than tick_upper
+    InvalidTickRange = 116,
+    /// tick_lower below MIN_TICK
+    TickLowerTooLow = 117,
+    /// tick_upper above MAX_TICK
+    TickUpperTooHigh = 118,
+    /// Tick is not a multiple of the tick spacing
+    TickNotOnSpacing = 119,
+    /// Tick gross liquidity would exceed max_liquidity_per_tick
+    LiquidityOverflow = 120,
+    /// Range tick has no liquidity referencing it
+    TickNotInitialized = 121,
+    /// A state-changing call re-entered the pool
+    Reentrancy = 122,
+    /// Payout exceeds the solvency counters
+    ReserveUnderflow = 123,
+    /// No position stored for this owner and range
+    PositionNotFound = 124,
+    /// Oracle cardinality above the supported maximum
+    CardinalityTooLarge = 125,
+    /// Requested observation predates the oldest stored one
+    ObservationTooOld = 126,
+    /// Flash loan amounts are zero or exceed the pool balance
+    InvalidFlashAmount = 127,
+    /// Flash callback repaid less than principal plus fee
+    FlashRepaymentShort = 128,
+    /// Factory has no protocol fee recipient configured
+    FeeRecipientNotSet = 129,
+    /// Pool predates the solvency counters
+    SolvencyCountersNotEnabled = 130,
+    /// Protocol fee denominator is not 0 or in [4, 10]
+    InvalidFeeProtocol = 131,
+    /// Stored layout is newer than this code
+    StorageVersionAhead = 132,
+    /// Swap input transfer delivered less than the amount owed
+    SwapPaymentShort = 133,
+    /// Caller is not an approved operator of the position
+    NotPositionOperator = 134,
+    /// Price checkpoint capacity is 0 or above the supported maximum
+    InvalidCheckpointCapacity = 135,
+    /// Mint adds less than MIN_LIQUIDITY
+    LiquidityTooSmall = 136,
+    /// Recipient is the pool itself, one of its tokens or the factory
+    InvalidRecipient = 137,
+    /// Donation transfer delivered less than the amount donated
+    DonationShort = 138,
+}
@@ -1,13 +1,17 @@
 #![no_std]
 
+mod error;
 mod pool;
 mod position;
 mod tick;
 
+pub use error::*;
 pub use pool::*;
 pub use position::*;
 pub use tick::*;
 
+use soroban_sdk::{Address, Env};
+
 /// Q96 constant (2^96) for fixed-point math
 pub const Q96: u128 = 1 << 96;
 
@@ -41,6 +45,18 @@ pub fn fee_to_tick_spacing(fee: Fee) -> i32 {
     }
 }
 
+/// Whether `recipient` can meaningfully receive an entrypoint's tokens
+/// False if `recipient` is the called contract itself or any of `excluded`
+/// (typically the tokens involved, the factory and the pool). Each contract
+/// raises its own InvalidRecipient error when this is false.
+pub fn is_valid_recipient(env: &Env, recipient: &Address, excluded: &[&Address]) -> bool {
+    *recipient != env.current_contract_address() && !excluded.contains(&recipient)
+}
+
+/// Smallest liquidity a single mint may add
+/// Smaller positions cost two tick entries, a bitmap flip and a position entry for dust
+pub const MIN_LIQUIDITY: u128 = 1000;
+
 /// Calculate maximum liquidity per tick for a given tick spacing
 pub fn max_liquidity_per_tick(tick_spacing: i32) -> u128 {
     let min_tick = (MIN_TICK / tick_spacing) * tick_spacing;
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, Vec};
 
 /// Current pool state - stored in Instance storage for frequent access
 #[contracttype]
@@ -18,6 +18,12 @@ pub struct PoolState {
     pub protocol_fees_0: i128,
     /// Protocol fees accumulated for token1
     pub protocol_fees_1: i128,
+    /// Index of the most recently written observation
+    pub observation_index: u32,
+    /// Number of populated observation slots
+    pub observation_cardinality: u32,
+    /// Number of slots to grow into on the next write
+    pub observation_cardinality_next: u32,
 }
 
 impl PoolState {
@@ -30,10 +36,118 @@ impl PoolState {
             fee_growth_global_1_x128: 0,
             protocol_fees_0: 0,
             protocol_fees_1: 0,
+            observation_index: 0,
+            observation_cardinality: 1,
+            observation_cardinality_next: 1,
         }
     }
 }
 
+/// Hot pool state returned by a single getter (price, liquidity and fee accumulators)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Slot0 {
+    /// Current sqrt(price) as Q64.96
+    pub sqrt_price_x96: u128,
+    /// Current tick index
+    pub tick: i32,
+    /// Total liquidity currently in range
+    pub liquidity: u128,
+    /// Fee growth global for token0 (Q128.128)
+    pub fee_growth_global_0_x128: u128,
+    /// Fee growth global for token1 (Q128.128)
+    pub fee_growth_global_1_x128: u128,
+    /// Protocol fees accumulated for token0
+    pub protocol_fees_0: i128,
+    /// Protocol fees accumulated for token1
+    pub protocol_fees_1: i128,
+}
+
+impl From<&PoolState> for Slot0 {
+    fn from(state: &PoolState) -> Self {
+        Self {
+            sqrt_price_x96: state.sqrt_price_x96,
+            tick: state.tick,
+            liquidity: state.liquidity,
+            fee_growth_global_0_x128: state.fee_growth_global_0_x128,
+            fee_growth_global_1_x128: state.fee_growth_global_1_x128,
+            protocol_fees_0: state.protocol_fees_0,
+            protocol_fees_1: state.protocol_fees_1,
+        }
+    }
+}
+
+/// Outcome of a pool swap, returned to the caller
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapResult {
+    /// Token0 delta (positive = paid in, negative = paid out)
+    pub amount0: i128,
+    /// Token1 delta (positive = paid in, negative = paid out)
+    pub amount1: i128,
+    /// Sqrt price after the swap
+    pub sqrt_price_after_x96: u128,
+    /// Tick after the swap
+    pub tick_after: i32,
+    /// In-range liquidity after the swap
+    pub liquidity_after: u128,
+    /// Total input-token fee paid, protocol share included
+    pub fee_amount_total: u128,
+    /// Initialized ticks crossed by the swap
+    pub ticks_crossed: u32,
+    /// True if the swap stopped at the tick crossing cap with amount left over
+    pub truncated: bool,
+}
+
+/// Outcome of a simulated pool swap: what `swap` with partial fills allowed
+/// would return against the current state
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapSimulation {
+    /// Token0 delta (positive = paid in, negative = paid out)
+    pub amount0: i128,
+    /// Token1 delta (positive = paid in, negative = paid out)
+    pub amount1: i128,
+    /// Sqrt price after the swap
+    pub sqrt_price_after_x96: u128,
+    /// Tick after the swap
+    pub tick_after: i32,
+    /// In-range liquidity after the swap
+    pub liquidity_after: u128,
+    /// Total input-token fee paid, protocol share included
+    pub fee_amount_total: u128,
+    /// Initialized ticks crossed by the swap
+    pub ticks_crossed: u32,
+    /// True if the swap would stop at the tick crossing cap with amount left over
+    pub truncated: bool,
+}
+
+/// Price oracle observation (one ring buffer slot)
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Observation {
+    /// Ledger timestamp of the observation
+    pub block_timestamp: u64,
+    /// Tick accumulator: sum of tick * seconds elapsed
+    pub tick_cumulative: i64,
+    /// Seconds per unit of in-range liquidity accumulator (Q128.128, wrapping)
+    pub seconds_per_liquidity_x128: u128,
+    /// Whether the slot has been written
+    pub initialized: bool,
+}
+
+/// Post-swap price recorded at most once per ledger for cheap price history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceCheckpoint {
+    /// Ledger timestamp of the swap that moved the price
+    pub timestamp: u64,
+    /// Sqrt price after the swap
+    pub sqrt_price_x96: u128,
+    /// Tick after the swap
+    pub tick: i32,
+}
+
 /// Pool configuration - immutable after creation
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -52,6 +166,62 @@ pub struct PoolConfig {
     pub max_liquidity_per_tick: u128,
 }
 
+/// Pool address with its state and config, fetched in one factory call
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolDetails {
+    /// Pool contract address
+    pub pool: Address,
+    /// Current pool state
+    pub state: PoolState,
+    /// Pool configuration
+    pub config: PoolConfig,
+}
+
+/// Market listing entry: pool address with its pair, fee and current price
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolSummary {
+    /// Pool contract address
+    pub pool: Address,
+    /// Token0 address (lower address)
+    pub token0: Address,
+    /// Token1 address (higher address)
+    pub token1: Address,
+    /// Fee tier in hundredths of bps
+    pub fee: u32,
+    /// Current sqrt(price) as Q64.96
+    pub sqrt_price_x96: u128,
+    /// Current tick index
+    pub tick: i32,
+    /// Liquidity currently in range
+    pub liquidity: u128,
+}
+
+/// Creation metadata recorded by the factory for each pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolMeta {
+    /// Account that authorized the pool creation
+    pub creator: Address,
+    /// Ledger sequence at creation
+    pub created_ledger: u32,
+    /// Ledger timestamp at creation
+    pub created_timestamp: u64,
+}
+
+/// One page of pool addresses from the factory registry
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolPage {
+    /// Pool addresses in iteration order
+    pub pools: Vec<Address>,
+    /// Registry index to pass as `start_index` for the next page, None when exhausted
+    pub next_start_index: Option<u32>,
+    /// Total number of pools in the registry
+    pub total: u32,
+}
+
 // ============================================================================
 // SWAP COMPUTATION TYPES - For Formal Verification
 // These types separate pure computation from side effects
@@ -124,6 +294,10 @@ pub struct SwapComputation {
     pub fee_growth_global_x128: u128,
     /// Whether the fee growth is for token0 (true) or token1 (false)
     pub fee_growth_is_token0: bool,
+    /// Total input-token fee taken by this swap, protocol share included
+    pub fee_amount_total: u128,
+    /// Protocol's share of the input-token fee taken by this swap
+    pub protocol_fee: u128,
     /// Ticks that were crossed (need storage updates)
     pub ticks_crossed: u32,
 }
@@ -33,7 +33,7 @@ impl PositionInfo {
 
 /// Position data for Position Manager (NFT metadata)
 #[contracttype]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PositionData {
     /// Pool contract address
     pub pool: Address,
@@ -1,4 +1,4 @@
-use soroban_sdk::contracttype;
+use soroban_sdk::{contracttype, Vec};
 
 /// Information stored for each initialized tick
 #[contracttype]
@@ -12,6 +12,12 @@ pub struct TickInfo {
     pub fee_growth_outside_0_x128: u128,
     /// Fee growth per unit liquidity on token1 side when tick was last crossed
     pub fee_growth_outside_1_x128: u128,
+    /// Tick cumulative on the other side of this tick when it was last crossed
+    pub tick_cumulative_outside: i64,
+    /// Seconds per liquidity on the other side of this tick (Q128.128, wrapping)
+    pub seconds_per_liq_outside_x128: u128,
+    /// Seconds spent on the other side of this tick
+    pub seconds_outside: u64,
     /// True if tick has been initialized
     pub initialized: bool,
 }
@@ -21,3 +27,35 @@ impl TickInfo {
         Self::default()
     }
 }
+
+/// An initialized tick and its liquidity, as listed by the tick lens
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PopulatedTick {
+    /// Tick index
+    pub tick: i32,
+    /// Total liquidity referencing this tick
+    pub liquidity_gross: u128,
+    /// Net liquidity change when tick is crossed (+ when moving right)
+    pub liquidity_net: i128,
+}
+
+/// One page of initialized ticks from a single bitmap word
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PopulatedTickPage {
+    /// Initialized ticks in ascending order
+    pub ticks: Vec<PopulatedTick>,
+    /// Bit index to pass as `start_bit` for the next page, None when the word is exhausted
+    pub next_start_bit: Option<u32>,
+}
+
+/// One page of initialized ticks from a tick range, spanning bitmap words
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PopulatedTickRange {
+    /// Initialized ticks in ascending order
+    pub ticks: Vec<PopulatedTick>,
+    /// Tick to pass as `start_tick` for the next page, None when the range is exhausted
+    pub next_start_tick: Option<i32>,
+}